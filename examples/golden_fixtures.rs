@@ -0,0 +1,22 @@
+//! Writes the golden test vectors as JSON, Python and TypeScript fixtures.
+//!
+//! ```text
+//! cargo run --example golden_fixtures --features fixtures -- <output-dir>
+//! ```
+
+use std::path::PathBuf;
+
+use matter_setup_code::fixtures::{golden_vectors, to_json, to_python, to_typescript};
+
+fn main() -> std::io::Result<()> {
+    let dir = PathBuf::from(std::env::args().nth(1).unwrap_or_else(|| ".".to_string()));
+    std::fs::create_dir_all(&dir)?;
+
+    let vectors = golden_vectors();
+    std::fs::write(dir.join("golden_vectors.json"), to_json(&vectors))?;
+    std::fs::write(dir.join("golden_vectors.py"), to_python(&vectors))?;
+    std::fs::write(dir.join("golden_vectors.ts"), to_typescript(&vectors))?;
+
+    println!("wrote {} vectors to {}", vectors.len(), dir.display());
+    Ok(())
+}
@@ -0,0 +1,29 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use matter_setup_code::tlv::{self, Decoder};
+use matter_setup_code::{SetupPayload, base38};
+
+// Fixed fields of the SDK example code "MT:-24J042C00KA0648G00"; the fuzzer
+// input is appended to them as the QR code's optional data.
+const FIXED_FIELDS: &str = "-24J042C00KA0648G00";
+
+fuzz_target!(|data: &[u8]| {
+    // The TLV decoder on its own: whatever it accepts must survive a
+    // re-encoding unchanged.
+    if let Ok(elements) = tlv::decode(data) {
+        assert_eq!(tlv::decode(&tlv::encode(&elements)).unwrap(), elements);
+    }
+
+    // The skipping path, which walks element types the decoder rejects.
+    let mut decoder = Decoder::new(data);
+    while let Ok(Some(_)) = decoder.skip_element() {}
+
+    // The full QR parser with the input as a TLV tail, both from the packed
+    // bytes and from the base38 text a scanner would read.
+    let mut packed = base38::decode(FIXED_FIELDS).unwrap();
+    packed.extend_from_slice(data);
+    if let Ok(payload) = SetupPayload::from_packed_bytes(&packed) {
+        let _ = payload.to_qr_code_str();
+    }
+    let _ = SetupPayload::parse_str(&format!("MT:{}", base38::encode(&packed)));
+});
@@ -0,0 +1,239 @@
+//! Allocation policies for the per-device discriminator and passcode.
+//!
+//! Manufacturing tools generating many payloads need a source of fresh
+//! discriminators and passcodes. The [`AssignmentPolicy`] trait is that
+//! extension point: the built-in [`Sequential`] and [`Random`] policies cover
+//! the common cases, and factories with reserved
+//! ranges or per-line offsets can implement their own. [`DeviceIdDerived`]
+//! derives the discriminator from a MAC address or serial number, so the
+//! same unit always gets the same discriminator when it is reflashed.
+
+use crate::entropy::{EntropySource, random_discriminator, random_passcode};
+use crate::error::{AssignmentError, Result};
+use crate::payload::{Discriminator, MAX_PASSCODE, Passcode, is_valid_passcode};
+
+/// Largest value of the 12-bit discriminator.
+const MAX_DISCRIMINATOR: u16 = 0xFFF;
+
+/// A source of discriminators and passcodes for newly generated payloads.
+pub trait AssignmentPolicy {
+    /// Returns the long discriminator for the next device.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the policy cannot produce another discriminator.
+    fn next_discriminator(&mut self) -> Result<Discriminator>;
+
+    /// Returns the setup passcode for the next device.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the policy cannot produce another passcode.
+    fn next_passcode(&mut self) -> Result<Passcode>;
+}
+
+impl<P: AssignmentPolicy + ?Sized> AssignmentPolicy for &mut P {
+    fn next_discriminator(&mut self) -> Result<Discriminator> {
+        (**self).next_discriminator()
+    }
+
+    fn next_passcode(&mut self) -> Result<Passcode> {
+        (**self).next_passcode()
+    }
+}
+
+/// Hands out consecutive values, skipping passcodes forbidden by the specification.
+///
+/// Discriminators wrap around after 0xFFF, since they are not required to be
+/// unique. Passcodes must be, so running past the largest valid passcode is
+/// an error rather than a wrap-around.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sequential {
+    discriminator: u16,
+    passcode: u32,
+}
+
+impl Sequential {
+    /// Creates a policy starting at the given discriminator and passcode.
+    ///
+    /// The discriminator is truncated to 12 bits.
+    pub fn new(first_discriminator: u16, first_passcode: u32) -> Self {
+        Sequential {
+            discriminator: first_discriminator & MAX_DISCRIMINATOR,
+            passcode: first_passcode,
+        }
+    }
+}
+
+impl AssignmentPolicy for Sequential {
+    fn next_discriminator(&mut self) -> Result<Discriminator> {
+        let discriminator = self.discriminator;
+        self.discriminator = (self.discriminator + 1) & MAX_DISCRIMINATOR;
+        Ok(Discriminator::from_long_field(discriminator))
+    }
+
+    fn next_passcode(&mut self) -> Result<Passcode> {
+        while self.passcode <= MAX_PASSCODE {
+            let passcode = self.passcode;
+            self.passcode += 1;
+            if is_valid_passcode(passcode) {
+                return Passcode::new(passcode);
+            }
+        }
+        Err(AssignmentError::PasscodesExhausted.into())
+    }
+}
+
+/// Derives a 12-bit discriminator from a device identifier (MAC address, serial number...).
+///
+/// The algorithm is part of the public contract and will not change between
+/// releases: the 32-bit FNV-1a hash of `device_id` is folded onto 12 bits by
+/// XOR-ing its low, middle and high 12-bit windows (`h ^ h >> 12 ^ h >> 24`)
+/// and masking the result with `0xFFF`.
+///
+/// # Example
+///
+/// ```
+/// use matter_setup_code::assignment::derive_discriminator;
+///
+/// let mac = [0x00, 0x1B, 0x63, 0x84, 0x45, 0xE6];
+/// assert_eq!(derive_discriminator(&mac), 0x02F);
+/// ```
+pub fn derive_discriminator(device_id: &[u8]) -> u16 {
+    const FNV_OFFSET_BASIS: u32 = 0x811C_9DC5;
+    const FNV_PRIME: u32 = 0x0100_0193;
+
+    let hash = device_id.iter().fold(FNV_OFFSET_BASIS, |acc, &byte| {
+        (acc ^ byte as u32).wrapping_mul(FNV_PRIME)
+    });
+    ((hash ^ (hash >> 12) ^ (hash >> 24)) as u16) & MAX_DISCRIMINATOR
+}
+
+/// Always assigns the discriminator derived from one device identifier.
+///
+/// The discriminator comes from [`derive_discriminator`], so reflashing the
+/// same unit (e.g. in an RMA or repair flow) reproduces it. Passcodes are
+/// delegated to an inner policy, since they must not be predictable from the
+/// device identifier.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeviceIdDerived<P> {
+    discriminator: Discriminator,
+    passcodes: P,
+}
+
+impl<P: AssignmentPolicy> DeviceIdDerived<P> {
+    /// Creates a policy for the device identified by `device_id`, drawing passcodes from `passcodes`.
+    pub fn new(device_id: &[u8], passcodes: P) -> Self {
+        DeviceIdDerived {
+            discriminator: Discriminator::from_long_field(derive_discriminator(device_id)),
+            passcodes,
+        }
+    }
+}
+
+impl<P: AssignmentPolicy> AssignmentPolicy for DeviceIdDerived<P> {
+    fn next_discriminator(&mut self) -> Result<Discriminator> {
+        Ok(self.discriminator)
+    }
+
+    fn next_passcode(&mut self) -> Result<Passcode> {
+        self.passcodes.next_passcode()
+    }
+}
+
+/// Draws uniformly random discriminators and valid passcodes from an [`EntropySource`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Random<E> {
+    source: E,
+}
+
+impl<E: EntropySource> Random<E> {
+    /// Creates a policy drawing from `source`.
+    pub fn new(source: E) -> Self {
+        Random { source }
+    }
+
+    /// Consumes the policy, returning the underlying entropy source.
+    pub fn into_inner(self) -> E {
+        self.source
+    }
+}
+
+impl<E: EntropySource> AssignmentPolicy for Random<E> {
+    fn next_discriminator(&mut self) -> Result<Discriminator> {
+        random_discriminator(&mut self.source).map(Discriminator::from_long_field)
+    }
+
+    fn next_passcode(&mut self) -> Result<Passcode> {
+        Passcode::new(random_passcode(&mut self.source)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MatterPayloadError;
+    use crate::entropy::tests::XorShift;
+
+    #[test]
+    fn test_sequential_skips_forbidden_passcodes() {
+        let mut policy = Sequential::new(0xFFE, 11111110);
+        assert_eq!(
+            policy.next_discriminator().unwrap(),
+            Discriminator::Long(0xFFE)
+        );
+        assert_eq!(
+            policy.next_discriminator().unwrap(),
+            Discriminator::Long(0xFFF)
+        );
+        assert_eq!(policy.next_discriminator().unwrap(), Discriminator::Long(0));
+
+        assert_eq!(policy.next_passcode().unwrap().get(), 11111110);
+        assert_eq!(policy.next_passcode().unwrap().get(), 11111112);
+    }
+
+    #[test]
+    fn test_sequential_exhaustion() {
+        let mut policy = Sequential::new(0, 0);
+        assert_eq!(policy.next_passcode().unwrap().get(), 1);
+
+        let mut policy = Sequential::new(0, MAX_PASSCODE);
+        assert_eq!(policy.next_passcode().unwrap().get(), MAX_PASSCODE);
+        assert_eq!(
+            policy.next_passcode().unwrap_err(),
+            MatterPayloadError::Assignment(AssignmentError::PasscodesExhausted)
+        );
+    }
+
+    #[test]
+    fn test_device_id_derived_is_stable() {
+        // Reference values are pinned: changing them would break already-provisioned fleets.
+        assert_eq!(
+            derive_discriminator(&[0x00, 0x1B, 0x63, 0x84, 0x45, 0xE6]),
+            0x02F
+        );
+        assert_eq!(derive_discriminator(b"SN-000123"), 0xA88);
+        assert_eq!(derive_discriminator(b""), 0xC8D);
+
+        let mut policy = DeviceIdDerived::new(b"SN-000123", Sequential::new(0, 20202021));
+        assert_eq!(
+            policy.next_discriminator().unwrap(),
+            Discriminator::Long(0xA88)
+        );
+        assert_eq!(
+            policy.next_discriminator().unwrap(),
+            Discriminator::Long(0xA88)
+        );
+        assert_eq!(policy.next_passcode().unwrap().get(), 20202021);
+        assert_eq!(policy.next_passcode().unwrap().get(), 20202022);
+    }
+
+    #[test]
+    fn test_random_stays_in_range() {
+        let mut policy = Random::new(XorShift(0x9E3779B97F4A7C15));
+        for _ in 0..1000 {
+            assert!(policy.next_discriminator().unwrap().checked().is_ok());
+            assert!(policy.next_passcode().unwrap().is_valid());
+        }
+    }
+}
@@ -1,18 +1,21 @@
 //! A Rust implementation of the Matter specification's Base38 encoding scheme.
 
-use crate::error::{Base38DecodeError, Result};
+use crate::basen::Codec;
+use crate::error::Result;
 
 const CODES: [char; 38] = [
     '0', '1', '2', '3', '4', '5', '6', '7', '8', '9', 'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I',
     'J', 'K', 'L', 'M', 'N', 'O', 'P', 'Q', 'R', 'S', 'T', 'U', 'V', 'W', 'X', 'Y', 'Z', '-', '.',
 ];
-const RADIX: u64 = CODES.len() as u64;
 
 // The Matter specification defines that byte chunks of 1, 2, or 3 bytes
 // are encoded into Base38 character chunks of 2, 4, or 5 characters, respectively.
 const BASE38_CHARS_NEEDED_IN_CHUNK: [usize; 3] = [2, 4, 5];
+#[cfg(kani)]
 const MAX_BYTES_IN_CHUNK: usize = 3;
-const MAX_ENCODED_CHARS_IN_CHUNK: usize = 5;
+
+/// The Matter Base38 profile of the generic [`Codec`].
+pub const BASE38: Codec = Codec::new(&CODES, &BASE38_CHARS_NEEDED_IN_CHUNK);
 
 /// Encodes a slice of bytes into a Base38 string.
 ///
@@ -27,27 +30,23 @@ const MAX_ENCODED_CHARS_IN_CHUNK: usize = 5;
 ///
 /// let data = vec![0x12, 0x34, 0x56, 0x78];
 /// let encoded = encode(&data);
-/// assert_eq!(encoded, "6593L1G");
+/// assert_eq!(encoded, "4D-Q263");
 /// ```
 pub fn encode(bytes: &[u8]) -> String {
-    let mut qrcode = String::new();
-    for chunk in bytes.chunks(MAX_BYTES_IN_CHUNK) {
-        // Pack the byte chunk into a u64 value in little-endian order.
-        let mut value = chunk
-            .iter()
-            .enumerate()
-            .fold(0u64, |acc, (i, &byte)| acc | ((byte as u64) << (i * 8)));
-
-        let chars_needed = BASE38_CHARS_NEEDED_IN_CHUNK[chunk.len() - 1];
-
-        // Perform the base conversion from base-256 (bytes) to base-38.
-        for _ in 0..chars_needed {
-            let remainder = (value % RADIX) as usize;
-            qrcode.push(CODES[remainder]);
-            value /= RADIX;
-        }
-    }
-    qrcode
+    BASE38.encode(bytes)
+}
+
+/// The number of Base38 characters [`encode`] produces for `len` bytes.
+///
+/// # Example
+///
+/// ```
+/// use matter_setup_code::base38::{encode, encoded_len};
+///
+/// assert_eq!(encoded_len(4), encode(&[0x12, 0x34, 0x56, 0x78]).len());
+/// ```
+pub fn encoded_len(len: usize) -> usize {
+    BASE38.encoded_len(len)
 }
 
 /// Decodes a Base38 string into a vector of bytes.
@@ -64,57 +63,14 @@ pub fn encode(bytes: &[u8]) -> String {
 /// # Example
 ///
 /// ```
-/// use matter_setup_code::base38::encode;
+/// use matter_setup_code::base38::decode;
 ///
-/// let encoded = "6593L1G";
+/// let encoded = "4D-Q263";
 /// let decoded = decode(encoded).unwrap();
 /// assert_eq!(decoded, vec![0x12, 0x34, 0x56, 0x78]);
 /// ```
 pub fn decode(s: &str) -> Result<Vec<u8>> {
-    let mut decoded_bytes = Vec::new();
-    let chars: Vec<char> = s.chars().collect();
-
-    for chunk in chars.chunks(MAX_ENCODED_CHARS_IN_CHUNK) {
-        // Convert the Base38 character chunk back into an integer.
-        // `try_fold` is used to accumulate the value while allowing an early
-        // exit with an error if an invalid character is encountered.
-        let value = chunk.iter().rev().try_fold(0u64, |acc, &c| {
-            CODES
-                .iter()
-                .position(|&code| code == c)
-                .map(|val| acc * RADIX + val as u64)
-                .ok_or(Base38DecodeError::InvalidCharacter(c))
-        })?;
-
-        let bytes_in_chunk = match chunk.len() {
-            2 => 1,
-            4 => 2,
-            5 => 3,
-            len => return Err(Base38DecodeError::InvalidChunkLength(len).into()),
-        };
-
-        // This validation is critical. A malformed input could produce a decoded
-        // value that is too large to fit into the expected number of bytes.
-        // For example, 5 characters could decode to a value greater than 2^24 - 1.
-        let max_value = 1u64 << (8 * bytes_in_chunk);
-        if value >= max_value {
-            return Err(Base38DecodeError::ValueOutOfRange {
-                value,
-                digits: chunk.len(),
-                expected_bytes: bytes_in_chunk,
-            }
-            .into());
-        }
-
-        // Unpack the integer back into little-endian bytes.
-        let mut temp_value = value;
-        for _ in 0..bytes_in_chunk {
-            decoded_bytes.push((temp_value & 0xFF) as u8);
-            temp_value >>= 8;
-        }
-    }
-
-    Ok(decoded_bytes)
+    BASE38.decode(s)
 }
 
 #[cfg(test)]
@@ -131,6 +87,14 @@ mod tests {
         assert_eq!(original_data, decoded);
     }
 
+    #[test]
+    fn test_encoded_len() {
+        for len in 0..10 {
+            assert_eq!(encoded_len(len), encode(&vec![0xFF; len]).len());
+        }
+        assert_eq!(encoded_len(11), 19);
+    }
+
     #[test]
     fn test_chunk_boundaries() {
         let inputs: Vec<Vec<u8>> = vec![
@@ -195,4 +159,23 @@ mod tests {
             assert_eq!(case, decoded, "Edge case failed");
         }
     }
-}
\ No newline at end of file
+}
+
+// Proof harnesses for the Kani model checker, run with `cargo kani`.
+#[cfg(kani)]
+mod verification {
+    use super::*;
+
+    /// Every 1, 2 or 3 byte chunk survives an encode/decode round trip.
+    #[kani::proof]
+    #[kani::unwind(7)]
+    fn check_chunk_roundtrip() {
+        let bytes: [u8; MAX_BYTES_IN_CHUNK] = kani::any();
+        let len: usize = kani::any_where(|&len| (1..=MAX_BYTES_IN_CHUNK).contains(&len));
+        let chunk = &bytes[..len];
+
+        let encoded = encode(chunk);
+        assert_eq!(encoded.len(), BASE38_CHARS_NEEDED_IN_CHUNK[len - 1]);
+        assert_eq!(decode(&encoded).unwrap(), chunk);
+    }
+}
@@ -0,0 +1,216 @@
+//! A configurable chunked base-N codec, the machinery behind [`crate::base38`].
+//!
+//! Input bytes are split into chunks of up to `n` bytes, each read as a
+//! little-endian integer and written as a fixed number of digits, least
+//! significant first. A [`Codec`] is defined by its alphabet and by the
+//! number of digits used for each chunk size, so label schemes using another
+//! alphabet or radix can reuse the same well-tested conversion.
+
+use crate::error::{Base38DecodeError, Result};
+
+/// A chunked base-N codec: an alphabet and a chunk size table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Codec {
+    alphabet: &'static [char],
+    chunk_digits: &'static [usize],
+}
+
+impl Codec {
+    /// Largest supported chunk, so every chunk value fits in a `u64`.
+    pub const MAX_CHUNK_BYTES: usize = 7;
+
+    /// Creates a codec.
+    ///
+    /// `chunk_digits[i]` is the number of digits encoding a chunk of `i + 1`
+    /// bytes; its length is the largest chunk size. The radix is the length of
+    /// `alphabet`.
+    ///
+    /// # Panics
+    ///
+    /// Panics (at compile time when used in a `const`) if the alphabet has
+    /// fewer than 2 characters, the table is empty or longer than
+    /// [`Codec::MAX_CHUNK_BYTES`], a chunk size has too few digits to hold
+    /// every value of its bytes, or two chunk sizes use the same number of
+    /// digits, which would make decoding ambiguous.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use matter_setup_code::basen::Codec;
+    ///
+    /// // Crockford-style base32 with 2-byte chunks.
+    /// const BASE32: Codec = Codec::new(
+    ///     &[
+    ///         '0', '1', '2', '3', '4', '5', '6', '7', '8', '9', 'A', 'B', 'C', 'D', 'E', 'F',
+    ///         'G', 'H', 'J', 'K', 'M', 'N', 'P', 'Q', 'R', 'S', 'T', 'V', 'W', 'X', 'Y', 'Z',
+    ///     ],
+    ///     &[2, 4],
+    /// );
+    /// let encoded = BASE32.encode(&[0xDE, 0xAD, 0xBE]);
+    /// assert_eq!(BASE32.decode(&encoded).unwrap(), vec![0xDE, 0xAD, 0xBE]);
+    /// ```
+    pub const fn new(alphabet: &'static [char], chunk_digits: &'static [usize]) -> Self {
+        assert!(alphabet.len() >= 2, "alphabet needs at least 2 characters");
+        assert!(
+            !chunk_digits.is_empty() && chunk_digits.len() <= Self::MAX_CHUNK_BYTES,
+            "chunk table must cover 1 to 7 bytes"
+        );
+        let radix = alphabet.len() as u128;
+        let mut i = 0;
+        while i < chunk_digits.len() {
+            // radix^digits must reach 256^bytes.
+            let needed = 1u128 << (8 * (i + 1));
+            let mut capacity = 1u128;
+            let mut d = 0;
+            while d < chunk_digits[i] {
+                capacity *= radix;
+                assert!(capacity <= 1 << 64, "chunk has too many digits for a u64");
+                d += 1;
+            }
+            assert!(capacity >= needed, "chunk has too few digits");
+            let mut j = 0;
+            while j < i {
+                assert!(
+                    chunk_digits[j] != chunk_digits[i],
+                    "chunk sizes must use distinct digit counts"
+                );
+                j += 1;
+            }
+            i += 1;
+        }
+        Codec {
+            alphabet,
+            chunk_digits,
+        }
+    }
+
+    /// The number of symbols in the alphabet.
+    pub const fn radix(&self) -> usize {
+        self.alphabet.len()
+    }
+
+    /// The largest chunk, in bytes.
+    pub const fn max_chunk_bytes(&self) -> usize {
+        self.chunk_digits.len()
+    }
+
+    /// The number of digits encoding a chunk of `bytes` bytes, if that size is supported.
+    pub fn digits_for(&self, bytes: usize) -> Option<usize> {
+        bytes
+            .checked_sub(1)
+            .and_then(|i| self.chunk_digits.get(i))
+            .copied()
+    }
+
+    /// The number of digits [`Codec::encode`] produces for `len` bytes.
+    pub fn encoded_len(&self, len: usize) -> usize {
+        let max = self.max_chunk_bytes();
+        let full = len / max * self.chunk_digits[max - 1];
+        full + self.digits_for(len % max).unwrap_or(0)
+    }
+
+    /// Encodes a slice of bytes into a string.
+    pub fn encode(&self, bytes: &[u8]) -> String {
+        let radix = self.radix() as u64;
+        let mut encoded = String::new();
+        for chunk in bytes.chunks(self.max_chunk_bytes()) {
+            // Pack the byte chunk into a u64 value in little-endian order.
+            let mut value = chunk
+                .iter()
+                .enumerate()
+                .fold(0u64, |acc, (i, &byte)| acc | ((byte as u64) << (i * 8)));
+
+            // Perform the base conversion from base-256 (bytes) to base-N.
+            for _ in 0..self.chunk_digits[chunk.len() - 1] {
+                encoded.push(self.alphabet[(value % radix) as usize]);
+                value /= radix;
+            }
+        }
+        encoded
+    }
+
+    /// Decodes a string into a vector of bytes.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Base38DecodeError::InvalidCharacter` for a character outside
+    /// the alphabet, `Base38DecodeError::InvalidChunkLength` if the final
+    /// chunk has a digit count no chunk size uses, or
+    /// `Base38DecodeError::ValueOutOfRange` if a chunk decodes to a value too
+    /// large for its byte count.
+    pub fn decode(&self, s: &str) -> Result<Vec<u8>> {
+        let radix = self.radix() as u64;
+        let max_digits = self.chunk_digits[self.max_chunk_bytes() - 1];
+        let mut decoded_bytes = Vec::new();
+        let chars: Vec<char> = s.chars().collect();
+
+        for chunk in chars.chunks(max_digits) {
+            // `try_fold` accumulates the value while allowing an early exit
+            // with an error if an invalid character is encountered.
+            let value = chunk.iter().rev().try_fold(0u64, |acc, &c| {
+                self.alphabet
+                    .iter()
+                    .position(|&code| code == c)
+                    .map(|val| acc * radix + val as u64)
+                    .ok_or(Base38DecodeError::InvalidCharacter(c))
+            })?;
+
+            let bytes_in_chunk = self
+                .chunk_digits
+                .iter()
+                .position(|&digits| digits == chunk.len())
+                .map(|i| i + 1)
+                .ok_or(Base38DecodeError::InvalidChunkLength(chunk.len()))?;
+
+            // A malformed input could produce a decoded value that is too
+            // large to fit into the expected number of bytes.
+            if value >= 1u64 << (8 * bytes_in_chunk) {
+                return Err(Base38DecodeError::ValueOutOfRange {
+                    value,
+                    digits: chunk.len(),
+                    expected_bytes: bytes_in_chunk,
+                }
+                .into());
+            }
+
+            // Unpack the integer back into little-endian bytes.
+            decoded_bytes.extend_from_slice(&value.to_le_bytes()[..bytes_in_chunk]);
+        }
+
+        Ok(decoded_bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::MatterPayloadError;
+
+    const HEX: Codec = Codec::new(
+        &[
+            '0', '1', '2', '3', '4', '5', '6', '7', '8', '9', 'a', 'b', 'c', 'd', 'e', 'f',
+        ],
+        &[2],
+    );
+
+    #[test]
+    fn test_custom_codec_roundtrip() {
+        // Single-byte chunks, least significant nibble first.
+        assert_eq!(HEX.encode(&[0x12, 0xAB]), "21ba");
+        assert_eq!(HEX.decode("21ba").unwrap(), vec![0x12, 0xAB]);
+        assert_eq!(
+            HEX.decode("2"),
+            Err(MatterPayloadError::Base38(
+                Base38DecodeError::InvalidChunkLength(1)
+            ))
+        );
+        assert_eq!(HEX.digits_for(1), Some(2));
+        assert_eq!(HEX.digits_for(2), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "chunk has too few digits")]
+    fn test_rejects_undersized_chunks() {
+        Codec::new(&['0', '1'], &[7]);
+    }
+}
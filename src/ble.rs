@@ -0,0 +1,203 @@
+//! Matching BLE advertisements against a setup payload.
+//!
+//! A commissionable device advertises the Matter service (UUID `0xFFF6`)
+//! with 8 bytes of service data carrying its discriminator, VID and PID.
+//! [`CommissionableAdvertisement`] decodes that service data, and
+//! [`scan_matching`] scans with btleplug and yields the devices whose
+//! advertisement matches a [`SetupPayload`], answering "is the device with
+//! this code actually advertising?" in the field.
+
+use btleplug::api::bleuuid::uuid_from_u16;
+use btleplug::api::{Central, CentralEvent, Manager as _, ScanFilter};
+use btleplug::platform::{Manager, PeripheralId};
+use futures::stream::{Stream, StreamExt};
+
+use crate::error::{BleError, Result};
+use crate::payload::SetupPayload;
+
+/// 16-bit UUID of the Matter BLE service.
+pub const MATTER_SERVICE_UUID16: u16 = 0xFFF6;
+
+/// Opcode of a commissionable device advertisement.
+const OPCODE_COMMISSIONABLE: u8 = 0x00;
+
+/// Length of the Matter service data in a commissionable advertisement.
+const SERVICE_DATA_LEN: usize = 8;
+
+/// The Matter service data of a commissionable device's BLE advertisement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CommissionableAdvertisement {
+    /// Long discriminator (12 bits)
+    pub discriminator: u16,
+    /// Advertisement version (4 bits)
+    pub version: u8,
+    /// Vendor ID
+    pub vid: u16,
+    /// Product ID
+    pub pid: u16,
+    /// Set if additional commissioning data is readable over GATT.
+    pub additional_data: bool,
+}
+
+impl CommissionableAdvertisement {
+    /// Decodes the Matter service data of a BLE advertisement.
+    ///
+    /// Trailing bytes added by newer revisions are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns `BleError::ServiceDataLength` if `data` is shorter than 8 bytes,
+    /// or `BleError::UnsupportedOpcode` if it is not a commissionable
+    /// advertisement.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use matter_setup_code::ble::CommissionableAdvertisement;
+    ///
+    /// let adv = CommissionableAdvertisement::from_service_data(&[
+    ///     0x00, 0x00, 0x0F, 0xF1, 0xFF, 0x01, 0x80, 0x00,
+    /// ])
+    /// .unwrap();
+    /// assert_eq!(adv.discriminator, 3840);
+    /// assert_eq!(adv.vid, 0xFFF1);
+    /// ```
+    pub fn from_service_data(data: &[u8]) -> Result<Self> {
+        if data.len() < SERVICE_DATA_LEN {
+            return Err(BleError::ServiceDataLength(data.len()).into());
+        }
+        if data[0] != OPCODE_COMMISSIONABLE {
+            return Err(BleError::UnsupportedOpcode(data[0]).into());
+        }
+        let discriminator_and_version = u16::from_le_bytes([data[1], data[2]]);
+        Ok(CommissionableAdvertisement {
+            discriminator: discriminator_and_version & 0x0FFF,
+            version: (discriminator_and_version >> 12) as u8,
+            vid: u16::from_le_bytes([data[3], data[4]]),
+            pid: u16::from_le_bytes([data[5], data[6]]),
+            additional_data: data[7] & 0x01 != 0,
+        })
+    }
+
+    /// Returns `true` if this advertisement may come from the device `payload` describes.
+    ///
+    /// The discriminator must match; VID and PID are only compared when the
+    /// payload carries them.
+    pub fn matches(&self, payload: &SetupPayload) -> bool {
+        payload.matches_discriminator(self.discriminator)
+            && payload.vid.is_none_or(|vid| vid.get() == self.vid)
+            && payload.pid.is_none_or(|pid| pid.get() == self.pid)
+    }
+}
+
+/// A scanned device whose advertisement matches the payload, yielded by [`scan_matching`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BleMatch {
+    /// The platform identifier of the advertising peripheral.
+    pub id: PeripheralId,
+    /// The decoded Matter service data.
+    pub advertisement: CommissionableAdvertisement,
+}
+
+/// Scans BLE advertisements on the first adapter and yields the devices matching `payload`.
+///
+/// The scan runs until the returned stream is dropped; wrap it in your
+/// runtime's timeout to bound it. A device is yielded every time it
+/// advertises, so callers wanting unique devices should deduplicate on
+/// [`BleMatch::id`].
+///
+/// # Errors
+///
+/// Returns `BleError::NoAdapter` if the host has no Bluetooth adapter, or
+/// `BleError::Scan` if the platform BLE stack refuses to start scanning.
+pub async fn scan_matching(payload: &SetupPayload) -> Result<impl Stream<Item = BleMatch>> {
+    let manager = Manager::new().await.map_err(scan_error)?;
+    let adapter = manager
+        .adapters()
+        .await
+        .map_err(scan_error)?
+        .into_iter()
+        .next()
+        .ok_or(BleError::NoAdapter)?;
+    let events = adapter.events().await.map_err(scan_error)?;
+    adapter
+        .start_scan(ScanFilter {
+            services: vec![uuid_from_u16(MATTER_SERVICE_UUID16)],
+        })
+        .await
+        .map_err(scan_error)?;
+
+    let payload = payload.clone();
+    Ok(events.filter_map(move |event| {
+        // The adapter stops scanning when dropped, so the stream owns it.
+        let _adapter = &adapter;
+        let found = match event {
+            CentralEvent::ServiceDataAdvertisement { id, service_data } => service_data
+                .get(&uuid_from_u16(MATTER_SERVICE_UUID16))
+                .and_then(|data| CommissionableAdvertisement::from_service_data(data).ok())
+                .filter(|advertisement| advertisement.matches(&payload))
+                .map(|advertisement| BleMatch { id, advertisement }),
+            _ => None,
+        };
+        async move { found }
+    }))
+}
+
+fn scan_error(e: btleplug::Error) -> BleError {
+    BleError::Scan(e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MatterPayloadError;
+    use crate::payload::Passcode;
+
+    // Discriminator 3840, version 0, VID 0xFFF1, PID 0x8001, no additional data.
+    const SERVICE_DATA: [u8; 8] = [0x00, 0x00, 0x0F, 0xF1, 0xFF, 0x01, 0x80, 0x00];
+
+    #[test]
+    fn test_decode_service_data() {
+        let adv = CommissionableAdvertisement::from_service_data(&SERVICE_DATA).unwrap();
+        assert_eq!(
+            adv,
+            CommissionableAdvertisement {
+                discriminator: 3840,
+                version: 0,
+                vid: 0xFFF1,
+                pid: 0x8001,
+                additional_data: false,
+            }
+        );
+
+        let err = CommissionableAdvertisement::from_service_data(&SERVICE_DATA[..7]).unwrap_err();
+        assert_eq!(err, MatterPayloadError::Ble(BleError::ServiceDataLength(7)));
+
+        let mut data = SERVICE_DATA;
+        data[0] = 0x01;
+        let err = CommissionableAdvertisement::from_service_data(&data).unwrap_err();
+        assert_eq!(err, MatterPayloadError::Ble(BleError::UnsupportedOpcode(1)));
+    }
+
+    #[test]
+    fn test_advertisement_matches_payload() {
+        let adv = CommissionableAdvertisement::from_service_data(&SERVICE_DATA).unwrap();
+        let qr = SetupPayload::parse_str("MT:-24J042C00KA0648G00").unwrap();
+        assert!(adv.matches(&qr));
+
+        let other_vendor = SetupPayload::new(
+            3840,
+            Passcode::new(20202021).unwrap(),
+            Some(2),
+            None,
+            Some(0xFFF2),
+            Some(0x8001),
+        );
+        assert!(!adv.matches(&other_vendor));
+
+        // A manual code without VID/PID matches on the upper discriminator bits only.
+        let manual = SetupPayload::parse_str("34970112332").unwrap();
+        assert_eq!(manual.discriminator.long(), None);
+        assert!(adv.matches(&manual));
+    }
+}
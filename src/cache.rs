@@ -0,0 +1,200 @@
+//! A thread-safe LRU cache of parse results.
+//!
+//! Cloud validators see the same few thousand codes over and over (client
+//! retries, duplicated scans). [`ParseCache`] remembers the outcome of the
+//! most recently parsed strings, errors included, so repeated inputs skip
+//! decoding. A [`CacheMetrics`] hook reports hits, misses and evictions to
+//! whatever metrics system the service uses.
+
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, PoisonError};
+
+use lru::LruCache;
+
+use crate::error::Result;
+use crate::payload::SetupPayload;
+
+/// Receives cache events; every method defaults to doing nothing.
+pub trait CacheMetrics: Send + Sync {
+    /// The input was found in the cache.
+    fn hit(&self) {}
+    /// The input was parsed and added to the cache.
+    fn miss(&self) {}
+    /// The least recently used entry was dropped to make room.
+    fn eviction(&self) {}
+}
+
+/// Counters implementing [`CacheMetrics`], for services without a metrics system.
+#[derive(Debug, Default)]
+pub struct CacheStats {
+    hits: AtomicU64,
+    misses: AtomicU64,
+    evictions: AtomicU64,
+}
+
+impl CacheStats {
+    /// Number of lookups answered from the cache.
+    pub fn hits(&self) -> u64 {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    /// Number of lookups that had to parse.
+    pub fn misses(&self) -> u64 {
+        self.misses.load(Ordering::Relaxed)
+    }
+
+    /// Number of entries dropped to make room.
+    pub fn evictions(&self) -> u64 {
+        self.evictions.load(Ordering::Relaxed)
+    }
+}
+
+impl CacheMetrics for CacheStats {
+    fn hit(&self) {
+        self.hits.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn miss(&self) {
+        self.misses.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn eviction(&self) {
+        self.evictions.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+impl<M: CacheMetrics + ?Sized> CacheMetrics for std::sync::Arc<M> {
+    fn hit(&self) {
+        (**self).hit();
+    }
+
+    fn miss(&self) {
+        (**self).miss();
+    }
+
+    fn eviction(&self) {
+        (**self).eviction();
+    }
+}
+
+/// An LRU cache of [`SetupPayload::parse_str`] results, keyed by input string.
+pub struct ParseCache {
+    entries: Mutex<LruCache<String, Result<SetupPayload>>>,
+    metrics: Option<Box<dyn CacheMetrics>>,
+}
+
+impl ParseCache {
+    /// Creates a cache holding up to `capacity` parse results.
+    pub fn new(capacity: NonZeroUsize) -> Self {
+        ParseCache {
+            entries: Mutex::new(LruCache::new(capacity)),
+            metrics: None,
+        }
+    }
+
+    /// Reports cache events to `metrics`.
+    pub fn with_metrics(mut self, metrics: impl CacheMetrics + 'static) -> Self {
+        self.metrics = Some(Box::new(metrics));
+        self
+    }
+
+    /// Parses `input` like [`SetupPayload::parse_str`], reusing a cached result if present.
+    ///
+    /// The lock is not held while parsing, so two threads missing on the same
+    /// input may both parse it; the results are identical.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::num::NonZeroUsize;
+    /// use std::sync::Arc;
+    /// use matter_setup_code::cache::{CacheStats, ParseCache};
+    ///
+    /// let stats = Arc::new(CacheStats::default());
+    /// let cache = ParseCache::new(NonZeroUsize::new(1024).unwrap()).with_metrics(stats.clone());
+    /// for _ in 0..3 {
+    ///     cache.parse("MT:-24J042C00KA0648G00").unwrap();
+    /// }
+    /// assert_eq!((stats.hits(), stats.misses()), (2, 1));
+    /// ```
+    pub fn parse(&self, input: &str) -> Result<SetupPayload> {
+        if let Some(cached) = self.lock().get(input) {
+            let cached = cached.clone();
+            self.report(|m| m.hit());
+            return cached;
+        }
+
+        let result = SetupPayload::parse_str(input);
+        self.report(|m| m.miss());
+        let evicted = self.lock().push(input.to_string(), result.clone());
+        if evicted.is_some_and(|(key, _)| key != input) {
+            self.report(|m| m.eviction());
+        }
+        result
+    }
+
+    /// Number of cached results.
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Returns `true` if nothing is cached.
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    /// Drops every cached result.
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, LruCache<String, Result<SetupPayload>>> {
+        // A panic while holding the lock cannot leave the cache inconsistent.
+        self.entries.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn report(&self, event: impl FnOnce(&dyn CacheMetrics)) {
+        if let Some(metrics) = &self.metrics {
+            event(metrics.as_ref());
+        }
+    }
+}
+
+impl std::fmt::Debug for ParseCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ParseCache")
+            .field("len", &self.len())
+            .field("capacity", &self.lock().cap())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_cache_hits_and_evictions() {
+        let stats = Arc::new(CacheStats::default());
+        let cache = ParseCache::new(NonZeroUsize::new(2).unwrap()).with_metrics(stats.clone());
+
+        let qr = cache.parse("MT:-24J042C00KA0648G00").unwrap();
+        assert_eq!(cache.parse("MT:-24J042C00KA0648G00").unwrap(), qr);
+        // Errors are cached too.
+        assert!(cache.parse("20000000031").is_err());
+        assert!(cache.parse("20000000031").is_err());
+        assert_eq!((stats.hits(), stats.misses(), stats.evictions()), (2, 2, 0));
+
+        // A third input evicts the least recently used one.
+        cache.parse("34970112332").unwrap();
+        assert_eq!(stats.evictions(), 1);
+        assert_eq!(cache.len(), 2);
+        cache.parse("MT:-24J042C00KA0648G00").unwrap();
+        assert_eq!(stats.misses(), 4);
+
+        cache.clear();
+        assert!(cache.is_empty());
+    }
+}
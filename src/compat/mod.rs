@@ -0,0 +1,6 @@
+//! Compatibility shims mirroring other setup payload implementations.
+//!
+//! These modules trade idiomatic Rust for a mechanical port: method names
+//! and behaviors follow the implementation being replaced, quirks included.
+
+pub mod python;
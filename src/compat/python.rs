@@ -0,0 +1,164 @@
+//! The API of the CHIP SDK's reference Python `SetupPayload`.
+//!
+//! Teams porting tooling built on `setup_payload.py` can bring the
+//! [`PythonSetupPayload`] trait into scope and keep their call sites
+//! (`SetupPayload.parse(code).generate_qrcode()`) while they migrate to the
+//! idiomatic API. Differences from the Python behavior are listed on each
+//! method; the reference quirks kept on purpose are:
+//!
+//! - The constructor defaults to on-network discovery (4), the standard
+//!   flow and VID/PID 0 ([`PythonSetupPayload::with_python_defaults`]).
+//! - A discriminator of 0 and a discovery bitmask of 0 are falsy in Python,
+//!   so `p_print` leaves them out.
+//! - Payloads parsed from a manual code have no long discriminator or
+//!   discovery capabilities, so `generate_qrcode` fails on them.
+
+use std::fmt::Write;
+
+use crate::error::{PayloadError, Result};
+use crate::payload::{CommissioningFlow, Passcode, SetupPayload};
+
+/// Method names of the reference Python `SetupPayload`, implemented for [`SetupPayload`].
+pub trait PythonSetupPayload: Sized {
+    /// `SetupPayload(discriminator, pincode)` with the Python constructor defaults:
+    /// discovery 4 (on-network), standard flow, VID and PID 0.
+    fn with_python_defaults(discriminator: u16, pincode: Passcode) -> Self;
+
+    /// `SetupPayload.parse(payload)`: a QR code if it starts with `MT:`, a manual code otherwise.
+    fn parse(payload: &str) -> Result<Self>;
+
+    /// `SetupPayload.parse_qrcode(payload)`.
+    ///
+    /// Python fails with an exception from its base38 decoder on a missing
+    /// prefix; this returns `PayloadError::InvalidQrCodePrefix`.
+    fn parse_qrcode(payload: &str) -> Result<Self>;
+
+    /// `SetupPayload.parse_manualcode(payload)`.
+    fn parse_manualcode(payload: &str) -> Result<Self>;
+
+    /// `payload.generate_qrcode()`.
+    fn generate_qrcode(&self) -> Result<String>;
+
+    /// `payload.generate_manualcode()`.
+    ///
+    /// 21 digits with the VID and PID for flows other than standard, as by
+    /// [`SetupPayload::to_manual_code_str`].
+    fn generate_manualcode(&self) -> Result<String>;
+
+    /// `payload.p_print()`, returned instead of printed.
+    fn p_print(&self) -> String;
+}
+
+impl PythonSetupPayload for SetupPayload {
+    fn with_python_defaults(discriminator: u16, pincode: Passcode) -> Self {
+        SetupPayload::new(
+            discriminator,
+            pincode,
+            Some(4),
+            Some(CommissioningFlow::Standard),
+            Some(0),
+            Some(0),
+        )
+    }
+
+    fn parse(payload: &str) -> Result<Self> {
+        SetupPayload::parse_str(payload)
+    }
+
+    fn parse_qrcode(payload: &str) -> Result<Self> {
+        if !payload.starts_with("MT:") {
+            return Err(PayloadError::InvalidQrCodePrefix.into());
+        }
+        SetupPayload::parse_str(payload)
+    }
+
+    fn parse_manualcode(payload: &str) -> Result<Self> {
+        if payload.starts_with("MT:") {
+            return Err(PayloadError::InvalidManualCodeLength(payload.len()).into());
+        }
+        SetupPayload::parse_str(payload)
+    }
+
+    fn generate_qrcode(&self) -> Result<String> {
+        self.to_qr_code_str()
+    }
+
+    fn generate_manualcode(&self) -> Result<String> {
+        self.to_manual_code_str()
+    }
+
+    fn p_print(&self) -> String {
+        let mut out = String::new();
+        // Writing to a String cannot fail, so the results are ignored below.
+        let _ = writeln!(out, "{:<24} :{}", "Flow", self.flow.bits());
+        let _ = writeln!(out, "{:<24} :{}", "Pincode", self.pincode.get());
+        let _ = writeln!(
+            out,
+            "{:<24} :{}",
+            "Short Discriminator",
+            self.discriminator.short()
+        );
+        if let Some(long) = self.discriminator.long().filter(|&d| d != 0) {
+            let _ = writeln!(out, "{:<24} :{}", "Long Discriminator", long);
+        }
+        if let Some(discovery) = self.discovery.filter(|d| !d.is_empty()) {
+            let _ = writeln!(
+                out,
+                "{:<24} :{}",
+                "Discovery Capabilities",
+                discovery.bits()
+            );
+        }
+        if let (Some(vid), Some(pid)) = (self.vid, self.pid) {
+            let (vid, pid) = (vid.get(), pid.get());
+            let _ = writeln!(out, "{:<24} :{:<6} (0x{:04x})", "Vendor Id", vid, vid);
+            let _ = writeln!(out, "{:<24} :{:<6} (0x{:04x})", "Product Id", pid, pid);
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::payload::{ProductId, VendorId};
+
+    #[test]
+    fn test_python_call_sites() {
+        let payload = SetupPayload::with_python_defaults(1132, Passcode::new(69414998).unwrap());
+        assert_eq!(payload.vid, Some(VendorId::UNASSIGNED));
+        let payload = SetupPayload {
+            vid: Some(VendorId::new(0xFFF1)),
+            pid: Some(ProductId::new(0x8000)),
+            ..payload
+        };
+        assert_eq!(payload.generate_qrcode().unwrap(), "MT:Y.K904QI143LH13SH10");
+        assert_eq!(payload.generate_manualcode().unwrap(), "11237442363");
+
+        assert_eq!(
+            <SetupPayload as PythonSetupPayload>::parse("MT:Y.K904QI143LH13SH10").unwrap(),
+            payload
+        );
+        assert!(SetupPayload::parse_qrcode("11237442363").is_err());
+        assert!(SetupPayload::parse_manualcode("MT:Y.K904QI143LH13SH10").is_err());
+    }
+
+    #[test]
+    fn test_p_print() {
+        let payload = SetupPayload::parse_manualcode("11237442363").unwrap();
+        assert_eq!(
+            payload.p_print(),
+            "Flow                     :0\n\
+             Pincode                  :69414998\n\
+             Short Discriminator      :4\n"
+        );
+
+        let payload = SetupPayload::parse_qrcode("MT:Y.K904QI143LH13SH10").unwrap();
+        assert!(payload.p_print().ends_with(
+            "Long Discriminator       :1132\n\
+             Discovery Capabilities   :4\n\
+             Vendor Id                :65521  (0xfff1)\n\
+             Product Id               :32768  (0x8000)\n"
+        ));
+    }
+}
@@ -0,0 +1,213 @@
+//! Pluggable randomness for passcode, discriminator and salt generation.
+//!
+//! Production lines often need their secrets to come from an HSM or a
+//! hardware TRNG, while tests want a seeded, reproducible generator. The
+//! passcodes, discriminators and salts this crate generates are all drawn
+//! through the [`EntropySource`] trait, so both are a matter of passing a
+//! different source. With the `rand`
+//! feature, [`RngSource`] adapts any `rand::RngCore`.
+
+use crate::error::{EntropyError, Result};
+use crate::payload::is_valid_passcode;
+#[cfg(feature = "rand")]
+use crate::payload::{
+    CommissioningFlow, DiscoveryCapabilities, Discriminator, Passcode, ProductId, SetupPayload,
+    VendorId,
+};
+
+/// Shortest SPAKE2+ salt allowed by the Matter specification, in bytes.
+pub const MIN_SALT_LEN: usize = 16;
+/// Longest SPAKE2+ salt allowed by the Matter specification, in bytes.
+pub const MAX_SALT_LEN: usize = 32;
+
+/// A source of random bytes.
+pub trait EntropySource {
+    /// Fills `dest` entirely with random bytes.
+    ///
+    /// # Errors
+    ///
+    /// Returns `EntropyError::Unavailable` if the source cannot provide randomness
+    /// (e.g. the HSM is unreachable).
+    fn fill_bytes(&mut self, dest: &mut [u8]) -> Result<()>;
+}
+
+impl<E: EntropySource + ?Sized> EntropySource for &mut E {
+    fn fill_bytes(&mut self, dest: &mut [u8]) -> Result<()> {
+        (**self).fill_bytes(dest)
+    }
+}
+
+/// Adapts a `rand::RngCore` into an [`EntropySource`].
+#[cfg(feature = "rand")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RngSource<R>(pub R);
+
+#[cfg(feature = "rand")]
+impl<R: rand::RngCore> EntropySource for RngSource<R> {
+    fn fill_bytes(&mut self, dest: &mut [u8]) -> Result<()> {
+        self.0.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "rand")]
+impl SetupPayload {
+    /// Generates a payload for a new device, with a uniformly random
+    /// 12-bit discriminator and passcode drawn from `rng`, and the given
+    /// vendor ID, product ID and discovery capabilities.
+    ///
+    /// The passcode is one the specification allows. The flow is
+    /// [`CommissioningFlow::Standard`].
+    ///
+    /// # Errors
+    ///
+    /// Never fails with a `rand` generator; the `Result` mirrors the
+    /// [`EntropySource`] based functions it draws through.
+    pub fn generate_random<R: rand::RngCore + ?Sized>(
+        rng: &mut R,
+        vid: u16,
+        pid: u16,
+        discovery: DiscoveryCapabilities,
+    ) -> Result<Self> {
+        let mut source = RngSource(rng);
+        let discriminator = random_discriminator(&mut source)?;
+        let pincode = random_passcode(&mut source)?;
+        Ok(SetupPayload {
+            discriminator: Discriminator::from_long(discriminator)?,
+            pincode: Passcode::new(pincode)?,
+            discovery: Some(discovery),
+            flow: CommissioningFlow::Standard,
+            vid: Some(VendorId::new(vid)),
+            pid: Some(ProductId::new(pid)),
+            version: 0,
+            optional_data: Vec::new(),
+            raw_optional_data: Vec::new(),
+        })
+    }
+}
+
+/// Draws a uniformly random passcode among those allowed by the specification.
+///
+/// # Errors
+///
+/// Returns an error if `source` fails.
+pub fn random_passcode<E: EntropySource + ?Sized>(source: &mut E) -> Result<u32> {
+    // Sample 27 bits (0..2^27) and reject anything out of range or forbidden,
+    // which keeps the distribution uniform over the accepted values.
+    loop {
+        let mut bytes = [0u8; 4];
+        source.fill_bytes(&mut bytes)?;
+        let candidate = u32::from_le_bytes(bytes) & 0x7FF_FFFF;
+        if is_valid_passcode(candidate) {
+            return Ok(candidate);
+        }
+    }
+}
+
+/// Draws a uniformly random 12-bit discriminator.
+///
+/// # Errors
+///
+/// Returns an error if `source` fails.
+pub fn random_discriminator<E: EntropySource + ?Sized>(source: &mut E) -> Result<u16> {
+    let mut bytes = [0u8; 2];
+    source.fill_bytes(&mut bytes)?;
+    Ok(u16::from_le_bytes(bytes) & 0xFFF)
+}
+
+/// Draws a random SPAKE2+ salt of `len` bytes.
+///
+/// # Errors
+///
+/// Returns `EntropyError::InvalidSaltLength` if `len` is outside
+/// `MIN_SALT_LEN..=MAX_SALT_LEN`, or an error if `source` fails.
+pub fn random_salt<E: EntropySource + ?Sized>(source: &mut E, len: usize) -> Result<Vec<u8>> {
+    if !(MIN_SALT_LEN..=MAX_SALT_LEN).contains(&len) {
+        return Err(EntropyError::InvalidSaltLength(len).into());
+    }
+    let mut salt = vec![0u8; len];
+    source.fill_bytes(&mut salt)?;
+    Ok(salt)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::MatterPayloadError;
+
+    /// A seeded xorshift generator, shared by the tests of modules built on entropy sources.
+    pub(crate) struct XorShift(pub u64);
+
+    impl EntropySource for XorShift {
+        fn fill_bytes(&mut self, dest: &mut [u8]) -> Result<()> {
+            for byte in dest {
+                self.0 ^= self.0 << 13;
+                self.0 ^= self.0 >> 7;
+                self.0 ^= self.0 << 17;
+                *byte = self.0 as u8;
+            }
+            Ok(())
+        }
+    }
+
+    struct Unplugged;
+
+    impl EntropySource for Unplugged {
+        fn fill_bytes(&mut self, _dest: &mut [u8]) -> Result<()> {
+            Err(EntropyError::Unavailable.into())
+        }
+    }
+
+    #[test]
+    fn test_random_values_are_valid() {
+        let mut source = XorShift(0x9E3779B97F4A7C15);
+        for _ in 0..1000 {
+            assert!(is_valid_passcode(random_passcode(&mut source).unwrap()));
+            assert!(random_discriminator(&mut source).unwrap() <= 0xFFF);
+        }
+        assert_eq!(random_salt(&mut source, 32).unwrap().len(), 32);
+    }
+
+    #[test]
+    fn test_entropy_errors() {
+        assert_eq!(
+            random_passcode(&mut Unplugged).unwrap_err(),
+            MatterPayloadError::Entropy(EntropyError::Unavailable)
+        );
+        assert_eq!(
+            random_salt(&mut XorShift(1), 8).unwrap_err(),
+            MatterPayloadError::Entropy(EntropyError::InvalidSaltLength(8))
+        );
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn test_generate_random() {
+        struct Rng(XorShift);
+
+        impl rand::RngCore for Rng {
+            fn next_u32(&mut self) -> u32 {
+                self.next_u64() as u32
+            }
+
+            fn next_u64(&mut self) -> u64 {
+                let mut bytes = [0u8; 8];
+                self.fill_bytes(&mut bytes);
+                u64::from_le_bytes(bytes)
+            }
+
+            fn fill_bytes(&mut self, dest: &mut [u8]) {
+                self.0.fill_bytes(dest).unwrap();
+            }
+        }
+
+        let mut rng = Rng(XorShift(0x9E3779B97F4A7C15));
+        for _ in 0..100 {
+            let payload =
+                SetupPayload::generate_random(&mut rng, 0xFFF1, 0x8001, DiscoveryCapabilities::BLE)
+                    .unwrap();
+            assert!(payload.validate().is_empty());
+            assert!(payload.to_qr_code_str().is_ok());
+        }
+    }
+}
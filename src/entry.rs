@@ -0,0 +1,295 @@
+//! Live validation of manual pairing codes as the user types them.
+//!
+//! Pairing UIs only learn that a manual code is wrong once it is submitted.
+//! [`EntryValidator`] takes digits one at a time and reports, after each
+//! keystroke, how many digits are still expected (11 or 21, known from the
+//! first digit), whether the prefix can ever be valid, and once the code is
+//! complete, whether its check digit is correct.
+//!
+//! A prefix is rejected as soon as it is certain to be invalid: each group
+//! of digits encodes a bit field and is checked against the field's range
+//! once fully entered, and the passcode is checked once its digits are in.
+
+use crate::error::{PayloadError, Result};
+use crate::payload::{Passcode, SetupPayload};
+use crate::verhoeff;
+
+/// Length of a manual code without VID/PID.
+const SHORT_LEN: usize = 11;
+/// Length of a manual code carrying VID/PID.
+const LONG_LEN: usize = 21;
+
+/// Digit groups encoding a bit field, as (end of the group, largest value).
+/// The first digit is checked on its own; the VID and PID groups only exist
+/// in the 21-digit form.
+const GROUPS: [(usize, u32); 4] = [(6, 0xFFFF), (10, 0x1FFF), (15, 0xFFFF), (20, 0xFFFF)];
+
+/// Number of digits after which the passcode is known.
+const PASSCODE_END: usize = 10;
+
+/// What the digits entered so far amount to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntryState {
+    /// Nothing entered yet.
+    Empty,
+    /// More digits are expected.
+    Incomplete {
+        /// Digits entered so far
+        entered: usize,
+        /// Length of the complete code
+        expected: usize,
+    },
+    /// The first digit is above 7, so no valid code starts this way.
+    InvalidPrefix,
+    /// A group of digits exceeds the bit field it encodes, so no valid code
+    /// starts this way.
+    OutOfRange,
+    /// The passcode the digits encode is forbidden by the specification.
+    InvalidPasscode,
+    /// All digits are entered but the check digit is wrong.
+    InvalidChecksum,
+    /// All digits are entered and the check digit is correct.
+    Complete,
+}
+
+impl EntryState {
+    /// Returns `true` for states a UI should flag as an error.
+    pub fn is_error(self) -> bool {
+        matches!(
+            self,
+            EntryState::InvalidPrefix
+                | EntryState::OutOfRange
+                | EntryState::InvalidPasscode
+                | EntryState::InvalidChecksum
+        )
+    }
+}
+
+/// Accumulates manual code digits one keystroke at a time.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EntryValidator {
+    digits: String,
+}
+
+impl EntryValidator {
+    /// Creates an empty validator.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends one character and returns the new state.
+    ///
+    /// Spaces and dashes, which users type to mirror the printed grouping,
+    /// are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PayloadError::InvalidManualCodeDigit` for any other non-digit,
+    /// or `PayloadError::InvalidManualCodeLength` if the code is already
+    /// complete. The entry is left unchanged in both cases.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use matter_setup_code::entry::{EntryState, EntryValidator};
+    ///
+    /// let mut entry = EntryValidator::new();
+    /// assert_eq!(
+    ///     entry.push('3').unwrap(),
+    ///     EntryState::Incomplete { entered: 1, expected: 11 }
+    /// );
+    /// for c in "4970-112-332".chars() {
+    ///     entry.push(c).unwrap();
+    /// }
+    /// assert_eq!(entry.state(), EntryState::Complete);
+    /// ```
+    pub fn push(&mut self, c: char) -> Result<EntryState> {
+        if c == ' ' || c == '-' {
+            return Ok(self.state());
+        }
+        if !c.is_ascii_digit() {
+            return Err(PayloadError::InvalidManualCodeDigit {
+                offset: self.digits.len(),
+                character: c,
+            }
+            .into());
+        }
+        if self.digits.len() >= self.expected_len().unwrap_or(LONG_LEN) {
+            return Err(PayloadError::InvalidManualCodeLength(self.digits.len() + 1).into());
+        }
+        self.digits.push(c);
+        Ok(self.state())
+    }
+
+    /// Removes the last digit (backspace) and returns the new state.
+    pub fn pop(&mut self) -> EntryState {
+        self.digits.pop();
+        self.state()
+    }
+
+    /// Clears the entry.
+    pub fn clear(&mut self) {
+        self.digits.clear();
+    }
+
+    /// The digits entered so far.
+    pub fn as_str(&self) -> &str {
+        &self.digits
+    }
+
+    /// The length of the complete code, once the first digit is known and valid.
+    pub fn expected_len(&self) -> Option<usize> {
+        let first = self.digits.bytes().next()? - b'0';
+        match first {
+            0..=3 => Some(SHORT_LEN),
+            // Bit 2 of the first digit flags a VID/PID-carrying code.
+            4..=7 => Some(LONG_LEN),
+            _ => None,
+        }
+    }
+
+    /// The state of the entry.
+    pub fn state(&self) -> EntryState {
+        if self.digits.is_empty() {
+            return EntryState::Empty;
+        }
+        let Some(expected) = self.expected_len() else {
+            return EntryState::InvalidPrefix;
+        };
+        let entered = self.digits.len();
+        let group = |start: usize, end: usize| -> u32 {
+            self.digits.as_bytes()[start..end]
+                .iter()
+                .fold(0, |acc, &digit| acc * 10 + u32::from(digit - b'0'))
+        };
+        let mut start = 1;
+        for (end, max) in GROUPS {
+            if end > entered.min(expected - 1) {
+                break;
+            }
+            if group(start, end) > max {
+                return EntryState::OutOfRange;
+            }
+            start = end;
+        }
+        if entered >= PASSCODE_END {
+            let passcode = (group(6, 10) << 14) | (group(1, 6) & 0x3FFF);
+            if Passcode::new(passcode).is_err() {
+                return EntryState::InvalidPasscode;
+            }
+        }
+        if entered < expected {
+            return EntryState::Incomplete {
+                entered: self.digits.len(),
+                expected,
+            };
+        }
+        match verhoeff::validate(&self.digits) {
+            Ok(true) => EntryState::Complete,
+            _ => EntryState::InvalidChecksum,
+        }
+    }
+
+    /// Parses the complete code.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`SetupPayload::parse_str`], e.g. for an
+    /// incomplete code.
+    pub fn finish(&self) -> Result<SetupPayload> {
+        SetupPayload::parse_str(&self.digits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MatterPayloadError;
+
+    #[test]
+    fn test_entry_progress() {
+        let mut entry = EntryValidator::new();
+        assert_eq!(entry.state(), EntryState::Empty);
+        assert_eq!(entry.expected_len(), None);
+
+        for (i, c) in "1123744236".chars().enumerate() {
+            assert_eq!(
+                entry.push(c).unwrap(),
+                EntryState::Incomplete {
+                    entered: i + 1,
+                    expected: 11
+                }
+            );
+        }
+        // Wrong check digit, then corrected with a backspace.
+        assert_eq!(entry.push('4').unwrap(), EntryState::InvalidChecksum);
+        assert!(entry.state().is_error());
+        entry.pop();
+        assert_eq!(entry.push('3').unwrap(), EntryState::Complete);
+        assert_eq!(entry.finish().unwrap().pincode.get(), 69414998);
+
+        // The code is complete: further digits are refused.
+        assert_eq!(
+            entry.push('0').unwrap_err(),
+            MatterPayloadError::Payload(PayloadError::InvalidManualCodeLength(12))
+        );
+        assert_eq!(entry.as_str(), "11237442363");
+    }
+
+    #[test]
+    fn test_entry_prefix_and_characters() {
+        let mut entry = EntryValidator::new();
+        assert_eq!(
+            entry.push('5').unwrap(),
+            EntryState::Incomplete {
+                entered: 1,
+                expected: 21
+            }
+        );
+        assert_eq!(
+            entry.push('x').unwrap_err(),
+            MatterPayloadError::Payload(PayloadError::InvalidManualCodeDigit {
+                offset: 1,
+                character: 'x'
+            })
+        );
+
+        entry.clear();
+        assert_eq!(entry.push('9').unwrap(), EntryState::InvalidPrefix);
+        assert!(entry.finish().is_err());
+    }
+
+    #[test]
+    fn test_entry_early_errors() {
+        let mut entry = EntryValidator::new();
+        for c in "1999".chars() {
+            entry.push(c).unwrap();
+        }
+        assert!(matches!(entry.state(), EntryState::Incomplete { .. }));
+        // The passcode's low bits and the discriminator's fit in 16 bits.
+        for c in "99".chars() {
+            entry.push(c).unwrap();
+        }
+        assert_eq!(entry.state(), EntryState::OutOfRange);
+        assert!(entry.state().is_error());
+
+        // Passcode 0.
+        entry.clear();
+        for c in "100000000".chars() {
+            entry.push(c).unwrap();
+        }
+        assert!(!entry.state().is_error());
+        assert_eq!(entry.push('0').unwrap(), EntryState::InvalidPasscode);
+
+        // The PID group of a 21-digit code.
+        entry.clear();
+        for c in "7497011233655219".chars() {
+            entry.push(c).unwrap();
+        }
+        assert!(!entry.state().is_error());
+        for c in "9999".chars() {
+            entry.push(c).unwrap();
+        }
+        assert_eq!(entry.state(), EntryState::OutOfRange);
+    }
+}
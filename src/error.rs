@@ -1,8 +1,12 @@
 use thiserror::Error;
 use deku::DekuError;
 
+use crate::payload::DiscoveryCapabilities;
+use crate::spec::SpecVersion;
+use crate::validation::ValidationIssue;
+
 /// The primary error type for the `matter-payload` library.
-#[derive(Error, Debug, PartialEq, Eq)]
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
 pub enum MatterPayloadError {
     /// Errors originating from the Base38 decoding process.
     #[error("Base38 decoding failed")]
@@ -19,10 +23,69 @@ pub enum MatterPayloadError {
 
     #[error("Deku framework error: {0}")]
     Deku(#[from] DekuError),
+
+    /// Errors originating from specification revision checks.
+    #[error("Specification version error")]
+    Spec(#[from] SpecError),
+
+    /// Errors originating from discriminator and passcode allocation.
+    #[error("Assignment policy error")]
+    Assignment(#[from] AssignmentError),
+
+    /// Errors originating from entropy sources.
+    #[error("Entropy source error")]
+    Entropy(#[from] EntropyError),
+
+    /// Errors originating from TLV decoding.
+    #[error("TLV decoding error")]
+    Tlv(#[from] TlvError),
+
+    /// Errors originating from NDEF messages and NFC tag layouts.
+    #[error("NDEF error")]
+    Ndef(#[from] NdefError),
+
+    /// A parsed payload failing the checks of a validation policy.
+    #[error("Payload validation error")]
+    Validation(#[from] ValidationIssue),
+
+    /// Errors originating from loading vendor payload profiles.
+    #[cfg(feature = "profiles")]
+    #[error("Profile error")]
+    Profile(#[from] ProfileError),
+
+    /// Errors originating from JSON Lines import and export.
+    #[cfg(feature = "jsonl")]
+    #[error("JSON Lines error")]
+    Jsonl(#[from] JsonlError),
+
+    /// Errors originating from sealing or unsealing provisioning records.
+    #[cfg(feature = "crypto")]
+    #[error("Sealed record error")]
+    Seal(#[from] SealError),
+
+    /// Errors originating from BLE advertisement decoding and scanning.
+    #[cfg(feature = "ble-scan")]
+    #[error("BLE scan error")]
+    Ble(#[from] BleError),
+
+    /// Errors originating from mDNS browsing.
+    #[cfg(feature = "mdns")]
+    #[error("mDNS error")]
+    Mdns(#[from] MdnsError),
+
+    /// Errors originating from drawing QR code symbols.
+    #[cfg(feature = "render")]
+    #[error("QR code rendering error")]
+    Render(#[from] RenderError),
+
+    /// Errors originating from reading QR codes in images.
+    #[cfg(feature = "qr-scan")]
+    #[error("QR code scanning error")]
+    Scan(#[from] ScanError),
 }
 
 /// Specific errors that can occur during Base38 decoding.
-#[derive(Error, Debug, PartialEq, Eq)]
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
 pub enum Base38DecodeError {
     #[error("invalid character '{0}' found in input")]
     InvalidCharacter(char),
@@ -39,7 +102,7 @@ pub enum Base38DecodeError {
 }
 
 /// Specific errors that can occur during Verhoeff checksum operations.
-#[derive(Error, Debug, PartialEq, Eq)]
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
 pub enum VerhoeffError {
     #[error("input contains non-digit character '{0}'")]
     InvalidCharacter(char),
@@ -49,14 +112,14 @@ pub enum VerhoeffError {
 }
 
 /// Specific errors that can occur during bit utility operations.
-#[derive(Error, Debug, PartialEq, Eq)]
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
 pub enum BitUtilsError {
     #[error("value {value} overflows the requested {bits} bits")]
     ValueOverflow { value: u64, bits: usize },
 }
 
 /// Specific errors that can occur during payload parsing or generation.
-#[derive(Error, Debug, PartialEq, Eq)]
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
 pub enum PayloadError {
     #[error("invalid payload length: expected 11 or 21, got {0}")]
     InvalidManualCodeLength(usize),
@@ -64,17 +127,262 @@ pub enum PayloadError {
     #[error("manual code check digit is invalid")]
     InvalidManualCodeChecksum,
 
-    #[error("manual code contains an invalid digit: {0}")]
-    InvalidManualCodeDigit(String),
+    #[error("manual code contains invalid character '{character}' at offset {offset}")]
+    InvalidManualCodeDigit { offset: usize, character: char },
 
     #[error("manual code's first digit must be <= 7")]
     InvalidManualCodePrefix,
 
+    #[error("manual code's first digit announces a VID and PID, which need 21 digits, not {0}")]
+    ManualCodeLengthMismatch(usize),
+
     #[error("QR code payload must start with 'MT:'")]
     InvalidQrCodePrefix,
 
     #[error("manual code discriminator must be <= 15, but was {0}")]
     DiscriminatorOutOfRange(u8),
+
+    #[error("{0} is not an 11-digit manual code")]
+    ManualCodeNumberOutOfRange(u64),
+
+    #[error("a 21-digit manual code does not fit in a ManualCodeNumber")]
+    LongManualCodeNumber,
+
+    #[error("an 11-digit manual code cannot carry commissioning flow {0}")]
+    ShortManualCodeFlow(u8),
+
+    #[error("non-ASCII byte {byte:#04x} at offset {offset}")]
+    NonAsciiByte { offset: usize, byte: u8 },
+
+    #[error("discriminator is required")]
+    MissingDiscriminator,
+
+    #[error("passcode is required")]
+    MissingPasscode,
+
+    #[error("discriminator must fit in 12 bits, but was {0}")]
+    DiscriminatorTooLarge(u16),
+
+    #[error("passcode {0} is out of range or forbidden by the specification")]
+    InvalidPasscode(u32),
+
+    #[error("vendor ID is required for QR code generation")]
+    MissingVendorId,
+
+    #[error("product ID is required for QR code generation")]
+    MissingProductId,
+
+    #[error("discovery capabilities are required for QR code generation")]
+    MissingDiscovery,
+
+    #[error("long discriminator is required for QR code generation")]
+    MissingLongDiscriminator,
+
+    #[error("custom commissioning flow requires an assigned vendor ID and product ID")]
+    CustomFlowWithoutVidPid,
+
+    #[error("commissioning flow {0} is reserved")]
+    ReservedCommissioningFlow(u8),
+
+    #[error("QR code padding bits are not zero: {0:#06b}")]
+    NonZeroPadding(u8),
+
+    #[error("packed QR code payload must be at least 11 bytes, got {0}")]
+    InvalidPackedLength(usize),
+
+    #[error("discriminator {0} does not match the payload's discriminator")]
+    DiscriminatorMismatch(u16),
+
+    #[error("payload does not use the custom commissioning flow")]
+    NotCustomFlow,
+
+    #[error("invalid optional data at byte {offset}: {reason}")]
+    InvalidOptionalData { offset: usize, reason: &'static str },
+
+    #[error("serial number must be at most 32 bytes, got {0}")]
+    SerialNumberTooLong(usize),
+
+    #[error("optional data tag {0:#04x} is not in the vendor range 0x80-0xFF")]
+    InvalidVendorTag(u8),
+
+    #[error("no payloads to concatenate")]
+    NoPayloads,
+
+    #[error("QR code payload of {0} bytes exceeds the 523-byte maximum")]
+    PayloadTooLarge(usize),
+}
+
+/// Specific errors that can occur when a payload is checked against a specification revision.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum SpecError {
+    #[error("discovery capabilities {capabilities} are not defined in {version}")]
+    UnsupportedDiscovery {
+        capabilities: DiscoveryCapabilities,
+        version: SpecVersion,
+    },
+
+    #[error("discovery capabilities have reserved bits set: {0:#04x}")]
+    ReservedDiscoveryBits(u8),
+
+    #[error("payload format version {0} is not defined by any known revision")]
+    UnknownPayloadVersion(u8),
+}
+
+/// Specific errors that can occur while allocating discriminators and passcodes.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum AssignmentError {
+    #[error("no valid passcodes left to assign")]
+    PasscodesExhausted,
+}
+
+/// Specific errors that can occur while drawing random values.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum EntropyError {
+    #[error("entropy source is unavailable")]
+    Unavailable,
+
+    #[error("salt length must be between 16 and 32 bytes, got {0}")]
+    InvalidSaltLength(usize),
+}
+
+/// Specific errors that can occur while decoding Matter TLV.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum TlvError {
+    #[error("truncated element at byte {offset}")]
+    Truncated { offset: usize },
+
+    #[error("unsupported element type {element_type:#04x} at byte {offset}")]
+    UnsupportedElementType { offset: usize, element_type: u8 },
+
+    #[error("unsupported tag form at byte {offset}")]
+    UnsupportedTag { offset: usize },
+
+    #[error("invalid UTF-8 string at byte {offset}")]
+    InvalidUtf8 { offset: usize },
+
+    #[error("end of container outside any container at byte {offset}")]
+    UnexpectedEndOfContainer { offset: usize },
+
+    #[error("container nested too deeply at byte {offset}")]
+    TooDeeplyNested { offset: usize },
+}
+
+/// Specific errors that can occur while decoding NDEF messages or laying
+/// them out on NFC tags.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum NdefError {
+    #[error("truncated record at byte {offset}")]
+    Truncated { offset: usize },
+
+    #[error("unsupported chunked record at byte {offset}")]
+    ChunkedRecord { offset: usize },
+
+    #[error("no Matter onboarding record in the message")]
+    NoOnboardingRecord,
+
+    #[error("more than one Matter onboarding record in the message")]
+    DuplicateOnboardingRecord,
+
+    #[error("tag data area size must be a nonzero multiple of 8 up to 2040 bytes, got {0}")]
+    InvalidDataAreaSize(usize),
+
+    #[error("NDEF message needs {needed} bytes of tag data area, only {available} available")]
+    TagTooSmall { needed: usize, available: usize },
+}
+
+/// Specific errors that can occur while loading vendor payload profiles.
+#[cfg(feature = "profiles")]
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ProfileError {
+    #[error("invalid profile file: {0}")]
+    Toml(#[from] toml::de::Error),
+
+    #[error("no profile named '{0}'")]
+    UnknownProfile(String),
+
+    #[error("serial number '{0}' is not an unsigned integer")]
+    NonNumericSerialNumber(String),
+}
+
+/// Specific errors that can occur while sealing or unsealing provisioning records.
+#[cfg(feature = "crypto")]
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum SealError {
+    #[error("sealed record is truncated ({0} bytes)")]
+    Truncated(usize),
+
+    #[error("unsupported sealed record format version {0}")]
+    UnsupportedVersion(u8),
+
+    #[error("sealed record failed authentication (wrong key, associated data, or tampered)")]
+    Authentication,
+
+    #[error("encryption failed")]
+    Encryption,
+}
+
+/// Specific errors that can occur while reading or writing JSON Lines.
+#[cfg(feature = "jsonl")]
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum JsonlError {
+    #[error("I/O error: {0}")]
+    Io(String),
+
+    #[error("line {line}: {message}")]
+    InvalidLine { line: usize, message: String },
+}
+
+/// Specific errors that can occur while decoding or scanning BLE advertisements.
+#[cfg(feature = "ble-scan")]
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum BleError {
+    #[error("Matter service data must be at least 8 bytes, got {0}")]
+    ServiceDataLength(usize),
+
+    #[error("unsupported Matter BLE opcode {0:#04x}")]
+    UnsupportedOpcode(u8),
+
+    #[error("no Bluetooth adapter found")]
+    NoAdapter,
+
+    #[error("BLE scan failed: {0}")]
+    Scan(String),
+}
+
+/// Specific errors that can occur while browsing for commissionable nodes.
+#[cfg(feature = "mdns")]
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum MdnsError {
+    #[error("mDNS daemon error: {0}")]
+    Daemon(String),
+}
+
+/// Specific errors that can occur while drawing QR code symbols.
+#[cfg(feature = "render")]
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum RenderError {
+    #[error("QR code string does not fit in a QR symbol: {0}")]
+    Symbol(qrcode::types::QrError),
+
+    #[error("foreground is not dark enough against the background for scanners")]
+    LowContrast,
+
+    #[error("a logo {0}% of the symbol wide hides more modules than error correction recovers")]
+    LogoTooLarge(u8),
+}
+
+/// Specific errors that can occur while reading QR codes in images.
+#[cfg(feature = "qr-scan")]
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ScanError {
+    #[error("image cannot be decoded: {0}")]
+    Image(String),
+
+    #[error("no QR code found in the image")]
+    NoQrCode,
+
+    #[error("QR code cannot be decoded: {0}")]
+    Symbol(String),
 }
 
 pub type Result<T> = std::result::Result<T, MatterPayloadError>;
\ No newline at end of file
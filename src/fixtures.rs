@@ -0,0 +1,154 @@
+//! Language-neutral golden test vectors.
+//!
+//! Teams maintaining setup payload parsers in other languages can stay
+//! bit-compatible with this crate by running its test vectors. The vectors
+//! are computed by the crate itself: for every input, the parsed fields and
+//! the strings regenerated from them, or the error for invalid inputs.
+//! [`to_json`], [`to_python`] and [`to_typescript`] render them as files
+//! ready to include in another test suite; the `golden_fixtures` example
+//! writes all three to a directory.
+
+use serde::Serialize;
+
+use crate::payload::SetupPayload;
+
+/// Reference inputs, including the well-known CHIP SDK example codes.
+const VALID_INPUTS: [(&str, &str); 5] = [
+    ("chip_tool_qr", "MT:Y.K904QI143LH13SH10"),
+    ("chip_tool_manual", "11237442363"),
+    ("sdk_example_ble_qr", "MT:-24J042C00KA0648G00"),
+    ("sdk_example_on_network_qr", "MT:-24J0AFN00KA0648G00"),
+    ("sdk_example_manual", "34970112332"),
+];
+
+/// Inputs every conforming parser must reject.
+const INVALID_INPUTS: [(&str, &str); 5] = [
+    ("manual_bad_length", "12345"),
+    ("manual_bad_checksum", "20000000031"),
+    ("manual_bad_prefix", "91237442368"),
+    ("qr_bad_character", "MT:Y.K904QI143LH13SH1@"),
+    ("qr_bad_chunk_length", "MT:Y.K904QI143LH13SH1"),
+];
+
+/// One golden test vector.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GoldenVector {
+    /// Stable identifier of the vector
+    pub name: &'static str,
+    /// The string fed to the parser
+    pub input: &'static str,
+    /// Whether parsing must succeed
+    pub valid: bool,
+    /// The parsed fields, for valid inputs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payload: Option<SetupPayload>,
+    /// The QR code regenerated from the parsed fields, when they allow it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub qr_code: Option<String>,
+    /// The manual code regenerated from the parsed fields
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub manual_code: Option<String>,
+    /// The error message produced by this crate, for invalid inputs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Computes the golden vectors with the current implementation.
+pub fn golden_vectors() -> Vec<GoldenVector> {
+    let valid = VALID_INPUTS.iter().map(|&(name, input)| {
+        let payload = SetupPayload::parse_str(input).expect("golden input must parse");
+        // A payload parsed from a manual code lacks the fields a QR code needs.
+        let qr_code = (payload.vid.is_some()
+            && payload.pid.is_some()
+            && payload.discovery.is_some()
+            && payload.discriminator.long().is_some())
+        .then(|| {
+            payload
+                .to_qr_code_str()
+                .expect("golden QR code must encode")
+        });
+        GoldenVector {
+            name,
+            input,
+            valid: true,
+            qr_code,
+            manual_code: payload.to_manual_code_str().ok(),
+            payload: Some(payload),
+            error: None,
+        }
+    });
+    let invalid = INVALID_INPUTS.iter().map(|&(name, input)| GoldenVector {
+        name,
+        input,
+        valid: false,
+        payload: None,
+        qr_code: None,
+        manual_code: None,
+        error: Some(
+            SetupPayload::parse_str(input)
+                .expect_err("invalid golden input must be rejected")
+                .to_string(),
+        ),
+    });
+    valid.chain(invalid).collect()
+}
+
+/// Renders the vectors as a pretty-printed JSON array.
+pub fn to_json(vectors: &[GoldenVector]) -> String {
+    serde_json::to_string_pretty(vectors).expect("golden vectors always serialize")
+}
+
+/// Renders the vectors as a Python module defining `GOLDEN_VECTORS`.
+pub fn to_python(vectors: &[GoldenVector]) -> String {
+    format!(
+        "# Generated by matter_setup_code {}. Do not edit.\nimport json\n\nGOLDEN_VECTORS = json.loads(r\"\"\"\n{}\n\"\"\")\n",
+        env!("CARGO_PKG_VERSION"),
+        to_json(vectors)
+    )
+}
+
+/// Renders the vectors as a TypeScript module exporting `GOLDEN_VECTORS`.
+pub fn to_typescript(vectors: &[GoldenVector]) -> String {
+    format!(
+        "// Generated by matter_setup_code {}. Do not edit.\nexport const GOLDEN_VECTORS = {} as const;\n",
+        env!("CARGO_PKG_VERSION"),
+        to_json(vectors)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_golden_vectors_roundtrip() {
+        let vectors = golden_vectors();
+        assert_eq!(vectors.len(), VALID_INPUTS.len() + INVALID_INPUTS.len());
+
+        for vector in vectors.iter().filter(|v| v.valid) {
+            if vector.input.starts_with("MT:") {
+                assert_eq!(vector.qr_code.as_deref(), Some(vector.input));
+            } else {
+                assert_eq!(vector.manual_code.as_deref(), Some(vector.input));
+            }
+        }
+        assert!(
+            vectors
+                .iter()
+                .filter(|v| !v.valid)
+                .all(|v| v.error.is_some())
+        );
+    }
+
+    #[test]
+    fn test_fixture_files() {
+        let vectors = golden_vectors();
+        let json = to_json(&vectors);
+        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed[0]["input"], "MT:Y.K904QI143LH13SH10");
+        assert_eq!(parsed[0]["payload"]["pincode"], 69414998);
+
+        assert!(to_python(&vectors).contains("GOLDEN_VECTORS = json.loads"));
+        assert!(to_typescript(&vectors).contains("export const GOLDEN_VECTORS = ["));
+    }
+}
@@ -0,0 +1,206 @@
+//! Discriminator collision analysis for a set of devices.
+//!
+//! A commissioner uses the discriminator to find the device to commission
+//! among everything advertising nearby. When several devices installed on the
+//! same site share a discriminator, the commissioner has to try them one by
+//! one (or picks the wrong one first), so large deployments want to detect and
+//! re-provision clashing units before shipment.
+
+use std::collections::BTreeMap;
+
+use crate::payload::SetupPayload;
+
+/// Number of distinct 12-bit long discriminators.
+const LONG_DISCRIMINATOR_SPACE: u32 = 1 << 12;
+/// Number of distinct 4-bit short discriminators.
+const SHORT_DISCRIMINATOR_SPACE: u32 = 1 << 4;
+
+/// A group of devices sharing the same discriminator.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Collision {
+    /// The shared discriminator value
+    pub discriminator: u16,
+    /// Positions of the clashing payloads in the analysed slice
+    pub indices: Vec<usize>,
+}
+
+impl Collision {
+    /// Probability that a commissioner looking for one of these devices reaches
+    /// a different one first, assuming it picks among the candidates at random.
+    pub fn misdirection_probability(&self) -> f64 {
+        let count = self.indices.len() as f64;
+        (count - 1.0) / count
+    }
+}
+
+/// The discriminator collisions found in a set of payloads.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CollisionReport {
+    /// Number of payloads analysed
+    pub device_count: usize,
+    /// Groups sharing a 12-bit long discriminator (QR code / BLE / DNS-SD matching)
+    pub long: Vec<Collision>,
+    /// Groups sharing a 4-bit short discriminator (manual code matching)
+    pub short: Vec<Collision>,
+    /// Probability that at least two of `device_count` uniformly random long
+    /// discriminators would collide, for comparison with the observed result
+    pub expected_long_collision_probability: f64,
+}
+
+impl CollisionReport {
+    /// Returns `true` if no two payloads share a long discriminator.
+    pub fn is_clear(&self) -> bool {
+        self.long.is_empty()
+    }
+
+    /// Number of devices involved in at least one long discriminator collision.
+    pub fn colliding_devices(&self) -> usize {
+        self.long.iter().map(|c| c.indices.len()).sum()
+    }
+}
+
+/// Finds the discriminator collisions among `payloads`.
+///
+/// Payloads without a long discriminator (e.g. parsed from a manual code)
+/// only take part in the short discriminator analysis.
+///
+/// # Example
+///
+/// ```
+/// use matter_setup_code::{Passcode, SetupPayload};
+/// use matter_setup_code::fleet::check_collisions;
+///
+/// let payload = |discriminator, passcode| {
+///     let passcode = Passcode::new(passcode).unwrap();
+///     SetupPayload::new(discriminator, passcode, Some(4), None, Some(0xFFF1), Some(0x8000))
+/// };
+/// let payloads = [payload(1132, 20202021), payload(1132, 20202022), payload(3840, 20202023)];
+/// let report = check_collisions(&payloads);
+/// assert_eq!(report.long.len(), 1);
+/// assert_eq!(report.long[0].indices, vec![0, 1]);
+/// ```
+pub fn check_collisions(payloads: &[SetupPayload]) -> CollisionReport {
+    let long = group_collisions(
+        payloads
+            .iter()
+            .enumerate()
+            .filter_map(|(i, p)| p.discriminator.long().map(|d| (d, i))),
+    );
+    let short = group_collisions(
+        payloads
+            .iter()
+            .enumerate()
+            .map(|(i, p)| (p.discriminator.short() as u16, i)),
+    );
+
+    CollisionReport {
+        device_count: payloads.len(),
+        long,
+        short,
+        expected_long_collision_probability: random_collision_probability(
+            payloads.len(),
+            LONG_DISCRIMINATOR_SPACE,
+        ),
+    }
+}
+
+/// Probability that at least two of `devices` uniformly random short
+/// discriminators collide. With only 16 values this reaches 1 at 17 devices,
+/// which is why commissioners prefer the long discriminator when available.
+pub fn short_collision_probability(devices: usize) -> f64 {
+    random_collision_probability(devices, SHORT_DISCRIMINATOR_SPACE)
+}
+
+/// Groups `(discriminator, index)` pairs, keeping only discriminators used more than once.
+fn group_collisions(entries: impl Iterator<Item = (u16, usize)>) -> Vec<Collision> {
+    let mut groups: BTreeMap<u16, Vec<usize>> = BTreeMap::new();
+    for (discriminator, index) in entries {
+        groups.entry(discriminator).or_default().push(index);
+    }
+
+    groups
+        .into_iter()
+        .filter(|(_, indices)| indices.len() > 1)
+        .map(|(discriminator, indices)| Collision {
+            discriminator,
+            indices,
+        })
+        .collect()
+}
+
+/// The birthday-problem probability of at least one collision among `devices` draws from `space` values.
+fn random_collision_probability(devices: usize, space: u32) -> f64 {
+    if devices > space as usize {
+        return 1.0;
+    }
+    let space = space as f64;
+    let all_distinct = (0..devices).fold(1.0, |acc, i| acc * (1.0 - i as f64 / space));
+    1.0 - all_distinct
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::payload::Passcode;
+
+    fn payload(discriminator: u16) -> SetupPayload {
+        SetupPayload::new(
+            discriminator,
+            Passcode::new(20202021).unwrap(),
+            Some(4),
+            None,
+            Some(0xFFF1),
+            Some(0x8000),
+        )
+    }
+
+    #[test]
+    fn test_long_and_short_collisions() {
+        // 0x100 and 0x1FF share the short discriminator 1 but not the long one.
+        let payloads = [
+            payload(0x100),
+            payload(0x1FF),
+            payload(0x100),
+            payload(0x100),
+            payload(0x800),
+        ];
+        let report = check_collisions(&payloads);
+
+        assert_eq!(report.device_count, 5);
+        assert_eq!(
+            report.long,
+            vec![Collision {
+                discriminator: 0x100,
+                indices: vec![0, 2, 3]
+            }]
+        );
+        assert_eq!(
+            report.short,
+            vec![Collision {
+                discriminator: 1,
+                indices: vec![0, 1, 2, 3]
+            }]
+        );
+        assert!(!report.is_clear());
+        assert_eq!(report.colliding_devices(), 3);
+        assert!((report.long[0].misdirection_probability() - 2.0 / 3.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_clear_fleet() {
+        let report = check_collisions(&[payload(1), payload(2), payload(3)]);
+        assert!(report.is_clear());
+        assert_eq!(report.colliding_devices(), 0);
+    }
+
+    #[test]
+    fn test_random_collision_probability() {
+        assert_eq!(random_collision_probability(0, 4096), 0.0);
+        assert_eq!(random_collision_probability(1, 4096), 0.0);
+        assert!((random_collision_probability(2, 4096) - 1.0 / 4096.0).abs() < 1e-12);
+        // Around 76 devices the chance of a long discriminator clash passes 50%.
+        assert!(random_collision_probability(75, 4096) < 0.5);
+        assert!(random_collision_probability(76, 4096) > 0.5);
+        assert_eq!(short_collision_probability(17), 1.0);
+    }
+}
@@ -0,0 +1,201 @@
+//! Commissioning parameters derived from a setup payload.
+//!
+//! Apps scan a code with this crate and then hand off to a Matter stack
+//! (the SDK's chip-tool, rs-matter, a platform API) to commission the device.
+//! [`CommissioningHandoff`] bundles what every stack needs from the code,
+//! plus any addresses already discovered, so the app can keep this crate as
+//! its single "code to commissioning parameters" layer whatever the stack.
+
+use std::net::SocketAddr;
+
+use crate::payload::{
+    CommissioningFlow, DiscoveryCapabilities, ProductId, SetupPayload, VendorId,
+    short_discriminator_from_long,
+};
+
+/// How a commissioner should filter advertisements to find the device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DiscriminatorFilter {
+    /// Match the full 12-bit discriminator (QR code).
+    Long(u16),
+    /// Match the upper 4 bits of the discriminator (manual code).
+    Short(u8),
+}
+
+impl DiscriminatorFilter {
+    /// Returns `true` if a device advertising the 12-bit `discriminator` passes the filter.
+    pub fn matches(self, discriminator: u16) -> bool {
+        match self {
+            DiscriminatorFilter::Long(long) => long == discriminator & 0x0FFF,
+            DiscriminatorFilter::Short(short) => {
+                short == short_discriminator_from_long(discriminator)
+            }
+        }
+    }
+}
+
+/// Everything a commissioning stack needs to find and commission one device.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommissioningHandoff {
+    /// Setup PIN code used to establish the PASE session
+    pub passcode: u32,
+    /// Filter selecting the device among commissionable nodes
+    pub discriminator: DiscriminatorFilter,
+    /// Transports the device advertises on, if the code says so
+    pub discovery: Option<DiscoveryCapabilities>,
+    /// Commissioning flow type
+    pub flow: CommissioningFlow,
+    /// Vendor ID
+    pub vid: Option<u16>,
+    /// Product ID
+    pub pid: Option<u16>,
+    /// Addresses the device was already found at, if any
+    pub addresses: Vec<SocketAddr>,
+}
+
+impl CommissioningHandoff {
+    /// Adds addresses the device was discovered at, e.g. by an mDNS browse.
+    pub fn with_addresses(mut self, addresses: impl IntoIterator<Item = SocketAddr>) -> Self {
+        self.addresses.extend(addresses);
+        self
+    }
+
+    /// Returns `true` if the device may be commissioned over BLE.
+    ///
+    /// Manual codes do not carry discovery capabilities, so any transport is possible.
+    pub fn may_use_ble(&self) -> bool {
+        self.discovery
+            .is_none_or(|d| d.contains(DiscoveryCapabilities::BLE))
+    }
+
+    /// Returns `true` if the device may be found on the IP network.
+    pub fn may_use_network(&self) -> bool {
+        !self.addresses.is_empty()
+            || self
+                .discovery
+                .is_none_or(|d| d.contains(DiscoveryCapabilities::ON_NETWORK))
+    }
+
+    /// Builds the chip-tool `pairing` arguments commissioning this device as `node_id`.
+    ///
+    /// A known address is used directly (`pairing ethernet`); otherwise the
+    /// device is looked up on the network by discriminator. BLE pairing needs
+    /// network credentials this type does not hold, so it is left to the caller.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use matter_setup_code::SetupPayload;
+    /// use matter_setup_code::handoff::CommissioningHandoff;
+    ///
+    /// let payload = SetupPayload::parse_str("MT:-24J042C00KA0648G00").unwrap();
+    /// let handoff = CommissioningHandoff::from(&payload);
+    /// assert_eq!(
+    ///     handoff.chip_tool_args(1),
+    ///     ["pairing", "onnetwork-long", "1", "20202021", "3840"]
+    /// );
+    /// ```
+    pub fn chip_tool_args(&self, node_id: u64) -> Vec<String> {
+        let mut args = vec!["pairing".to_string()];
+        match (self.addresses.first(), self.discriminator) {
+            (Some(address), DiscriminatorFilter::Long(long)) => {
+                args.push("ethernet".to_string());
+                args.extend([
+                    node_id.to_string(),
+                    self.passcode.to_string(),
+                    long.to_string(),
+                    address.ip().to_string(),
+                    address.port().to_string(),
+                ]);
+            }
+            (_, DiscriminatorFilter::Long(long)) => {
+                args.push("onnetwork-long".to_string());
+                args.extend([
+                    node_id.to_string(),
+                    self.passcode.to_string(),
+                    long.to_string(),
+                ]);
+            }
+            (_, DiscriminatorFilter::Short(short)) => {
+                args.push("onnetwork-short".to_string());
+                args.extend([
+                    node_id.to_string(),
+                    self.passcode.to_string(),
+                    short.to_string(),
+                ]);
+            }
+        }
+        args
+    }
+}
+
+impl From<&SetupPayload> for CommissioningHandoff {
+    fn from(payload: &SetupPayload) -> Self {
+        CommissioningHandoff {
+            passcode: payload.pincode.get(),
+            discriminator: match payload.discriminator.long() {
+                Some(long) => DiscriminatorFilter::Long(long),
+                None => DiscriminatorFilter::Short(payload.discriminator.short()),
+            },
+            discovery: payload.discovery,
+            flow: payload.flow,
+            vid: payload.vid.map(VendorId::get),
+            pid: payload.pid.map(ProductId::get),
+            addresses: Vec::new(),
+        }
+    }
+}
+
+#[cfg(feature = "mdns")]
+impl CommissioningHandoff {
+    /// Adds the addresses of a node found by [`crate::mdns::find_commissionable`].
+    pub fn with_node(self, node: &crate::mdns::CommissionableNode) -> Self {
+        let port = node.port;
+        self.with_addresses(node.addresses.iter().map(|&ip| SocketAddr::new(ip, port)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_handoff_from_qr_code() {
+        let payload = SetupPayload::parse_str("MT:-24J042C00KA0648G00").unwrap();
+        let handoff = CommissioningHandoff::from(&payload);
+        assert_eq!(handoff.passcode, 20202021);
+        assert_eq!(handoff.discriminator, DiscriminatorFilter::Long(3840));
+        assert_eq!(handoff.vid, Some(0xFFF1));
+        assert!(handoff.may_use_ble());
+        assert!(!handoff.may_use_network());
+
+        let handoff = handoff.with_addresses(["192.168.1.20:5540".parse().unwrap()]);
+        assert!(handoff.may_use_network());
+        assert_eq!(
+            handoff.chip_tool_args(7),
+            [
+                "pairing",
+                "ethernet",
+                "7",
+                "20202021",
+                "3840",
+                "192.168.1.20",
+                "5540"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_handoff_from_manual_code() {
+        let payload = SetupPayload::parse_str("34970112332").unwrap();
+        let handoff = CommissioningHandoff::from(&payload);
+        assert_eq!(handoff.discriminator, DiscriminatorFilter::Short(15));
+        assert!(handoff.discriminator.matches(3840));
+        assert!(!handoff.discriminator.matches(0x0E00));
+        assert!(handoff.may_use_ble() && handoff.may_use_network());
+        assert_eq!(
+            handoff.chip_tool_args(1),
+            ["pairing", "onnetwork-short", "1", "20202021", "15"]
+        );
+    }
+}
@@ -0,0 +1,348 @@
+//! In-memory index of issued payloads for deduplication and lookup.
+//!
+//! QA tools checking an incoming shipment against previously issued codes
+//! need to answer "was this code already issued?" and "which devices use
+//! this discriminator?" quickly. [`PayloadIndex`] stores one [`IndexEntry`]
+//! per payload, with the passcode replaced by a hash so an exported index
+//! does not carry the passcodes themselves.
+//!
+//! The passcode space is small (27 bits), so the hash is a deduplication
+//! key, not a secret: anyone holding the index can brute-force it.
+
+use std::collections::HashMap;
+
+use crate::payload::{ProductId, SetupPayload, VendorId};
+
+/// What the index remembers about one payload.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IndexEntry {
+    /// Long discriminator (12 bits), if the payload carried one
+    pub long_discriminator: Option<u16>,
+    /// Short discriminator (4 bits)
+    pub short_discriminator: u8,
+    /// Hash of the setup PIN code, see [`passcode_hash`]
+    pub passcode_hash: u64,
+    /// Vendor ID
+    pub vid: Option<u16>,
+    /// Product ID
+    pub pid: Option<u16>,
+}
+
+impl IndexEntry {
+    /// Builds the index entry for `payload`.
+    pub fn new(payload: &SetupPayload) -> Self {
+        IndexEntry {
+            long_discriminator: payload.discriminator.long(),
+            short_discriminator: payload.discriminator.short(),
+            passcode_hash: passcode_hash(payload.pincode.get()),
+            vid: payload.vid.map(VendorId::get),
+            pid: payload.pid.map(ProductId::get),
+        }
+    }
+
+    /// Returns `true` if both entries may describe the same device.
+    ///
+    /// Fields missing on either side (a manual code has no long discriminator
+    /// and usually no VID/PID) are not compared.
+    fn matches(&self, other: &IndexEntry) -> bool {
+        fn agree<T: PartialEq>(a: Option<T>, b: Option<T>) -> bool {
+            match (a, b) {
+                (Some(a), Some(b)) => a == b,
+                _ => true,
+            }
+        }
+        self.short_discriminator == other.short_discriminator
+            && self.passcode_hash == other.passcode_hash
+            && agree(self.long_discriminator, other.long_discriminator)
+            && agree(self.vid, other.vid)
+            && agree(self.pid, other.pid)
+    }
+}
+
+/// Hashes a passcode with 64-bit FNV-1a over its little-endian bytes.
+///
+/// The algorithm is fixed so serialized indexes stay comparable across releases.
+pub fn passcode_hash(pincode: u32) -> u64 {
+    fnv1a(&pincode.to_le_bytes())
+}
+
+/// Version of the canonical form hashed by [`SetupPayload::stable_id`].
+///
+/// Bumped, with the previous form kept available, if that form ever changes.
+pub const STABLE_ID_VERSION: u8 = 1;
+
+impl SetupPayload {
+    /// Returns a deterministic identifier for this payload, usable as a
+    /// database key or deduplication token across services.
+    ///
+    /// The identifier is the 64-bit FNV-1a hash of this 15-byte canonical form
+    /// (version [`STABLE_ID_VERSION`]), multi-byte fields little-endian:
+    ///
+    /// | Bytes | Field |
+    /// |-------|-------|
+    /// | 0 | form version (`1`) |
+    /// | 1 | presence flags: bit 0 long discriminator, bit 1 discovery, bit 2 VID, bit 3 PID, bit 4 passcode |
+    /// | 2-3 | long discriminator |
+    /// | 4 | short discriminator |
+    /// | 5-8 | passcode |
+    /// | 9 | discovery capabilities |
+    /// | 10 | commissioning flow |
+    /// | 11-12 | VID |
+    /// | 13-14 | PID |
+    ///
+    /// Absent fields, and the passcode when `include_passcode` is `false`,
+    /// are written as zeros with their presence flag cleared. Excluding the
+    /// passcode gives an identifier that can be shared without leaking it,
+    /// though the passcode space is small enough to brute-force.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use matter_setup_code::SetupPayload;
+    ///
+    /// let payload = SetupPayload::parse_str("MT:-24J042C00KA0648G00").unwrap();
+    /// assert_eq!(payload.stable_id(true), 0xa8a9_8e14_087f_144a);
+    /// ```
+    pub fn stable_id(&self, include_passcode: bool) -> u64 {
+        let mut flags = 0u8;
+        let mut present = |bit: u8, is_present: bool| {
+            if is_present {
+                flags |= 1 << bit;
+            }
+        };
+        present(0, self.discriminator.long().is_some());
+        present(1, self.discovery.is_some());
+        present(2, self.vid.is_some());
+        present(3, self.pid.is_some());
+        present(4, include_passcode);
+
+        let passcode = if include_passcode {
+            self.pincode.get()
+        } else {
+            0
+        };
+        let mut form = Vec::with_capacity(15);
+        form.push(STABLE_ID_VERSION);
+        form.push(flags);
+        form.extend(self.discriminator.long().unwrap_or(0).to_le_bytes());
+        form.push(self.discriminator.short());
+        form.extend(passcode.to_le_bytes());
+        form.push(self.discovery.map_or(0, |d| d.bits()));
+        form.push(self.flow.bits());
+        form.extend(self.vid.map_or(0, VendorId::get).to_le_bytes());
+        form.extend(self.pid.map_or(0, ProductId::get).to_le_bytes());
+        fnv1a(&form)
+    }
+}
+
+/// 64-bit FNV-1a, the hash behind [`passcode_hash`] and [`SetupPayload::stable_id`].
+fn fnv1a(bytes: &[u8]) -> u64 {
+    const FNV_OFFSET_BASIS: u64 = 0xCBF2_9CE4_8422_2325;
+    const FNV_PRIME: u64 = 0x0000_0100_0000_01B3;
+
+    bytes.iter().fold(FNV_OFFSET_BASIS, |acc, &byte| {
+        (acc ^ byte as u64).wrapping_mul(FNV_PRIME)
+    })
+}
+
+/// A set of issued payloads, searchable by passcode and discriminator.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(from = "Vec<IndexEntry>", into = "Vec<IndexEntry>")
+)]
+pub struct PayloadIndex {
+    entries: Vec<IndexEntry>,
+    // (short discriminator, passcode hash) -> positions in `entries`
+    by_key: HashMap<(u8, u64), Vec<usize>>,
+}
+
+impl PayloadIndex {
+    /// Creates an empty index.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `payload` to the index.
+    ///
+    /// Returns `false` (and leaves the index unchanged) if a matching payload
+    /// was already indexed.
+    pub fn insert(&mut self, payload: &SetupPayload) -> bool {
+        let entry = IndexEntry::new(payload);
+        if self.find_entry(&entry).is_some() {
+            return false;
+        }
+        self.push(entry);
+        true
+    }
+
+    /// Returns `true` if a payload matching `payload` was indexed.
+    ///
+    /// A payload parsed from a manual code matches the QR code payload it
+    /// was derived from, since only the fields both carry are compared.
+    pub fn contains(&self, payload: &SetupPayload) -> bool {
+        self.find_entry(&IndexEntry::new(payload)).is_some()
+    }
+
+    /// Returns the entries whose long discriminator is `discriminator`.
+    pub fn find_by_discriminator(&self, discriminator: u16) -> impl Iterator<Item = &IndexEntry> {
+        self.entries
+            .iter()
+            .filter(move |e| e.long_discriminator == Some(discriminator))
+    }
+
+    /// Returns the entries whose short discriminator is `short_discriminator`.
+    pub fn find_by_short_discriminator(
+        &self,
+        short_discriminator: u8,
+    ) -> impl Iterator<Item = &IndexEntry> {
+        self.entries
+            .iter()
+            .filter(move |e| e.short_discriminator == short_discriminator)
+    }
+
+    /// Returns an iterator over all entries, in insertion order.
+    pub fn iter(&self) -> impl Iterator<Item = &IndexEntry> {
+        self.entries.iter()
+    }
+
+    /// Number of indexed payloads.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the index is empty.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn find_entry(&self, entry: &IndexEntry) -> Option<&IndexEntry> {
+        self.by_key
+            .get(&(entry.short_discriminator, entry.passcode_hash))?
+            .iter()
+            .map(|&i| &self.entries[i])
+            .find(|candidate| candidate.matches(entry))
+    }
+
+    fn push(&mut self, entry: IndexEntry) {
+        self.by_key
+            .entry((entry.short_discriminator, entry.passcode_hash))
+            .or_default()
+            .push(self.entries.len());
+        self.entries.push(entry);
+    }
+}
+
+impl From<Vec<IndexEntry>> for PayloadIndex {
+    fn from(entries: Vec<IndexEntry>) -> Self {
+        let mut index = PayloadIndex::new();
+        for entry in entries {
+            index.push(entry);
+        }
+        index
+    }
+}
+
+impl From<PayloadIndex> for Vec<IndexEntry> {
+    fn from(index: PayloadIndex) -> Self {
+        index.entries
+    }
+}
+
+impl<'a> FromIterator<&'a SetupPayload> for PayloadIndex {
+    fn from_iter<I: IntoIterator<Item = &'a SetupPayload>>(iter: I) -> Self {
+        let mut index = PayloadIndex::new();
+        for payload in iter {
+            index.insert(payload);
+        }
+        index
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::payload::Passcode;
+
+    fn qr_payload(discriminator: u16, pincode: u32) -> SetupPayload {
+        SetupPayload::new(
+            discriminator,
+            Passcode::new(pincode).unwrap(),
+            Some(4),
+            None,
+            Some(0xFFF1),
+            Some(0x8000),
+        )
+    }
+
+    #[test]
+    fn test_insert_and_contains() {
+        let mut index = PayloadIndex::new();
+        assert!(index.insert(&qr_payload(1132, 69414998)));
+        assert!(index.insert(&qr_payload(1133, 69414998)));
+        assert!(!index.insert(&qr_payload(1132, 69414998)));
+        assert_eq!(index.len(), 2);
+
+        assert!(index.contains(&qr_payload(1133, 69414998)));
+        assert!(!index.contains(&qr_payload(1132, 20202021)));
+        // Same short discriminator and passcode, but a different long discriminator.
+        assert!(!index.contains(&qr_payload(1134, 69414998)));
+    }
+
+    #[test]
+    fn test_manual_code_matches_issued_qr_code() {
+        let index: PayloadIndex = [qr_payload(1132, 69414998)].iter().collect();
+        let manual = SetupPayload::parse_str("11237442363").unwrap();
+        assert!(index.contains(&manual));
+    }
+
+    #[test]
+    fn test_find_by_discriminator() {
+        let payloads = [
+            qr_payload(1132, 1),
+            qr_payload(1132, 2),
+            qr_payload(1200, 3),
+        ];
+        let index: PayloadIndex = payloads.iter().collect();
+        assert_eq!(index.find_by_discriminator(1132).count(), 2);
+        assert_eq!(index.find_by_discriminator(42).count(), 0);
+        assert_eq!(index.find_by_short_discriminator(4).count(), 3);
+    }
+
+    #[test]
+    fn test_stable_id() {
+        // Pinned values: a change here breaks identifiers stored by users.
+        let payload = SetupPayload::parse_str("MT:-24J042C00KA0648G00").unwrap();
+        assert_eq!(payload.stable_id(true), 0xa8a9_8e14_087f_144a);
+        assert_eq!(payload.stable_id(false), 0xbe75_c408_8c9d_c04a);
+
+        let other = SetupPayload {
+            pincode: Passcode::new(20202022).unwrap(),
+            ..payload.clone()
+        };
+        assert_ne!(other.stable_id(true), payload.stable_id(true));
+        assert_eq!(other.stable_id(false), payload.stable_id(false));
+
+        // A missing field differs from one present with value zero.
+        let no_vid = SetupPayload {
+            vid: None,
+            ..payload.clone()
+        };
+        let zero_vid = SetupPayload {
+            vid: Some(VendorId::UNASSIGNED),
+            ..payload
+        };
+        assert_ne!(no_vid.stable_id(true), zero_vid.stable_id(true));
+    }
+
+    #[test]
+    fn test_rebuild_from_entries() {
+        let index: PayloadIndex = [qr_payload(1132, 1), qr_payload(1200, 3)].iter().collect();
+        let entries: Vec<IndexEntry> = index.clone().into();
+        let rebuilt = PayloadIndex::from(entries);
+        assert_eq!(rebuilt, index);
+        assert!(rebuilt.contains(&qr_payload(1200, 3)));
+    }
+}
@@ -0,0 +1,168 @@
+//! Streaming JSON Lines import and export of payload inventories.
+//!
+//! Inventory files exchanged between factories and cloud backends can reach
+//! several gigabytes, so payloads are read and written one line at a time
+//! instead of as a single JSON document. A malformed line is reported with
+//! its line number and reading carries on with the next one.
+
+use std::io::{BufRead, Write};
+
+use crate::error::{JsonlError, Result};
+use crate::payload::SetupPayload;
+
+/// An iterator over the payloads of a JSON Lines stream, created by [`read_jsonl`].
+#[derive(Debug)]
+pub struct JsonlReader<R> {
+    reader: R,
+    line: usize,
+    buffer: String,
+    failed: bool,
+}
+
+/// Reads payloads from a JSON Lines stream, one JSON object per line.
+///
+/// Blank lines are skipped. Each item is either a payload or the error for
+/// that line; an I/O error ends the iteration after being reported.
+///
+/// # Example
+///
+/// ```
+/// use matter_setup_code::io::read_jsonl;
+///
+/// let input = r#"{"discriminator":{"long":1132},"pincode":69414998,"discovery":4,"flow":"standard","vid":65521,"pid":32768}
+/// not json
+/// "#;
+/// let results: Vec<_> = read_jsonl(input.as_bytes()).collect();
+/// assert_eq!(results.len(), 2);
+/// assert!(results[0].is_ok());
+/// assert!(results[1].is_err());
+/// ```
+pub fn read_jsonl<R: BufRead>(reader: R) -> JsonlReader<R> {
+    JsonlReader {
+        reader,
+        line: 0,
+        buffer: String::new(),
+        failed: false,
+    }
+}
+
+impl<R: BufRead> Iterator for JsonlReader<R> {
+    type Item = Result<SetupPayload>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        loop {
+            self.buffer.clear();
+            self.line += 1;
+            match self.reader.read_line(&mut self.buffer) {
+                Ok(0) => return None,
+                Ok(_) => {}
+                Err(e) => {
+                    // Stop after an I/O error: the stream position is unknown.
+                    self.failed = true;
+                    return Some(Err(JsonlError::Io(e.to_string()).into()));
+                }
+            }
+
+            let line = self.buffer.trim();
+            if line.is_empty() {
+                continue;
+            }
+            return Some(serde_json::from_str(line).map_err(|e| {
+                JsonlError::InvalidLine {
+                    line: self.line,
+                    message: e.to_string(),
+                }
+                .into()
+            }));
+        }
+    }
+}
+
+impl<R> JsonlReader<R> {
+    /// The 1-based number of the last line read.
+    pub fn line_number(&self) -> usize {
+        self.line
+    }
+}
+
+/// Writes `payloads` to `writer` as JSON Lines, returning the number of lines written.
+///
+/// # Errors
+///
+/// Returns `JsonlError::Io` if writing fails.
+pub fn write_jsonl<'a, W: Write>(
+    mut writer: W,
+    payloads: impl IntoIterator<Item = &'a SetupPayload>,
+) -> Result<usize> {
+    let mut count = 0;
+    for payload in payloads {
+        serde_json::to_writer(&mut writer, payload).map_err(|e| JsonlError::Io(e.to_string()))?;
+        writer
+            .write_all(b"\n")
+            .map_err(|e| JsonlError::Io(e.to_string()))?;
+        count += 1;
+    }
+    writer.flush().map_err(|e| JsonlError::Io(e.to_string()))?;
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MatterPayloadError;
+    use crate::payload::{CommissioningFlow, Passcode};
+
+    fn payloads() -> Vec<SetupPayload> {
+        vec![
+            SetupPayload::new(
+                1132,
+                Passcode::new(69414998).unwrap(),
+                Some(4),
+                None,
+                Some(0xFFF1),
+                Some(0x8000),
+            ),
+            SetupPayload::new(
+                3840,
+                Passcode::new(20202021).unwrap(),
+                Some(2),
+                Some(CommissioningFlow::Custom),
+                Some(0xFFF2),
+                Some(0x8001),
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_jsonl_roundtrip() {
+        let mut buffer = Vec::new();
+        assert_eq!(write_jsonl(&mut buffer, &payloads()).unwrap(), 2);
+        assert_eq!(buffer.iter().filter(|&&b| b == b'\n').count(), 2);
+
+        let read: Vec<_> = read_jsonl(buffer.as_slice())
+            .collect::<Result<_>>()
+            .unwrap();
+        assert_eq!(read, payloads());
+    }
+
+    #[test]
+    fn test_jsonl_recovers_from_bad_lines() {
+        let mut buffer = Vec::new();
+        write_jsonl(&mut buffer, &payloads()[..1]).unwrap();
+        buffer.extend_from_slice(b"\n{\"pincode\": 1}\n");
+        write_jsonl(&mut buffer, &payloads()[1..]).unwrap();
+
+        let mut reader = read_jsonl(buffer.as_slice());
+        assert_eq!(reader.next().unwrap().unwrap(), payloads()[0]);
+        assert!(matches!(
+            reader.next().unwrap().unwrap_err(),
+            MatterPayloadError::Jsonl(JsonlError::InvalidLine { line: 3, .. })
+        ));
+        assert_eq!(reader.next().unwrap().unwrap(), payloads()[1]);
+        assert_eq!(reader.line_number(), 4);
+        assert!(reader.next().is_none());
+    }
+}
@@ -1,8 +1,42 @@
 mod error;
 mod payload;
-mod base38;
-mod verhoeff;
+pub mod basen;
+pub mod base38;
+pub mod verhoeff;
 mod bit_utils;
+pub mod spec;
+pub mod entropy;
+pub mod assignment;
+pub mod fleet;
+pub mod index;
+pub mod handoff;
+pub mod report;
+pub mod compat;
+pub mod entry;
+pub mod validation;
+pub mod tlv;
+pub mod symbol;
+pub mod ndef;
+pub mod repair;
+#[cfg(feature = "profiles")]
+pub mod profile;
+#[cfg(feature = "crypto")]
+pub mod sealed;
+#[cfg(feature = "jsonl")]
+pub mod io;
+#[cfg(feature = "fixtures")]
+pub mod fixtures;
+#[cfg(feature = "ble-scan")]
+pub mod ble;
+#[cfg(feature = "mdns")]
+pub mod mdns;
+#[cfg(feature = "cache")]
+pub mod cache;
+#[cfg(feature = "render")]
+pub mod render;
+#[cfg(feature = "qr-scan")]
+pub mod scan;
 
 pub use error::{MatterPayloadError, Result};
-pub use payload::{SetupPayload, CommissioningFlow};
\ No newline at end of file
+pub use payload::{SetupPayload, SetupPayloadBuilder, Discriminator, short_discriminator_from_long, CommissioningFlow, DiscoveryCapabilities, ManualCode, ManualCodeLength, ManualCodeNumber, ManualCodeOptions, OptionalDataElement, Passcode, QrCodeString, SerialNumber, VendorId, ProductId, Defect, PartialParse, FieldDiff, FieldExplanation, FieldName, FieldValue, ParseOptions, ParsedPayload, PayloadSource, PayloadTemplate, VendorElement};
+pub use tlv::TlvValue;
\ No newline at end of file
@@ -0,0 +1,210 @@
+//! Finding commissionable nodes on the local network.
+//!
+//! Devices already on the IP network advertise the `_matterc._udp` DNS-SD
+//! service while waiting to be commissioned, with their discriminator and
+//! VID/PID in the TXT record. [`CommissionableTxt`] parses that record, and
+//! [`find_commissionable`] browses the network and returns the nodes
+//! matching a [`SetupPayload`]: the discovery half of a minimal commissioner.
+
+use std::collections::HashSet;
+use std::net::IpAddr;
+use std::time::Duration;
+
+use mdns_sd::{ServiceDaemon, ServiceEvent};
+
+use crate::error::{MdnsError, Result};
+use crate::payload::{ProductId, SetupPayload, VendorId};
+
+/// DNS-SD service type of commissionable nodes.
+pub const COMMISSIONABLE_SERVICE_TYPE: &str = "_matterc._udp.local.";
+
+/// The fields of a commissionable node's TXT record used to match a payload.
+///
+/// Keys this crate does not use (device name, pairing hint, ...) are skipped.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CommissionableTxt {
+    /// Long discriminator (12 bits), from the `D` key
+    pub discriminator: Option<u16>,
+    /// Vendor ID, from the `VP` key
+    pub vid: Option<u16>,
+    /// Product ID, from the `VP` key (`vid+pid`)
+    pub pid: Option<u16>,
+    /// Commissioning mode, from the `CM` key
+    pub commissioning_mode: Option<u8>,
+}
+
+impl CommissionableTxt {
+    /// Parses TXT record entries given as key/value pairs.
+    ///
+    /// Keys are matched case-insensitively. A value that does not parse
+    /// leaves its field unset rather than failing the whole record, as
+    /// devices in the field do not always follow the specification.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use matter_setup_code::mdns::CommissionableTxt;
+    ///
+    /// let txt = CommissionableTxt::parse([("D", "3840"), ("VP", "65521+32769"), ("CM", "1")]);
+    /// assert_eq!(txt.discriminator, Some(3840));
+    /// assert_eq!(txt.vid, Some(0xFFF1));
+    /// assert_eq!(txt.pid, Some(0x8001));
+    /// ```
+    pub fn parse<'a>(entries: impl IntoIterator<Item = (&'a str, &'a str)>) -> Self {
+        let mut txt = CommissionableTxt::default();
+        for (key, value) in entries {
+            if key.eq_ignore_ascii_case("D") {
+                txt.discriminator = value.parse().ok().filter(|&d: &u16| d <= 0x0FFF);
+            } else if key.eq_ignore_ascii_case("VP") {
+                // The PID is optional: "65521" and "65521+32769" are both valid.
+                let mut parts = value.splitn(2, '+');
+                txt.vid = parts.next().and_then(|vid| vid.parse().ok());
+                txt.pid = parts.next().and_then(|pid| pid.parse().ok());
+            } else if key.eq_ignore_ascii_case("CM") {
+                txt.commissioning_mode = value.parse().ok();
+            }
+        }
+        txt
+    }
+
+    /// Returns `true` if this record may belong to the device `payload` describes.
+    ///
+    /// The discriminator must be present and match; VID and PID are only
+    /// compared when both the payload and the record carry them.
+    pub fn matches(&self, payload: &SetupPayload) -> bool {
+        fn agree(a: Option<u16>, b: Option<u16>) -> bool {
+            match (a, b) {
+                (Some(a), Some(b)) => a == b,
+                _ => true,
+            }
+        }
+        self.discriminator
+            .is_some_and(|d| payload.matches_discriminator(d))
+            && agree(self.vid, payload.vid.map(VendorId::get))
+            && agree(self.pid, payload.pid.map(ProductId::get))
+    }
+}
+
+/// A commissionable node found by [`find_commissionable`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommissionableNode {
+    /// Full DNS-SD instance name
+    pub instance: String,
+    /// Host name the service points to
+    pub hostname: String,
+    /// Resolved addresses of the host
+    pub addresses: Vec<IpAddr>,
+    /// UDP port of the commissioning service
+    pub port: u16,
+    /// Parsed TXT record
+    pub txt: CommissionableTxt,
+}
+
+/// Browses `_matterc._udp` for `timeout` and returns the nodes matching `payload`.
+///
+/// The browse runs on the mdns-sd daemon thread, so this future works on any
+/// async runtime. Each node is returned once, in the order it was resolved.
+///
+/// # Errors
+///
+/// Returns `MdnsError::Daemon` if the mDNS daemon cannot be started or
+/// refuses to browse.
+pub async fn find_commissionable(
+    payload: &SetupPayload,
+    timeout: Duration,
+) -> Result<Vec<CommissionableNode>> {
+    let daemon = ServiceDaemon::new().map_err(daemon_error)?;
+    let receiver = daemon
+        .browse(COMMISSIONABLE_SERVICE_TYPE)
+        .map_err(daemon_error)?;
+
+    // Stopping the browse makes the daemon send `SearchStopped`, which ends
+    // the loop below without needing a runtime-specific timer.
+    let stopper = daemon.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(timeout);
+        let _ = stopper.stop_browse(COMMISSIONABLE_SERVICE_TYPE);
+    });
+
+    let mut seen = HashSet::new();
+    let mut nodes = Vec::new();
+    while let Ok(event) = receiver.recv_async().await {
+        match event {
+            ServiceEvent::ServiceResolved(info) => {
+                let txt = CommissionableTxt::parse(
+                    info.get_properties()
+                        .iter()
+                        .map(|property| (property.key(), property.val_str())),
+                );
+                if txt.matches(payload) && seen.insert(info.get_fullname().to_string()) {
+                    nodes.push(CommissionableNode {
+                        instance: info.get_fullname().to_string(),
+                        hostname: info.get_hostname().to_string(),
+                        addresses: info.get_addresses().iter().copied().collect(),
+                        port: info.get_port(),
+                        txt,
+                    });
+                }
+            }
+            ServiceEvent::SearchStopped(_) => break,
+            _ => {}
+        }
+    }
+
+    let _ = daemon.shutdown();
+    Ok(nodes)
+}
+
+fn daemon_error(e: mdns_sd::Error) -> MdnsError {
+    MdnsError::Daemon(e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_txt() {
+        let txt = CommissionableTxt::parse([
+            ("D", "3840"),
+            ("vp", "65521+32769"),
+            ("CM", "1"),
+            ("DN", "Kitchen light"),
+        ]);
+        assert_eq!(
+            txt,
+            CommissionableTxt {
+                discriminator: Some(3840),
+                vid: Some(0xFFF1),
+                pid: Some(0x8001),
+                commissioning_mode: Some(1),
+            }
+        );
+
+        // VID without PID, out-of-range discriminator.
+        let txt = CommissionableTxt::parse([("D", "4096"), ("VP", "65521")]);
+        assert_eq!(txt.discriminator, None);
+        assert_eq!(txt.vid, Some(0xFFF1));
+        assert_eq!(txt.pid, None);
+    }
+
+    #[test]
+    fn test_txt_matches_payload() {
+        let qr = SetupPayload::parse_str("MT:-24J042C00KA0648G00").unwrap();
+        let txt = CommissionableTxt::parse([("D", "3840"), ("VP", "65521+32769")]);
+        assert!(txt.matches(&qr));
+
+        let vid_only = CommissionableTxt::parse([("D", "3840"), ("VP", "65521")]);
+        assert!(vid_only.matches(&qr));
+
+        let other_vendor = CommissionableTxt::parse([("D", "3840"), ("VP", "65522+32769")]);
+        assert!(!other_vendor.matches(&qr));
+
+        // Without a discriminator there is nothing to match on.
+        assert!(!CommissionableTxt::parse([("VP", "65521+32769")]).matches(&qr));
+
+        let manual = SetupPayload::parse_str("34970112332").unwrap();
+        assert!(CommissionableTxt::parse([("D", "3841")]).matches(&manual));
+        assert!(!CommissionableTxt::parse([("D", "3584")]).matches(&manual));
+    }
+}
@@ -0,0 +1,352 @@
+//! NFC Data Exchange Format (NDEF) messages carrying onboarding payloads.
+//!
+//! A device supporting NFC onboarding exposes its QR code string on a tag,
+//! as the URI of an NDEF well-known URI record ("U"). The string is stored
+//! whole, with the "MT:" scheme and no URI identifier abbreviation, since
+//! none of the abbreviated prefixes matches it.
+//!
+//! Tags may hold other records next to it, such as a product page URL;
+//! [`SetupPayload::from_ndef_message`] picks the onboarding record out.
+//!
+//! [`type2_tag_image`] lays a message out in the memory of an NFC Forum
+//! Type 2 tag, such as the NTAG21x family, for factory writers to program.
+
+use crate::error::{NdefError, Result};
+use crate::payload::SetupPayload;
+
+/// Message begin flag of a record header.
+const MB: u8 = 0x80;
+/// Message end flag of a record header.
+const ME: u8 = 0x40;
+/// Chunk flag of a record header.
+const CF: u8 = 0x20;
+/// Short record flag of a record header: the payload length takes 1 byte.
+const SR: u8 = 0x10;
+/// ID length flag of a record header: the record has an ID.
+const IL: u8 = 0x08;
+/// Type name format bits of a record header.
+const TNF_MASK: u8 = 0x07;
+/// Type name format of NFC Forum well-known types.
+const TNF_WELL_KNOWN: u8 = 0x01;
+
+/// Type of the well-known URI record.
+const URI_RECORD_TYPE: &[u8] = b"U";
+/// URI identifier code for a URI stored without abbreviation.
+const URI_IDENTIFIER_NONE: u8 = 0x00;
+
+/// Magic number opening the capability container of a Type 2 tag.
+const CC_MAGIC: u8 = 0xE1;
+/// Type 2 tag mapping version 1.0.
+const CC_VERSION: u8 = 0x10;
+/// Read and write access granted without restriction.
+const CC_ACCESS_OPEN: u8 = 0x00;
+/// Largest data area a capability container can describe, in bytes.
+const MAX_DATA_AREA_SIZE: usize = 8 * u8::MAX as usize;
+
+/// Type of the TLV block holding an NDEF message.
+const NDEF_MESSAGE_TLV: u8 = 0x03;
+/// Type of the TLV block ending the data area's contents.
+const TERMINATOR_TLV: u8 = 0xFE;
+/// Length byte announcing a 2-byte TLV length.
+const TLV_LONG_LENGTH: u8 = 0xFF;
+
+/// Scheme of an onboarding URI.
+const ONBOARDING_URI_PREFIX: &str = "MT:";
+
+/// A record of an NDEF message, borrowed from it.
+struct Record<'a> {
+    tnf: u8,
+    record_type: &'a [u8],
+    payload: &'a [u8],
+}
+
+impl Record<'_> {
+    /// The URI of a well-known URI record stored unabbreviated, if this is
+    /// one.
+    fn uri(&self) -> Option<&str> {
+        if self.tnf != TNF_WELL_KNOWN || self.record_type != URI_RECORD_TYPE {
+            return None;
+        }
+        match self.payload.split_first() {
+            Some((&URI_IDENTIFIER_NONE, uri)) => std::str::from_utf8(uri).ok(),
+            _ => None,
+        }
+    }
+}
+
+/// Splits an NDEF message into its records, up to the one flagged as the
+/// message end.
+fn records(message: &[u8]) -> Result<Vec<Record<'_>>> {
+    let mut records = Vec::new();
+    let mut offset = 0;
+    while offset < message.len() {
+        let start = offset;
+        let truncated = || NdefError::Truncated { offset: start };
+        let mut take = |len: usize| {
+            let bytes = message.get(offset..offset + len).ok_or_else(truncated)?;
+            offset += len;
+            Ok::<_, NdefError>(bytes)
+        };
+
+        let header = take(1)?[0];
+        if header & CF != 0 {
+            return Err(NdefError::ChunkedRecord { offset: start }.into());
+        }
+        let type_len = usize::from(take(1)?[0]);
+        let payload_len = if header & SR != 0 {
+            usize::from(take(1)?[0])
+        } else {
+            let bytes = take(4)?;
+            u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize
+        };
+        let id_len = if header & IL != 0 {
+            usize::from(take(1)?[0])
+        } else {
+            0
+        };
+        let record_type = take(type_len)?;
+        take(id_len)?;
+        let payload = take(payload_len)?;
+
+        records.push(Record {
+            tnf: header & TNF_MASK,
+            record_type,
+            payload,
+        });
+        if header & ME != 0 {
+            break;
+        }
+    }
+    Ok(records)
+}
+
+/// Encodes an NDEF message made of a single URI record holding `uri`
+/// unabbreviated.
+///
+/// # Example
+///
+/// ```
+/// use matter_setup_code::ndef::uri_message;
+///
+/// assert_eq!(uri_message("MT:X"), [0xD1, 0x01, 0x05, b'U', 0x00, b'M', b'T', b':', b'X']);
+/// ```
+pub fn uri_message(uri: &str) -> Vec<u8> {
+    let payload_len = 1 + uri.len();
+    let mut message = Vec::with_capacity(payload_len + 7);
+    let short = payload_len <= usize::from(u8::MAX);
+    let flags = if short { MB | ME | SR } else { MB | ME };
+    message.push(flags | TNF_WELL_KNOWN);
+    message.push(URI_RECORD_TYPE.len() as u8);
+    if short {
+        message.push(payload_len as u8);
+    } else {
+        message.extend_from_slice(&(payload_len as u32).to_be_bytes());
+    }
+    message.extend_from_slice(URI_RECORD_TYPE);
+    message.push(URI_IDENTIFIER_NONE);
+    message.extend_from_slice(uri.as_bytes());
+    message
+}
+
+/// Lays out `message` in the memory of an NFC Forum Type 2 tag whose data
+/// area holds `data_area_size` bytes, e.g. 144 for an NTAG213, 496 for an
+/// NTAG215 and 872 for an NTAG216.
+///
+/// The image starts at page 3, with the capability container: pages 0 to 2
+/// hold the tag's serial number and static lock bytes, which it sets
+/// itself. The data area follows, holding an NDEF message TLV block and a
+/// terminator TLV block, padded with zeros. Tags keeping their dynamic lock
+/// bits at the default location need no lock control TLV block.
+///
+/// # Errors
+///
+/// Returns `NdefError::InvalidDataAreaSize` if `data_area_size` is not a
+/// nonzero multiple of 8 up to 2040, or `NdefError::TagTooSmall` if the
+/// message does not fit.
+///
+/// # Example
+///
+/// ```
+/// use matter_setup_code::ndef::{type2_tag_image, uri_message};
+///
+/// let image = type2_tag_image(&uri_message("MT:X"), 48).unwrap();
+/// assert_eq!(image.len(), 4 + 48);
+/// assert_eq!(image[..6], [0xE1, 0x10, 0x06, 0x00, 0x03, 0x09]);
+/// ```
+pub fn type2_tag_image(message: &[u8], data_area_size: usize) -> Result<Vec<u8>> {
+    if data_area_size == 0
+        || !data_area_size.is_multiple_of(8)
+        || data_area_size > MAX_DATA_AREA_SIZE
+    {
+        return Err(NdefError::InvalidDataAreaSize(data_area_size).into());
+    }
+
+    let mut data_area = vec![NDEF_MESSAGE_TLV];
+    match u8::try_from(message.len()) {
+        Ok(len) if len != TLV_LONG_LENGTH => data_area.push(len),
+        _ => {
+            data_area.push(TLV_LONG_LENGTH);
+            data_area.extend_from_slice(&(message.len() as u16).to_be_bytes());
+        }
+    }
+    data_area.extend_from_slice(message);
+    data_area.push(TERMINATOR_TLV);
+    if data_area.len() > data_area_size {
+        return Err(NdefError::TagTooSmall {
+            needed: data_area.len(),
+            available: data_area_size,
+        }
+        .into());
+    }
+    data_area.resize(data_area_size, 0x00);
+
+    let mut image = vec![
+        CC_MAGIC,
+        CC_VERSION,
+        (data_area_size / 8) as u8,
+        CC_ACCESS_OPEN,
+    ];
+    image.append(&mut data_area);
+    Ok(image)
+}
+
+impl SetupPayload {
+    /// Encodes the NDEF message to write to an NFC tag for onboarding: a
+    /// single URI record holding the payload's QR code string.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`SetupPayload::to_qr_code_str`].
+    pub fn to_ndef_message(&self) -> Result<Vec<u8>> {
+        Ok(uri_message(&self.to_qr_code_str()?))
+    }
+
+    /// Parses the onboarding payload of an NDEF message read from an NFC
+    /// tag: the URI record whose URI starts with "MT:". Other records are
+    /// ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NdefError::Truncated` or `NdefError::ChunkedRecord` if the
+    /// message cannot be split into records, `NdefError::NoOnboardingRecord`
+    /// or `NdefError::DuplicateOnboardingRecord` unless exactly one record
+    /// is an onboarding record, or the error parsing its URI.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use matter_setup_code::SetupPayload;
+    ///
+    /// let payload = SetupPayload::parse_str("MT:-24J042C00KA0648G00").unwrap();
+    /// let message = payload.to_ndef_message().unwrap();
+    /// assert_eq!(SetupPayload::from_ndef_message(&message).unwrap(), payload);
+    /// ```
+    pub fn from_ndef_message(message: &[u8]) -> Result<SetupPayload> {
+        let records = records(message)?;
+        let mut uris = records
+            .iter()
+            .filter_map(Record::uri)
+            .filter(|uri| uri.starts_with(ONBOARDING_URI_PREFIX));
+        let uri = uris.next().ok_or(NdefError::NoOnboardingRecord)?;
+        if uris.next().is_some() {
+            return Err(NdefError::DuplicateOnboardingRecord.into());
+        }
+        SetupPayload::parse_str(uri)
+    }
+
+    /// Lays out the payload's NDEF message in the memory of an NFC Forum
+    /// Type 2 tag, as [`type2_tag_image`] does.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of [`SetupPayload::to_ndef_message`] and
+    /// [`type2_tag_image`].
+    pub fn to_type2_tag_image(&self, data_area_size: usize) -> Result<Vec<u8>> {
+        type2_tag_image(&self.to_ndef_message()?, data_area_size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MatterPayloadError;
+
+    #[test]
+    fn test_ndef_message() {
+        let payload = SetupPayload::parse_str("MT:-24J042C00KA0648G00").unwrap();
+        let message = payload.to_ndef_message().unwrap();
+        assert_eq!(message[..5], [0xD1, 0x01, 0x17, b'U', 0x00]);
+        assert_eq!(&message[5..], b"MT:-24J042C00KA0648G00");
+
+        // A 300-byte URI needs a long record, its length on 4 bytes.
+        let message = uri_message(&"X".repeat(300));
+        assert_eq!(message[..7], [0xC1, 0x01, 0x00, 0x00, 0x01, 0x2D, b'U']);
+        assert_eq!(message.len(), 7 + 301);
+    }
+
+    #[test]
+    fn test_from_ndef_message() {
+        let payload = SetupPayload::parse_str("MT:-24J042C00KA0648G00").unwrap();
+        let onboarding = payload.to_ndef_message().unwrap();
+        // "https://example.com", abbreviated, with an ID, as the first
+        // record of a two-record message.
+        let mut message = vec![0x99, 0x01, 0x0C, 0x01, b'U', b'#', 0x04];
+        message.extend_from_slice(b"example.com");
+        let mut last = onboarding.clone();
+        last[0] &= !MB;
+        message.extend_from_slice(&last);
+        assert_eq!(SetupPayload::from_ndef_message(&message).unwrap(), payload);
+
+        let mut duplicate = onboarding.clone();
+        duplicate[0] &= !ME;
+        duplicate.extend_from_slice(&last);
+        assert_eq!(
+            SetupPayload::from_ndef_message(&duplicate).unwrap_err(),
+            MatterPayloadError::Ndef(NdefError::DuplicateOnboardingRecord)
+        );
+        assert_eq!(
+            SetupPayload::from_ndef_message(&uri_message("https://example.com")).unwrap_err(),
+            MatterPayloadError::Ndef(NdefError::NoOnboardingRecord)
+        );
+        assert_eq!(
+            SetupPayload::from_ndef_message(&onboarding[..10]).unwrap_err(),
+            MatterPayloadError::Ndef(NdefError::Truncated { offset: 0 })
+        );
+        assert_eq!(
+            SetupPayload::from_ndef_message(&[0xF1, 0x01, 0x01, b'U', 0x00]).unwrap_err(),
+            MatterPayloadError::Ndef(NdefError::ChunkedRecord { offset: 0 })
+        );
+    }
+
+    #[test]
+    fn test_type2_tag_image() {
+        let payload = SetupPayload::parse_str("MT:-24J042C00KA0648G00").unwrap();
+        let message = payload.to_ndef_message().unwrap();
+        let image = payload.to_type2_tag_image(144).unwrap();
+        assert_eq!(image.len(), 148);
+        assert_eq!(image[..6], [0xE1, 0x10, 0x12, 0x00, 0x03, 0x1B]);
+        assert_eq!(image[6..33], message);
+        assert_eq!(image[33], 0xFE);
+        assert!(image[34..].iter().all(|&byte| byte == 0));
+
+        // A 300-byte message takes a 3-byte TLV length.
+        let long = uri_message(&"X".repeat(300));
+        let image = type2_tag_image(&long, 496).unwrap();
+        assert_eq!(image[4..8], [0x03, 0xFF, 0x01, 0x34]);
+        assert_eq!(image[8 + long.len()], 0xFE);
+
+        assert_eq!(
+            type2_tag_image(&long, 312).unwrap_err(),
+            MatterPayloadError::Ndef(NdefError::TagTooSmall {
+                needed: 313,
+                available: 312,
+            })
+        );
+        for size in [0, 100, 2048] {
+            assert_eq!(
+                type2_tag_image(&message, size).unwrap_err(),
+                MatterPayloadError::Ndef(NdefError::InvalidDataAreaSize(size))
+            );
+        }
+    }
+}
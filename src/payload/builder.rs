@@ -0,0 +1,241 @@
+//! A validating builder for [`SetupPayload`].
+
+use super::{
+    CommissioningFlow, DiscoveryCapabilities, Discriminator, OptionalDataElement, Passcode,
+    ProductId, SetupPayload, VendorId,
+};
+use crate::error::{PayloadError, Result};
+
+/// Builds a [`SetupPayload`] field by field, checking every value in [`build`](Self::build).
+///
+/// The discriminator and passcode are required; the flow defaults to
+/// [`CommissioningFlow::Standard`] and the other fields to absent.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SetupPayloadBuilder {
+    discriminator: Option<u16>,
+    passcode: Option<u32>,
+    vid: Option<VendorId>,
+    pid: Option<ProductId>,
+    discovery: Option<DiscoveryCapabilities>,
+    flow: CommissioningFlow,
+    optional_data: Vec<OptionalDataElement>,
+}
+
+impl SetupPayloadBuilder {
+    /// Creates a builder with no field set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the 12-bit discriminator.
+    pub fn discriminator(mut self, discriminator: u16) -> Self {
+        self.discriminator = Some(discriminator);
+        self
+    }
+
+    /// Sets the setup passcode.
+    pub fn passcode(mut self, passcode: u32) -> Self {
+        self.passcode = Some(passcode);
+        self
+    }
+
+    /// Sets the vendor ID.
+    pub fn vendor_id(mut self, vid: u16) -> Self {
+        self.vid = Some(VendorId::new(vid));
+        self
+    }
+
+    /// Sets the product ID.
+    pub fn product_id(mut self, pid: u16) -> Self {
+        self.pid = Some(ProductId::new(pid));
+        self
+    }
+
+    /// Sets the discovery capabilities.
+    pub fn discovery(mut self, discovery: DiscoveryCapabilities) -> Self {
+        self.discovery = Some(discovery);
+        self
+    }
+
+    /// Sets the commissioning flow.
+    pub fn flow(mut self, flow: CommissioningFlow) -> Self {
+        self.flow = flow;
+        self
+    }
+
+    /// Appends an element to the optional data of the QR code.
+    pub fn optional_data(mut self, element: OptionalDataElement) -> Self {
+        self.optional_data.push(element);
+        self
+    }
+
+    /// Checks every field and produces the payload.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PayloadError::MissingDiscriminator` or
+    /// `PayloadError::MissingPasscode` if a required field was not set,
+    /// `PayloadError::DiscriminatorTooLarge` if the discriminator does not fit
+    /// in 12 bits, `PayloadError::InvalidPasscode` if the passcode is out
+    /// of range or one of the values the specification forbids,
+    /// `PayloadError::ReservedCommissioningFlow` for a reserved flow, or
+    /// `PayloadError::CustomFlowWithoutVidPid` if the flow is
+    /// [`CommissioningFlow::Custom`] without an assigned VID and PID, which
+    /// the manual code must then carry.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use matter_setup_code::{DiscoveryCapabilities, SetupPayload};
+    ///
+    /// let payload = SetupPayload::builder()
+    ///     .discriminator(3840)
+    ///     .passcode(20202021)
+    ///     .vendor_id(0xFFF1)
+    ///     .product_id(0x8001)
+    ///     .discovery(DiscoveryCapabilities::BLE)
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(payload.to_qr_code_str().unwrap(), "MT:-24J042C00KA0648G00");
+    ///
+    /// assert!(SetupPayload::builder().discriminator(3840).passcode(12345678).build().is_err());
+    /// ```
+    pub fn build(self) -> Result<SetupPayload> {
+        let discriminator = Discriminator::from_long(
+            self.discriminator
+                .ok_or(PayloadError::MissingDiscriminator)?,
+        )?;
+        let pincode = Passcode::new(self.passcode.ok_or(PayloadError::MissingPasscode)?)?;
+        let assigned_vid_pid = self.vid.is_some_and(|vid| !vid.is_unassigned())
+            && self.pid.is_some_and(|pid| !pid.is_unassigned());
+        match self.flow {
+            CommissioningFlow::Reserved(bits) => {
+                return Err(PayloadError::ReservedCommissioningFlow(bits).into());
+            }
+            CommissioningFlow::Custom if !assigned_vid_pid => {
+                return Err(PayloadError::CustomFlowWithoutVidPid.into());
+            }
+            _ => {}
+        }
+
+        Ok(SetupPayload {
+            discriminator,
+            pincode,
+            discovery: self.discovery,
+            flow: self.flow,
+            vid: self.vid,
+            pid: self.pid,
+            version: 0,
+            optional_data: self.optional_data,
+            raw_optional_data: Vec::new(),
+        })
+    }
+}
+
+impl SetupPayload {
+    /// Starts building a payload with [`SetupPayloadBuilder`].
+    pub fn builder() -> SetupPayloadBuilder {
+        SetupPayloadBuilder::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MatterPayloadError;
+
+    #[test]
+    fn test_builder_matches_new() {
+        let built = SetupPayload::builder()
+            .discriminator(1132)
+            .passcode(69414998)
+            .vendor_id(0xFFF1)
+            .product_id(0x8000)
+            .discovery(DiscoveryCapabilities::ON_NETWORK)
+            .flow(CommissioningFlow::Standard)
+            .build()
+            .unwrap();
+        assert_eq!(
+            built,
+            SetupPayload::new(
+                1132,
+                Passcode::new(69414998).unwrap(),
+                Some(4),
+                None,
+                Some(0xFFF1),
+                Some(0x8000)
+            )
+        );
+    }
+
+    #[test]
+    fn test_builder_validation() {
+        let err = SetupPayload::builder()
+            .passcode(20202021)
+            .build()
+            .unwrap_err();
+        assert_eq!(
+            err,
+            MatterPayloadError::Payload(PayloadError::MissingDiscriminator)
+        );
+
+        let err = SetupPayload::builder()
+            .discriminator(3840)
+            .build()
+            .unwrap_err();
+        assert_eq!(
+            err,
+            MatterPayloadError::Payload(PayloadError::MissingPasscode)
+        );
+
+        let err = SetupPayload::builder()
+            .discriminator(0x1000)
+            .passcode(20202021)
+            .build()
+            .unwrap_err();
+        assert_eq!(
+            err,
+            MatterPayloadError::Payload(PayloadError::DiscriminatorTooLarge(0x1000))
+        );
+
+        for passcode in [0, 11111111, 87654321, 99_999_999, 0x7FF_FFFF] {
+            let err = SetupPayload::builder()
+                .discriminator(3840)
+                .passcode(passcode)
+                .build()
+                .unwrap_err();
+            assert_eq!(
+                err,
+                MatterPayloadError::Payload(PayloadError::InvalidPasscode(passcode))
+            );
+        }
+
+        let err = SetupPayload::builder()
+            .discriminator(3840)
+            .passcode(20202021)
+            .vendor_id(0)
+            .product_id(0x8001)
+            .flow(CommissioningFlow::Custom)
+            .build()
+            .unwrap_err();
+        assert_eq!(
+            err,
+            MatterPayloadError::Payload(PayloadError::CustomFlowWithoutVidPid)
+        );
+
+        // Discriminator 0 is a valid long discriminator and survives a QR
+        // code round trip.
+        let payload = SetupPayload::builder()
+            .discriminator(0)
+            .passcode(20202021)
+            .vendor_id(0xFFF1)
+            .product_id(0x8001)
+            .discovery(DiscoveryCapabilities::BLE)
+            .build()
+            .unwrap();
+        assert_eq!(payload.discriminator, Discriminator::from_long(0).unwrap());
+        let qr = payload.to_qr_code_str().unwrap();
+        assert_eq!(qr, "MT:-24J042C00YZ.548G00");
+        assert_eq!(SetupPayload::parse_str(&qr).unwrap(), payload);
+    }
+}
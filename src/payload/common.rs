@@ -1,19 +1,164 @@
-use deku::prelude::*;
-
 /// Defines the commissioning flow for the Matter device.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, DekuRead, DekuWrite)]
-#[deku(
-    id_type = "u8",
-    bits = "2",
-    endian = "endian",
-    ctx = "endian: deku::ctx::Endian"
+///
+/// The flow is a 2-bit field. Value 3 is reserved by the specification; it
+/// parses as [`CommissioningFlow::Reserved`] so the caller can decide what to
+/// do with it, but codes are never generated with it.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "kebab-case")
 )]
-#[repr(u8)]
 pub enum CommissioningFlow {
     /// Standard commissioning flow.
-    Standard = 0,
+    #[default]
+    Standard,
     /// User action is required to confirm commissioning.
-    UserIntent = 1,
+    UserIntent,
     /// Vendor-specific, custom commissioning flow.
-    Custom = 2,
-}
\ No newline at end of file
+    Custom,
+    /// A value the specification reserves, as found in a parsed code.
+    Reserved(u8),
+}
+
+impl CommissioningFlow {
+    /// Reads the 2-bit field value; bits above the field are ignored.
+    pub const fn from_bits(bits: u8) -> Self {
+        match bits & 0x03 {
+            0 => CommissioningFlow::Standard,
+            1 => CommissioningFlow::UserIntent,
+            2 => CommissioningFlow::Custom,
+            reserved => CommissioningFlow::Reserved(reserved),
+        }
+    }
+
+    /// Returns the 2-bit field value.
+    pub const fn bits(self) -> u8 {
+        match self {
+            CommissioningFlow::Standard => 0,
+            CommissioningFlow::UserIntent => 1,
+            CommissioningFlow::Custom => 2,
+            CommissioningFlow::Reserved(bits) => bits,
+        }
+    }
+
+    /// Returns `true` for a value the specification reserves.
+    pub const fn is_reserved(self) -> bool {
+        matches!(self, CommissioningFlow::Reserved(_))
+    }
+}
+
+/// The discovery capabilities bitmask of a QR code payload.
+///
+/// Tells the commissioner which transports the device advertises on while
+/// waiting to be commissioned. Bits not defined by the specification are
+/// preserved so payloads from newer devices round-trip unchanged.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+pub struct DiscoveryCapabilities(u8);
+
+impl DiscoveryCapabilities {
+    /// Soft access point (Wi-Fi devices only).
+    pub const SOFT_AP: Self = Self(1 << 0);
+    /// Bluetooth Low Energy.
+    pub const BLE: Self = Self(1 << 1);
+    /// Already on the IP network (DNS-SD discovery).
+    pub const ON_NETWORK: Self = Self(1 << 2);
+    /// Wi-Fi Public Action Frame (Matter 1.4+).
+    pub const WIFI_PAF: Self = Self(1 << 3);
+    /// Near-field communication (Matter 1.4+).
+    pub const NFC: Self = Self(1 << 4);
+
+    /// The defined capabilities with their display names, in bit order.
+    const NAMED: [(Self, &'static str); 5] = [
+        (Self::SOFT_AP, "SoftAP"),
+        (Self::BLE, "BLE"),
+        (Self::ON_NETWORK, "OnNetwork"),
+        (Self::WIFI_PAF, "WiFiPAF"),
+        (Self::NFC, "NFC"),
+    ];
+
+    /// Mask of all bits defined by the specification.
+    const KNOWN_BITS: u8 = 0x1F;
+
+    /// Wraps a raw bitmask, keeping any reserved bits.
+    pub const fn from_bits(bits: u8) -> Self {
+        Self(bits)
+    }
+
+    /// Returns the raw bitmask.
+    pub const fn bits(self) -> u8 {
+        self.0
+    }
+
+    /// Returns `true` if every capability in `other` is also set in `self`.
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Returns `true` if no capability is set.
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns the bits set in `self` that the specification does not define.
+    pub const fn reserved_bits(self) -> u8 {
+        self.0 & !Self::KNOWN_BITS
+    }
+
+    /// Returns the names of the defined capabilities set in `self`, in bit order.
+    pub fn names(self) -> impl Iterator<Item = &'static str> {
+        Self::NAMED
+            .into_iter()
+            .filter(move |&(flag, _)| self.contains(flag))
+            .map(|(_, name)| name)
+    }
+}
+
+impl From<u8> for DiscoveryCapabilities {
+    fn from(bits: u8) -> Self {
+        Self(bits)
+    }
+}
+
+impl From<DiscoveryCapabilities> for u8 {
+    fn from(capabilities: DiscoveryCapabilities) -> Self {
+        capabilities.0
+    }
+}
+
+impl std::ops::BitOr for DiscoveryCapabilities {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl std::ops::BitOrAssign for DiscoveryCapabilities {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+impl std::fmt::Display for DiscoveryCapabilities {
+    /// Formats the set capabilities as `BLE | OnNetwork`, with reserved bits in hex.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_empty() {
+            return write!(f, "None");
+        }
+        let mut separator = "";
+        for name in self.names() {
+            write!(f, "{separator}{name}")?;
+            separator = " | ";
+        }
+        if self.reserved_bits() != 0 {
+            write!(f, "{separator}{:#04x}", self.reserved_bits())?;
+        }
+        Ok(())
+    }
+}
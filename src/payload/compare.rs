@@ -0,0 +1,274 @@
+use super::{
+    CommissioningFlow, DiscoveryCapabilities, Discriminator, OptionalDataElement, Passcode,
+    ProductId, SetupPayload, VendorId,
+};
+
+/// A field that differs between two payloads, as found by [`SetupPayload::diff`].
+///
+/// `left` is the value in the payload `diff` was called on, `right` the one
+/// in its argument.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum FieldDiff {
+    /// The discriminators differ.
+    Discriminator {
+        left: Discriminator,
+        right: Discriminator,
+    },
+    /// The passcodes differ.
+    Passcode { left: Passcode, right: Passcode },
+    /// The discovery capabilities differ.
+    Discovery {
+        left: Option<DiscoveryCapabilities>,
+        right: Option<DiscoveryCapabilities>,
+    },
+    /// The commissioning flows differ.
+    Flow {
+        left: CommissioningFlow,
+        right: CommissioningFlow,
+    },
+    /// The vendor IDs differ.
+    VendorId {
+        left: Option<VendorId>,
+        right: Option<VendorId>,
+    },
+    /// The product IDs differ.
+    ProductId {
+        left: Option<ProductId>,
+        right: Option<ProductId>,
+    },
+    /// The payload format versions differ.
+    Version { left: u8, right: u8 },
+    /// The optional data differs.
+    OptionalData {
+        left: Vec<OptionalDataElement>,
+        right: Vec<OptionalDataElement>,
+    },
+}
+
+impl SetupPayload {
+    /// Returns the canonical form of the payload, which keeps only what a
+    /// manual code could also express.
+    ///
+    /// QR parsing and manual code parsing fill fields differently, so two
+    /// payloads describing the same device rarely compare equal as parsed.
+    /// The canonical form has a short discriminator, no empty discovery
+    /// capabilities, and no VID or PID of 0 on a standard flow.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use matter_setup_code::SetupPayload;
+    ///
+    /// // A QR code with VID and PID 0, and the manual code of the same device.
+    /// let qr = SetupPayload::parse_str("MT:0000000000KA0648G00").unwrap();
+    /// let manual = SetupPayload::parse_str("34970112332").unwrap();
+    /// assert_ne!(qr, manual);
+    /// assert_eq!(qr.normalize(), manual.normalize());
+    /// ```
+    pub fn normalize(&self) -> Self {
+        let drop_unassigned = self.flow == CommissioningFlow::Standard;
+        SetupPayload {
+            discriminator: Discriminator::from_short_field(self.discriminator.short()),
+            discovery: self.discovery.filter(|discovery| !discovery.is_empty()),
+            vid: self
+                .vid
+                .filter(|vid| !(drop_unassigned && vid.is_unassigned())),
+            pid: self
+                .pid
+                .filter(|pid| !(drop_unassigned && pid.is_unassigned())),
+            ..self.clone()
+        }
+    }
+
+    /// Lists the fields that differ between `self` and `other`, in field order;
+    /// an empty list means the payloads are equal.
+    ///
+    /// The comparison is field by field. A manual code carries no long
+    /// discriminator and no discovery capabilities, so to explain why a
+    /// manual code and a QR code disagree, diff their [`normalize`](Self::normalize)d
+    /// forms.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use matter_setup_code::{FieldDiff, Passcode, SetupPayload};
+    ///
+    /// let qr = SetupPayload::parse_str("MT:0000000000KA0648G00").unwrap();
+    /// let manual = SetupPayload::parse_str("11237442363").unwrap();
+    /// assert_eq!(
+    ///     qr.normalize().diff(&manual.normalize()),
+    ///     [
+    ///         FieldDiff::Discriminator {
+    ///             left: qr.normalize().discriminator,
+    ///             right: manual.discriminator,
+    ///         },
+    ///         FieldDiff::Passcode {
+    ///             left: Passcode::new(20202021).unwrap(),
+    ///             right: Passcode::new(69414998).unwrap(),
+    ///         },
+    ///     ]
+    /// );
+    /// ```
+    pub fn diff(&self, other: &SetupPayload) -> Vec<FieldDiff> {
+        let mut diffs = Vec::new();
+        if self.discriminator != other.discriminator {
+            diffs.push(FieldDiff::Discriminator {
+                left: self.discriminator,
+                right: other.discriminator,
+            });
+        }
+        if self.pincode != other.pincode {
+            diffs.push(FieldDiff::Passcode {
+                left: self.pincode,
+                right: other.pincode,
+            });
+        }
+        if self.discovery != other.discovery {
+            diffs.push(FieldDiff::Discovery {
+                left: self.discovery,
+                right: other.discovery,
+            });
+        }
+        if self.flow != other.flow {
+            diffs.push(FieldDiff::Flow {
+                left: self.flow,
+                right: other.flow,
+            });
+        }
+        if self.vid != other.vid {
+            diffs.push(FieldDiff::VendorId {
+                left: self.vid,
+                right: other.vid,
+            });
+        }
+        if self.pid != other.pid {
+            diffs.push(FieldDiff::ProductId {
+                left: self.pid,
+                right: other.pid,
+            });
+        }
+        if self.version != other.version {
+            diffs.push(FieldDiff::Version {
+                left: self.version,
+                right: other.version,
+            });
+        }
+        if self.optional_data != other.optional_data {
+            diffs.push(FieldDiff::OptionalData {
+                left: self.optional_data.clone(),
+                right: other.optional_data.clone(),
+            });
+        }
+        diffs
+    }
+
+    /// Returns `true` if `self` and `other` may describe the same device, as
+    /// when a user enters both the QR code and the manual code.
+    ///
+    /// The discriminators must agree on the bits both carry: the short
+    /// discriminator when either is short, all 12 bits otherwise. The
+    /// passcodes must be equal, and the vendor and product IDs too when
+    /// both payloads carry them.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use matter_setup_code::SetupPayload;
+    ///
+    /// let qr = SetupPayload::parse_str("MT:-24J042C00KA0648G00").unwrap();
+    /// assert!(qr.is_compatible_with(&SetupPayload::parse_str("34970112332").unwrap()));
+    /// assert!(!qr.is_compatible_with(&SetupPayload::parse_str("11237442363").unwrap()));
+    /// ```
+    pub fn is_compatible_with(&self, other: &SetupPayload) -> bool {
+        let discriminators = match other.discriminator.long() {
+            Some(long) => self.discriminator.matches(long),
+            None => self.discriminator.short() == other.discriminator.short(),
+        };
+        let vids = self.vid.zip(other.vid).is_none_or(|(a, b)| a == b);
+        let pids = self.pid.zip(other.pid).is_none_or(|(a, b)| a == b);
+        discriminators && self.pincode == other.pincode && vids && pids
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        let qr = SetupPayload::parse_str("MT:-24J042C00KA0648G00").unwrap();
+        let normalized = qr.normalize();
+        assert_eq!(
+            normalized.discriminator,
+            Discriminator::from_short(15).unwrap()
+        );
+        assert_eq!(normalized.discovery, Some(DiscoveryCapabilities::BLE));
+        assert_eq!(normalized.vid, Some(VendorId::new(0xFFF1)));
+        assert_eq!(normalized.normalize(), normalized);
+
+        // VID and PID 0 are kept on a custom flow.
+        let mut custom = qr.clone();
+        custom.flow = CommissioningFlow::Custom;
+        custom.vid = Some(VendorId::UNASSIGNED);
+        custom.pid = Some(ProductId::UNASSIGNED);
+        custom.discovery = Some(DiscoveryCapabilities::default());
+        let normalized = custom.normalize();
+        assert_eq!(normalized.vid, Some(VendorId::UNASSIGNED));
+        assert_eq!(normalized.pid, Some(ProductId::UNASSIGNED));
+        assert_eq!(normalized.discovery, None);
+    }
+
+    #[test]
+    fn test_diff() {
+        let qr = SetupPayload::parse_str("MT:-24J042C00KA0648G00").unwrap();
+        assert!(qr.diff(&qr).is_empty());
+
+        let manual = SetupPayload::parse_str("34970112332").unwrap();
+        assert_eq!(
+            qr.diff(&manual),
+            [
+                FieldDiff::Discriminator {
+                    left: Discriminator::from_long(3840).unwrap(),
+                    right: Discriminator::from_short(15).unwrap(),
+                },
+                FieldDiff::Discovery {
+                    left: Some(DiscoveryCapabilities::BLE),
+                    right: None,
+                },
+                FieldDiff::VendorId {
+                    left: Some(VendorId::new(0xFFF1)),
+                    right: None,
+                },
+                FieldDiff::ProductId {
+                    left: Some(ProductId::new(0x8001)),
+                    right: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_is_compatible_with() {
+        let qr = SetupPayload::parse_str("MT:-24J042C00KA0648G00").unwrap();
+        let manual = SetupPayload::parse_str("34970112332").unwrap();
+        assert!(qr.is_compatible_with(&manual));
+        assert!(manual.is_compatible_with(&qr));
+
+        // Same short discriminator, different long one.
+        let mut other = qr.clone();
+        other.discriminator = Discriminator::from_long(3841).unwrap();
+        assert!(!qr.is_compatible_with(&other));
+        assert!(manual.is_compatible_with(&other));
+
+        let mut other = qr.clone();
+        other.vid = Some(VendorId::new(0xFFF2));
+        assert!(!qr.is_compatible_with(&other));
+        other.vid = None;
+        assert!(qr.is_compatible_with(&other));
+
+        let mut other = qr.clone();
+        other.pincode = Passcode::new(20202022).unwrap();
+        assert!(!qr.is_compatible_with(&other));
+    }
+}
@@ -0,0 +1,95 @@
+use super::SetupPayload;
+use crate::base38;
+use crate::error::{PayloadError, Result};
+
+/// Separates the payloads of a concatenated QR code.
+const PAYLOAD_SEPARATOR: &str = "*";
+
+impl SetupPayload {
+    /// Parses a QR code holding one or more payloads, as packaging for
+    /// several devices carries: a single "MT:" prefix followed by the base38
+    /// text of each payload, separated by `*`. A plain QR code parses to a
+    /// single payload.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PayloadError::InvalidQrCodePrefix` if the string is not a QR
+    /// code, or the error of the first payload that does not decode, e.g.
+    /// `PayloadError::InvalidPackedLength` for an empty one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use matter_setup_code::SetupPayload;
+    ///
+    /// let payloads =
+    ///     SetupPayload::parse_multiple("MT:-24J042C00KA0648G00*Y.K904QI143LH13SH10").unwrap();
+    /// assert_eq!(payloads.len(), 2);
+    /// assert_eq!(payloads[1].passcode().get(), 69414998);
+    /// ```
+    pub fn parse_multiple(payload_str: &str) -> Result<Vec<SetupPayload>> {
+        let encoded = payload_str
+            .strip_prefix("MT:")
+            .ok_or(PayloadError::InvalidQrCodePrefix)?;
+        encoded
+            .split(PAYLOAD_SEPARATOR)
+            .map(|chunk| SetupPayload::from_packed_bytes(&base38::decode(chunk)?))
+            .collect()
+    }
+
+    /// Generates a QR code string holding several payloads, the inverse of
+    /// [`SetupPayload::parse_multiple`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `PayloadError::NoPayloads` if `payloads` is empty, or the
+    /// error [`SetupPayload::to_qr_code_str`] returns for the first payload
+    /// that cannot be encoded.
+    pub fn to_concatenated_qr_code_str(payloads: &[SetupPayload]) -> Result<String> {
+        if payloads.is_empty() {
+            return Err(PayloadError::NoPayloads.into());
+        }
+        let chunks = payloads
+            .iter()
+            .map(|payload| Ok(base38::encode(&payload.to_packed_bytes()?)))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(format!("MT:{}", chunks.join(PAYLOAD_SEPARATOR)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MatterPayloadError;
+
+    #[test]
+    fn test_concatenated_qr_code() {
+        let first = SetupPayload::parse_str("MT:-24J042C00KA0648G00").unwrap();
+        let second = SetupPayload::parse_str("MT:Y.K904QI143LH13SH10").unwrap();
+        let joined = "MT:-24J042C00KA0648G00*Y.K904QI143LH13SH10";
+
+        let payloads = [first.clone(), second];
+        assert_eq!(
+            SetupPayload::to_concatenated_qr_code_str(&payloads).unwrap(),
+            joined
+        );
+        assert_eq!(SetupPayload::parse_multiple(joined).unwrap(), payloads);
+        assert_eq!(
+            SetupPayload::parse_multiple("MT:-24J042C00KA0648G00").unwrap(),
+            [first]
+        );
+
+        assert_eq!(
+            SetupPayload::parse_multiple("MT:-24J042C00KA0648G00*").unwrap_err(),
+            MatterPayloadError::Payload(PayloadError::InvalidPackedLength(0))
+        );
+        assert_eq!(
+            SetupPayload::parse_multiple("-24J042C00KA0648G00").unwrap_err(),
+            MatterPayloadError::Payload(PayloadError::InvalidQrCodePrefix)
+        );
+        assert_eq!(
+            SetupPayload::to_concatenated_qr_code_str(&[]).unwrap_err(),
+            MatterPayloadError::Payload(PayloadError::NoPayloads)
+        );
+    }
+}
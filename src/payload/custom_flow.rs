@@ -0,0 +1,101 @@
+use super::{CommissioningFlow, SetupPayload};
+use crate::error::{PayloadError, Result};
+
+impl SetupPayload {
+    /// Builds the URL to send the user to for a custom commissioning flow.
+    ///
+    /// `base_url` is the vendor's `CommissioningCustomFlowUrl` from the DCL.
+    /// The `vid` and `pid` query parameters carry the IDs in decimal,
+    /// `payload` the QR code string, and `returnUrl`, if given, where the
+    /// vendor's flow sends the user back to. Parameters are appended to any
+    /// query the base URL already has, and percent-encoded.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PayloadError::NotCustomFlow` if the flow is not
+    /// [`CommissioningFlow::Custom`], or the error from
+    /// [`SetupPayload::to_qr_code_str`] if the payload cannot be encoded.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use matter_setup_code::{CommissioningFlow, SetupPayload};
+    ///
+    /// let mut payload = SetupPayload::parse_str("MT:-24J042C00KA0648G00").unwrap();
+    /// payload.flow = CommissioningFlow::Custom;
+    /// let url = payload
+    ///     .custom_flow_url("https://example.com/setup", Some("myapp://done"))
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     url,
+    ///     "https://example.com/setup?vid=65521&pid=32769\
+    ///      &payload=MT%3A-24J0YXE00KA0648G00&returnUrl=myapp%3A%2F%2Fdone"
+    /// );
+    /// ```
+    pub fn custom_flow_url(&self, base_url: &str, return_url: Option<&str>) -> Result<String> {
+        if self.flow != CommissioningFlow::Custom {
+            return Err(PayloadError::NotCustomFlow.into());
+        }
+        let qr_code = self.to_qr_code_str()?;
+        let vid = self.vid.ok_or(PayloadError::MissingVendorId)?;
+        let pid = self.pid.ok_or(PayloadError::MissingProductId)?;
+
+        let separator = if base_url.contains('?') { '&' } else { '?' };
+        let mut url = format!(
+            "{base_url}{separator}vid={}&pid={}&payload={}",
+            vid.get(),
+            pid.get(),
+            percent_encode(&qr_code)
+        );
+        if let Some(return_url) = return_url {
+            url.push_str("&returnUrl=");
+            url.push_str(&percent_encode(return_url));
+        }
+        Ok(url)
+    }
+}
+
+/// Percent-encodes every byte except the RFC 3986 unreserved characters.
+fn percent_encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            encoded.push(char::from(byte));
+        } else {
+            encoded.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MatterPayloadError;
+
+    #[test]
+    fn test_custom_flow_url() {
+        let mut payload = SetupPayload::parse_str("MT:-24J042C00KA0648G00").unwrap();
+        assert_eq!(
+            payload
+                .custom_flow_url("https://example.com", None)
+                .unwrap_err(),
+            MatterPayloadError::Payload(PayloadError::NotCustomFlow)
+        );
+
+        payload.flow = CommissioningFlow::Custom;
+        let qr = payload.to_qr_code_str().unwrap();
+        let url = payload
+            .custom_flow_url("https://example.com/setup?lang=en", None)
+            .unwrap();
+        assert_eq!(
+            url,
+            format!(
+                "https://example.com/setup?lang=en&vid=65521&pid=32769&payload={}",
+                qr.replace(':', "%3A")
+            )
+        );
+
+        assert_eq!(percent_encode("a b/c?"), "a%20b%2Fc%3F");
+    }
+}
@@ -0,0 +1,197 @@
+use crate::error::{PayloadError, Result};
+
+/// Returns the 4-bit short discriminator of a 12-bit discriminator: its
+/// upper 4 bits, as carried by manual codes and matched against BLE and
+/// DNS-SD advertisements. Bits above the 12-bit field are ignored.
+///
+/// # Example
+///
+/// ```
+/// use matter_setup_code::short_discriminator_from_long;
+///
+/// assert_eq!(short_discriminator_from_long(3840), 15);
+/// assert_eq!(short_discriminator_from_long(0x0FF), 0);
+/// assert_eq!(short_discriminator_from_long(0x1F00), 15);
+/// ```
+pub const fn short_discriminator_from_long(long: u16) -> u8 {
+    ((long & Discriminator::MAX_LONG) >> 8) as u8
+}
+
+/// The discriminator a payload identifies its device with.
+///
+/// A QR code carries the full 12-bit value; a manual code only its upper 4
+/// bits. Keeping the two cases apart means a payload can never hold a short
+/// discriminator that disagrees with its long one.
+///
+/// [`Discriminator::from_long`] and [`Discriminator::from_short`] check that
+/// the value fits its field. A variant built directly is not checked until a
+/// code is generated from it, which fails, or [`SetupPayload::validate`]
+/// reports it.
+///
+/// [`SetupPayload::validate`]: crate::SetupPayload::validate
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum Discriminator {
+    /// The full 12-bit discriminator
+    Long(u16),
+    /// Only the upper 4 bits, as carried by a manual code
+    Short(u8),
+}
+
+impl Discriminator {
+    /// Largest 12-bit discriminator.
+    pub const MAX_LONG: u16 = 0x0FFF;
+    /// Largest 4-bit short discriminator.
+    pub const MAX_SHORT: u8 = 0x0F;
+
+    /// Creates a long discriminator from a 12-bit field of a decoded or
+    /// legacy payload, ignoring any higher bits.
+    pub(crate) const fn from_long_field(value: u16) -> Self {
+        Discriminator::Long(value & Self::MAX_LONG)
+    }
+
+    /// Creates a short discriminator from a 4-bit field of a decoded
+    /// payload, ignoring any higher bits.
+    pub(crate) const fn from_short_field(value: u8) -> Self {
+        Discriminator::Short(value & Self::MAX_SHORT)
+    }
+
+    /// Creates a long discriminator, checking that it fits in 12 bits.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PayloadError::DiscriminatorTooLarge` if `value` exceeds 0xFFF.
+    pub fn from_long(value: u16) -> Result<Self> {
+        Discriminator::Long(value).checked()
+    }
+
+    /// Creates a short discriminator, checking that it fits in 4 bits.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PayloadError::DiscriminatorOutOfRange` if `value` exceeds 15.
+    pub fn from_short(value: u8) -> Result<Self> {
+        Discriminator::Short(value).checked()
+    }
+
+    /// Returns `self` if its value fits its field.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PayloadError::DiscriminatorTooLarge` for a long discriminator
+    /// above 0xFFF, or `PayloadError::DiscriminatorOutOfRange` for a short
+    /// one above 15.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use matter_setup_code::Discriminator;
+    ///
+    /// assert!(Discriminator::Long(3840).checked().is_ok());
+    /// assert!(Discriminator::Long(0x1000).checked().is_err());
+    /// assert!(Discriminator::Short(16).checked().is_err());
+    /// ```
+    pub fn checked(self) -> Result<Self> {
+        match self {
+            Discriminator::Long(long) if long > Self::MAX_LONG => {
+                Err(PayloadError::DiscriminatorTooLarge(long).into())
+            }
+            Discriminator::Short(short) if short > Self::MAX_SHORT => {
+                Err(PayloadError::DiscriminatorOutOfRange(short).into())
+            }
+            _ => Ok(self),
+        }
+    }
+
+    /// The short discriminator: the upper 4 bits of a long discriminator,
+    /// or the short value itself.
+    pub fn short(self) -> u8 {
+        match self {
+            Discriminator::Long(long) => short_discriminator_from_long(long),
+            Discriminator::Short(short) => short,
+        }
+    }
+
+    /// The 12-bit discriminator, if known.
+    pub fn long(self) -> Option<u16> {
+        match self {
+            Discriminator::Long(long) => Some(long),
+            Discriminator::Short(_) => None,
+        }
+    }
+
+    /// Returns `true` if a device advertising the 12-bit `discriminator` may
+    /// be the one identified by `self`.
+    ///
+    /// A long discriminator must match exactly; a short one only compares
+    /// the upper 4 bits.
+    pub fn matches(self, discriminator: u16) -> bool {
+        match self {
+            Discriminator::Long(long) => long == discriminator & Self::MAX_LONG,
+            Discriminator::Short(short) => short == short_discriminator_from_long(discriminator),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Discriminator {
+    /// Deserializes a discriminator, rejecting one that does not fit its field.
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<Self, D::Error> {
+        /// The serialized form, before its range is checked.
+        #[derive(serde::Deserialize)]
+        #[serde(rename_all = "kebab-case")]
+        enum Unchecked {
+            Long(u16),
+            Short(u8),
+        }
+
+        match Unchecked::deserialize(deserializer)? {
+            Unchecked::Long(long) => Discriminator::Long(long),
+            Unchecked::Short(short) => Discriminator::Short(short),
+        }
+        .checked()
+        .map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MatterPayloadError;
+
+    #[test]
+    fn test_discriminator_conversions() {
+        let long = Discriminator::from_long(3840).unwrap();
+        assert_eq!(long.short(), 15);
+        assert_eq!(long.long(), Some(3840));
+
+        let short = Discriminator::from_short(4).unwrap();
+        assert_eq!(short.short(), 4);
+        assert_eq!(short.long(), None);
+        assert!(short.matches(1132));
+        assert!(!short.matches(0x500));
+
+        assert_eq!(Discriminator::from_long_field(0x1F00).short(), 15);
+        assert_eq!(short_discriminator_from_long(0x0FFF), 15);
+        assert_eq!(format!("{long:?} {short:?}"), "Long(3840) Short(4)");
+
+        assert_eq!(
+            Discriminator::from_long(0x1000).unwrap_err(),
+            MatterPayloadError::Payload(PayloadError::DiscriminatorTooLarge(0x1000))
+        );
+        assert_eq!(
+            Discriminator::from_short(16).unwrap_err(),
+            MatterPayloadError::Payload(PayloadError::DiscriminatorOutOfRange(16))
+        );
+        assert_eq!(
+            Discriminator::Long(0x1000).checked().unwrap_err(),
+            MatterPayloadError::Payload(PayloadError::DiscriminatorTooLarge(0x1000))
+        );
+    }
+}
@@ -0,0 +1,193 @@
+use super::qr::QrCodeData;
+use super::{
+    CommissioningFlow, DiscoveryCapabilities, SetupPayload, VendorId, short_discriminator_from_long,
+};
+use crate::error::Result;
+
+/// One field of a QR code payload, as listed by [`SetupPayload::explain_qr_code`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldExplanation {
+    /// Field name, as in the specification's payload table
+    pub name: &'static str,
+    /// Offset of the least significant bit, counted from the least
+    /// significant bit of the 88-bit payload as the specification does
+    pub offset: usize,
+    /// Width in bits
+    pub width: usize,
+    /// The value stored in the field
+    pub raw: u64,
+    /// What the value means, e.g. `BLE` for discovery capabilities 0x02
+    pub meaning: String,
+}
+
+impl std::fmt::Display for FieldExplanation {
+    /// Formats the field on one line, e.g.
+    /// `discovery      bits 37..45  0x2  BLE`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let bits = format!("{}..{}", self.offset, self.offset + self.width);
+        write!(
+            f,
+            "{:<14} bits {:<7} {:#x}  {}",
+            self.name, bits, self.raw, self.meaning
+        )
+    }
+}
+
+impl SetupPayload {
+    /// Breaks a QR code down into its fields, from the least significant
+    /// bit up: where each sits, its raw value and what it means.
+    ///
+    /// Values are reported as stored, so codes with reserved or invalid
+    /// values are explained as long as they decode. Helpful to compare a code
+    /// field by field with the output of chip-tool.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PayloadError::InvalidQrCodePrefix` if the string is not a QR
+    /// code, or an error if its base38 text does not decode.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use matter_setup_code::SetupPayload;
+    ///
+    /// let fields = SetupPayload::explain_qr_code("MT:-24J042C00KA0648G00").unwrap();
+    /// assert_eq!(fields[4].to_string(), "discovery      bits 37..45  0x2  BLE");
+    /// ```
+    pub fn explain_qr_code(payload_str: &str) -> Result<Vec<FieldExplanation>> {
+        let container = QrCodeData::parse_from_str(payload_str)?;
+
+        let version = container.version;
+        let vid = VendorId::new(container.vid);
+        let flow = CommissioningFlow::from_bits(container.flow);
+        let discovery = DiscoveryCapabilities::from_bits(container.discovery);
+        let discriminator = container.discriminator;
+        let passcode = container.pincode;
+        let padding = container.padding;
+
+        let fields = [
+            (
+                "version",
+                3,
+                u64::from(version),
+                if version == 0 {
+                    "0".to_string()
+                } else {
+                    format!("{version} (unknown)")
+                },
+            ),
+            (
+                "vendor ID",
+                16,
+                u64::from(container.vid),
+                if vid.is_test() {
+                    format!("{vid} (test vendor)")
+                } else {
+                    vid.to_string()
+                },
+            ),
+            (
+                "product ID",
+                16,
+                u64::from(container.pid),
+                format!("{:#06X}", container.pid),
+            ),
+            (
+                "flow",
+                2,
+                u64::from(container.flow),
+                match flow {
+                    CommissioningFlow::Standard => "Standard".to_string(),
+                    CommissioningFlow::UserIntent => "UserIntent".to_string(),
+                    CommissioningFlow::Custom => "Custom".to_string(),
+                    CommissioningFlow::Reserved(_) => "reserved".to_string(),
+                },
+            ),
+            (
+                "discovery",
+                8,
+                u64::from(container.discovery),
+                discovery.to_string(),
+            ),
+            (
+                "discriminator",
+                12,
+                u64::from(discriminator),
+                format!(
+                    "{discriminator} (short {})",
+                    short_discriminator_from_long(discriminator)
+                ),
+            ),
+            (
+                "passcode",
+                27,
+                u64::from(passcode),
+                if super::is_valid_passcode(passcode) {
+                    format!("{passcode:08}")
+                } else {
+                    format!("{passcode:08} (invalid)")
+                },
+            ),
+            (
+                "padding",
+                4,
+                u64::from(padding),
+                if padding == 0 {
+                    "zero".to_string()
+                } else {
+                    "non-zero".to_string()
+                },
+            ),
+        ];
+
+        let mut offset = 0;
+        Ok(fields
+            .into_iter()
+            .map(|(name, width, raw, meaning)| {
+                let field = FieldExplanation {
+                    name,
+                    offset,
+                    width,
+                    raw,
+                    meaning,
+                };
+                offset += width;
+                field
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_explain_qr_code() {
+        let fields = SetupPayload::explain_qr_code("MT:Y.K904QI143LH13SH10").unwrap();
+        let layout: Vec<_> = fields
+            .iter()
+            .map(|field| (field.name, field.offset, field.width, field.raw))
+            .collect();
+        assert_eq!(
+            layout,
+            [
+                ("version", 0, 3, 0),
+                ("vendor ID", 3, 16, 0xFFF1),
+                ("product ID", 19, 16, 0x8000),
+                ("flow", 35, 2, 0),
+                ("discovery", 37, 8, 4),
+                ("discriminator", 45, 12, 1132),
+                ("passcode", 57, 27, 69414998),
+                ("padding", 84, 4, 0),
+            ]
+        );
+        assert_eq!(fields[1].meaning, "0xFFF1 (test vendor)");
+        assert_eq!(fields[5].meaning, "1132 (short 4)");
+
+        // Reserved values are explained rather than rejected.
+        let fields = SetupPayload::explain_qr_code("MT:-24J0UBG00KA0648G00").unwrap();
+        assert_eq!(fields[3].meaning, "reserved");
+        assert!(SetupPayload::explain_qr_code("34970112332").is_err());
+    }
+}
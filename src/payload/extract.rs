@@ -0,0 +1,122 @@
+use super::SetupPayload;
+
+/// Prefix of a QR code string.
+const QR_PREFIX: &[u8] = b"MT:";
+
+/// Prefix of a QR code string percent-encoded in a URL, without its last
+/// hex digit, which may be in either case.
+const ENCODED_QR_PREFIX: &[u8] = b"MT%3";
+
+/// Length of the prefix of a QR code string at the start of `bytes`, plain
+/// or percent-encoded.
+fn prefix_len(bytes: &[u8]) -> Option<usize> {
+    if bytes.starts_with(QR_PREFIX) {
+        Some(QR_PREFIX.len())
+    } else if bytes.starts_with(ENCODED_QR_PREFIX) && matches!(bytes.get(4), Some(b'A' | b'a')) {
+        Some(ENCODED_QR_PREFIX.len() + 1)
+    } else {
+        None
+    }
+}
+
+/// Reads the base38 text and `*` separators, plain or percent-encoded, at
+/// the start of `bytes`; returns the text and the number of bytes read.
+fn read_encoded(bytes: &[u8]) -> (String, usize) {
+    let mut text = String::new();
+    let mut index = 0;
+    while let Some(&byte) = bytes.get(index) {
+        if byte.is_ascii_digit() || byte.is_ascii_uppercase() || b"-.*".contains(&byte) {
+            text.push(char::from(byte));
+            index += 1;
+        } else if bytes[index..].starts_with(b"%2")
+            && matches!(bytes.get(index + 2), Some(b'A' | b'a'))
+        {
+            text.push('*');
+            index += 3;
+        } else {
+            break;
+        }
+    }
+    (text, index)
+}
+
+impl SetupPayload {
+    /// Finds the QR code payloads embedded in `text`, such as a URL, a log
+    /// line or pasted chip-tool output, in order of appearance.
+    ///
+    /// A payload starts at "MT:", or "MT%3A" in a URL, that does not follow
+    /// a letter or a digit. Separators of concatenated QR codes may be
+    /// percent-encoded as well. Punctuation that happens to be base38, like
+    /// a sentence's final period, is left out when the payload only decodes
+    /// without it. Text that does not decode is skipped.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use matter_setup_code::SetupPayload;
+    ///
+    /// let text = "[1699] CHIP:SVR: SetupQRCode: [MT:-24J042C00KA0648G00]\n\
+    ///             https://example.com/setup?code=MT%3AY.K904QI143LH13SH10&lang=en";
+    /// let payloads = SetupPayload::extract_from_text(text);
+    /// assert_eq!(payloads.len(), 2);
+    /// assert_eq!(payloads[1].passcode().get(), 69414998);
+    /// ```
+    pub fn extract_from_text(text: &str) -> Vec<SetupPayload> {
+        let bytes = text.as_bytes();
+        let mut payloads = Vec::new();
+        let mut index = 0;
+        while index < bytes.len() {
+            let at_boundary = index == 0 || !bytes[index - 1].is_ascii_alphanumeric();
+            let Some(prefix) = prefix_len(&bytes[index..]).filter(|_| at_boundary) else {
+                index += 1;
+                continue;
+            };
+            let (encoded, read) = read_encoded(&bytes[index + prefix..]);
+            let encoded = encoded.trim_end_matches('*');
+            let found = (1..=encoded.len()).rev().find_map(|end| {
+                SetupPayload::parse_multiple(&format!("MT:{}", &encoded[..end])).ok()
+            });
+            match found {
+                Some(found) => {
+                    payloads.extend(found);
+                    index += prefix + read;
+                }
+                None => index += prefix,
+            }
+        }
+        payloads
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_from_text() {
+        let first = SetupPayload::parse_str("MT:-24J042C00KA0648G00").unwrap();
+        let second = SetupPayload::parse_str("MT:Y.K904QI143LH13SH10").unwrap();
+
+        assert_eq!(
+            SetupPayload::extract_from_text("Scan MT:Y.K904QI143LH13SH10."),
+            std::slice::from_ref(&second)
+        );
+        assert_eq!(
+            SetupPayload::extract_from_text(
+                "https://example.com/?qr=MT%3a-24J042C00KA0648G00%2AY.K904QI143LH13SH10"
+            ),
+            [first.clone(), second.clone()]
+        );
+        assert_eq!(
+            SetupPayload::extract_from_text(
+                "MT:-24J042C00KA0648G00 and MT:-24J042C00KA0648G00, MT:Y.K904QI143LH13SH10*"
+            ),
+            [first.clone(), first, second]
+        );
+
+        // Not a payload: inside a word, not base38, or nothing after the prefix.
+        assert!(SetupPayload::extract_from_text("SMT:-24J042C00KA0648G00").is_empty());
+        assert!(SetupPayload::extract_from_text("MT:HELLO MT: MT%3").is_empty());
+        assert!(SetupPayload::extract_from_text("").is_empty());
+    }
+}
@@ -0,0 +1,168 @@
+use super::{
+    CommissioningFlow, DiscoveryCapabilities, Discriminator, Passcode, ProductId, SetupPayload,
+    VendorId,
+};
+
+/// A fixed field of [`SetupPayload`], as listed by [`SetupPayload::fields`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub enum FieldName {
+    Discriminator,
+    Passcode,
+    Discovery,
+    Flow,
+    VendorId,
+    ProductId,
+    Version,
+}
+
+impl FieldName {
+    /// Every field, in declaration order.
+    pub const ALL: [FieldName; 7] = [
+        FieldName::Discriminator,
+        FieldName::Passcode,
+        FieldName::Discovery,
+        FieldName::Flow,
+        FieldName::VendorId,
+        FieldName::ProductId,
+        FieldName::Version,
+    ];
+
+    /// Width of the field in a QR code, in bits.
+    ///
+    /// A manual code stores the discriminator in 4 bits and the version in 1.
+    pub const fn bit_width(self) -> usize {
+        match self {
+            FieldName::Discriminator => 12,
+            FieldName::Passcode => 27,
+            FieldName::Discovery => 8,
+            FieldName::Flow => 2,
+            FieldName::VendorId | FieldName::ProductId => 16,
+            FieldName::Version => 3,
+        }
+    }
+
+    /// Returns the name used in logs and UIs, e.g. `vendor ID`.
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            FieldName::Discriminator => "discriminator",
+            FieldName::Passcode => "passcode",
+            FieldName::Discovery => "discovery",
+            FieldName::Flow => "flow",
+            FieldName::VendorId => "vendor ID",
+            FieldName::ProductId => "product ID",
+            FieldName::Version => "version",
+        }
+    }
+}
+
+impl std::fmt::Display for FieldName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The value of a [`SetupPayload`] field, as listed by [`SetupPayload::fields`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum FieldValue {
+    Discriminator(Discriminator),
+    Passcode(Passcode),
+    Discovery(Option<DiscoveryCapabilities>),
+    Flow(CommissioningFlow),
+    VendorId(Option<VendorId>),
+    ProductId(Option<ProductId>),
+    Version(u8),
+}
+
+impl std::fmt::Display for FieldValue {
+    /// Formats the value as shown to users, e.g. `3840`, `short 15`,
+    /// `BLE | OnNetwork` or `0xFFF1`; absent values format as `-`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fn optional<T: std::fmt::Display>(
+            f: &mut std::fmt::Formatter<'_>,
+            value: Option<T>,
+        ) -> std::fmt::Result {
+            match value {
+                Some(value) => write!(f, "{value}"),
+                None => f.write_str("-"),
+            }
+        }
+
+        match *self {
+            FieldValue::Discriminator(discriminator) => match discriminator.long() {
+                Some(long) => write!(f, "{long}"),
+                None => write!(f, "short {}", discriminator.short()),
+            },
+            FieldValue::Passcode(passcode) => write!(f, "{passcode}"),
+            FieldValue::Discovery(discovery) => optional(f, discovery),
+            FieldValue::Flow(CommissioningFlow::Reserved(bits)) => write!(f, "reserved ({bits})"),
+            FieldValue::Flow(flow) => write!(f, "{flow:?}"),
+            FieldValue::VendorId(vid) => optional(f, vid),
+            FieldValue::ProductId(pid) => optional(f, pid),
+            FieldValue::Version(version) => write!(f, "{version}"),
+        }
+    }
+}
+
+impl SetupPayload {
+    /// Lists every fixed field with its value, in declaration order, so
+    /// loggers and generic UIs can render a payload without matching on each
+    /// field. Optional data is left out.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use matter_setup_code::SetupPayload;
+    ///
+    /// let payload = SetupPayload::parse_str("34970112332").unwrap();
+    /// let lines: Vec<String> = payload
+    ///     .fields()
+    ///     .map(|(name, value)| format!("{name} ({} bits): {value}", name.bit_width()))
+    ///     .collect();
+    /// assert_eq!(lines[0], "discriminator (12 bits): short 15");
+    /// assert_eq!(lines[4], "vendor ID (16 bits): -");
+    /// ```
+    pub fn fields(&self) -> impl Iterator<Item = (FieldName, FieldValue)> {
+        [
+            FieldValue::Discriminator(self.discriminator),
+            FieldValue::Passcode(self.pincode),
+            FieldValue::Discovery(self.discovery),
+            FieldValue::Flow(self.flow),
+            FieldValue::VendorId(self.vid),
+            FieldValue::ProductId(self.pid),
+            FieldValue::Version(self.version),
+        ]
+        .into_iter()
+        .zip(FieldName::ALL)
+        .map(|(value, name)| (name, value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fields() {
+        let payload = SetupPayload::parse_str("MT:-24J042C00KA0648G00").unwrap();
+        let rendered: Vec<_> = payload
+            .fields()
+            .map(|(name, value)| (name.as_str(), value.to_string()))
+            .collect();
+        assert_eq!(
+            rendered,
+            [
+                ("discriminator", "3840".to_string()),
+                ("passcode", "20202021".to_string()),
+                ("discovery", "BLE".to_string()),
+                ("flow", "Standard".to_string()),
+                ("vendor ID", "0xFFF1".to_string()),
+                ("product ID", "0x8001".to_string()),
+                ("version", "0".to_string()),
+            ]
+        );
+        let width: usize = FieldName::ALL.iter().map(|name| name.bit_width()).sum();
+        assert_eq!(width, 84);
+    }
+}
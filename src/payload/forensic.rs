@@ -0,0 +1,280 @@
+//! Best-effort parsing of damaged codes for failure analysis.
+
+use super::SetupPayload;
+use super::manual::ManualCodeData;
+use super::qr::{QR_PAYLOAD_BYTES, QrCodeData};
+use crate::error::MatterPayloadError;
+use crate::verhoeff;
+
+/// QR payload fields with their bit ranges in the decoded big-endian stream.
+const QR_FIELDS: [(&str, std::ops::Range<usize>); 7] = [
+    ("pincode", 4..31),
+    ("discriminator", 31..43),
+    ("discovery", 43..51),
+    ("flow", 51..53),
+    ("pid", 53..69),
+    ("vid", 69..85),
+    ("version", 85..88),
+];
+
+/// A problem found by [`SetupPayload::parse_best_effort`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Defect {
+    /// The manual code's check digit does not match the other digits.
+    ChecksumMismatch {
+        /// Check digit computed from the other digits
+        expected: u8,
+        /// Check digit found in the input
+        found: u8,
+    },
+    /// A manual code character was not a digit; the check digit pinned it down.
+    RecoveredDigit {
+        /// Byte offset of the character in the input
+        offset: usize,
+        /// The character found
+        character: char,
+        /// The only digit making the check digit valid
+        digit: u8,
+    },
+    /// A QR code character is outside the Base38 alphabet and was read as `0`.
+    InvalidCharacter {
+        /// Byte offset of the character in the input
+        offset: usize,
+        /// The character found
+        character: char,
+        /// Fields sharing the damaged Base38 chunk, whose values are unreliable
+        unreliable_fields: Vec<&'static str>,
+    },
+    /// The input is too damaged to recover any field.
+    Unrecoverable(MatterPayloadError),
+}
+
+/// What [`SetupPayload::parse_best_effort`] could recover from an input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartialParse {
+    /// The recovered fields, unless the input was unrecoverable
+    pub payload: Option<SetupPayload>,
+    /// Everything that was wrong with the input; empty if it parses strictly
+    pub defects: Vec<Defect>,
+}
+
+impl PartialParse {
+    /// Returns `true` if the input parsed without any defect.
+    pub fn is_clean(&self) -> bool {
+        self.payload.is_some() && self.defects.is_empty()
+    }
+
+    fn clean(payload: SetupPayload) -> Self {
+        PartialParse {
+            payload: Some(payload),
+            defects: Vec::new(),
+        }
+    }
+
+    fn unrecoverable(error: MatterPayloadError) -> Self {
+        PartialParse {
+            payload: None,
+            defects: vec![Defect::Unrecoverable(error)],
+        }
+    }
+}
+
+impl SetupPayload {
+    /// Parses `input` like [`SetupPayload::parse_str`], but carries on past a
+    /// wrong check digit or a single bad character, reporting what was wrong.
+    ///
+    /// Support teams use this to read the discriminator or VID off damaged
+    /// or mistyped codes that strict parsing rightly refuses. Never use the
+    /// result to commission a device.
+    ///
+    /// - A manual code with a wrong check digit is decoded anyway.
+    /// - A single non-digit in a manual code is recovered from the check digit.
+    /// - A single character outside the Base38 alphabet in a QR code is read
+    ///   as `0`; the fields sharing its chunk are listed as unreliable.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use matter_setup_code::{Defect, SetupPayload};
+    ///
+    /// let partial = SetupPayload::parse_best_effort("34970112331");
+    /// assert_eq!(partial.payload.unwrap().pincode.get(), 20202021);
+    /// assert_eq!(
+    ///     partial.defects,
+    ///     [Defect::ChecksumMismatch { expected: 2, found: 1 }]
+    /// );
+    /// ```
+    pub fn parse_best_effort(input: &str) -> PartialParse {
+        if let Ok(payload) = SetupPayload::parse_str(input) {
+            return PartialParse::clean(payload);
+        }
+        if input.starts_with("MT:") {
+            best_effort_qr(input)
+        } else {
+            best_effort_manual(input)
+        }
+    }
+}
+
+fn best_effort_manual(input: &str) -> PartialParse {
+    let bad: Vec<(usize, char)> = input
+        .char_indices()
+        .filter(|(_, c)| !c.is_ascii_digit())
+        .collect();
+    let mut defects = Vec::new();
+    let digits = match bad.as_slice() {
+        [] => input.to_string(),
+        &[(offset, character)] if offset + 1 < input.len() => {
+            // Verhoeff detects every single-digit error, so at most one digit fits.
+            let candidates: Vec<String> = (b'0'..=b'9')
+                .map(|digit| {
+                    let mut candidate = input.to_string();
+                    candidate.replace_range(
+                        offset..offset + character.len_utf8(),
+                        &(digit as char).to_string(),
+                    );
+                    candidate
+                })
+                .filter(|candidate| verhoeff::validate(candidate).unwrap_or(false))
+                .collect();
+            match candidates.as_slice() {
+                [candidate] => {
+                    defects.push(Defect::RecoveredDigit {
+                        offset,
+                        character,
+                        digit: candidate.as_bytes()[offset] - b'0',
+                    });
+                    candidate.clone()
+                }
+                _ => {
+                    return PartialParse::unrecoverable(
+                        SetupPayload::parse_str(input).unwrap_err(),
+                    );
+                }
+            }
+        }
+        _ => return PartialParse::unrecoverable(SetupPayload::parse_str(input).unwrap_err()),
+    };
+
+    if digits.len() != 11 && digits.len() != 21 {
+        return PartialParse::unrecoverable(SetupPayload::parse_str(input).unwrap_err());
+    }
+    let (body, check) = digits.split_at(digits.len() - 1);
+    let found = check.as_bytes()[0] - b'0';
+    match verhoeff::calculate_checksum(body) {
+        Ok(expected) if expected != found => {
+            defects.push(Defect::ChecksumMismatch { expected, found })
+        }
+        Ok(_) => {}
+        Err(e) => return PartialParse::unrecoverable(e),
+    }
+
+    match ManualCodeData::decode_digits(&digits)
+        .and_then(SetupPayload::from_manual_data)
+        .and_then(SetupPayload::with_valid_passcode)
+    {
+        Ok(payload) => PartialParse {
+            payload: Some(payload),
+            defects,
+        },
+        Err(e) => PartialParse::unrecoverable(e),
+    }
+}
+
+fn best_effort_qr(input: &str) -> PartialParse {
+    let bad: Vec<(usize, char)> = input
+        .char_indices()
+        .skip(3)
+        .filter(|&(_, c)| !matches!(c, '0'..='9' | 'A'..='Z' | '-' | '.'))
+        .collect();
+    let &[(offset, character)] = bad.as_slice() else {
+        return PartialParse::unrecoverable(SetupPayload::parse_str(input).unwrap_err());
+    };
+
+    let mut repaired = input.to_string();
+    repaired.replace_range(offset..offset + character.len_utf8(), "0");
+    match QrCodeData::parse_from_str(&repaired)
+        .and_then(SetupPayload::from_qr_data)
+        .and_then(SetupPayload::with_valid_passcode)
+    {
+        Ok(payload) => PartialParse {
+            payload: Some(payload),
+            defects: vec![Defect::InvalidCharacter {
+                offset,
+                character,
+                unreliable_fields: qr_fields_in_chunk((offset - 3) / 5),
+            }],
+        },
+        Err(e) => PartialParse::unrecoverable(e),
+    }
+}
+
+/// Names the QR fields overlapping the bytes of Base38 chunk `chunk`.
+fn qr_fields_in_chunk(chunk: usize) -> Vec<&'static str> {
+    // Chunk k decodes to bytes 3k..3k+3, which are reversed before the
+    // fields are read most significant bit first.
+    let first = 3 * chunk;
+    let last = (first + 2).min(QR_PAYLOAD_BYTES - 1);
+    let bits = 8 * (QR_PAYLOAD_BYTES - 1 - last)..8 * (QR_PAYLOAD_BYTES - first);
+    QR_FIELDS
+        .iter()
+        .filter(|(_, range)| range.start < bits.end && bits.start < range.end)
+        .map(|&(name, _)| name)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::payload::Discriminator;
+
+    #[test]
+    fn test_clean_input() {
+        let partial = SetupPayload::parse_best_effort("MT:-24J042C00KA0648G00");
+        assert!(partial.is_clean());
+    }
+
+    #[test]
+    fn test_manual_code_defects() {
+        let partial = SetupPayload::parse_best_effort("3497011x332");
+        assert_eq!(
+            partial.defects,
+            [Defect::RecoveredDigit {
+                offset: 7,
+                character: 'x',
+                digit: 2
+            }]
+        );
+        assert_eq!(partial.payload.unwrap().pincode.get(), 20202021);
+
+        let partial = SetupPayload::parse_best_effort("123");
+        assert!(partial.payload.is_none());
+        assert!(matches!(partial.defects[..], [Defect::Unrecoverable(_)]));
+    }
+
+    #[test]
+    fn test_qr_code_bad_character() {
+        // The last Base38 chunk holds the padding and the start of the passcode.
+        // The damaged character was a `0`, so reading it as `0` restores the code.
+        let partial = SetupPayload::parse_best_effort("MT:-24J042C00KA0648G0@");
+        let payload = partial.payload.unwrap();
+        assert_eq!(
+            payload.discriminator,
+            Discriminator::from_long(3840).unwrap()
+        );
+        assert_eq!(payload.pincode.get(), 20202021);
+        assert_eq!(
+            partial.defects,
+            [Defect::InvalidCharacter {
+                offset: 21,
+                character: '@',
+                unreliable_fields: vec!["pincode"]
+            }]
+        );
+
+        assert_eq!(qr_fields_in_chunk(0), ["pid", "vid", "version"]);
+
+        let partial = SetupPayload::parse_best_effort("MT:@24J042C00KA0648G0@");
+        assert!(partial.payload.is_none());
+    }
+}
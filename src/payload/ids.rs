@@ -0,0 +1,125 @@
+use std::ops::RangeInclusive;
+
+/// A Connectivity Standards Alliance vendor ID.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+pub struct VendorId(u16);
+
+impl VendorId {
+    /// VID 0, meaning no vendor is specified (the Python reference default).
+    pub const UNASSIGNED: Self = Self(0x0000);
+
+    /// The vendor IDs the CSA reserves for development and testing.
+    pub const TEST_RANGE: RangeInclusive<u16> = 0xFFF1..=0xFFF4;
+
+    /// Wraps a raw vendor ID.
+    pub const fn new(vid: u16) -> Self {
+        Self(vid)
+    }
+
+    /// Returns the raw vendor ID.
+    pub const fn get(self) -> u16 {
+        self.0
+    }
+
+    /// Returns `true` for the CSA test vendor IDs (0xFFF1 to 0xFFF4), which
+    /// production commissioners may refuse.
+    pub fn is_test(self) -> bool {
+        Self::TEST_RANGE.contains(&self.0)
+    }
+
+    /// Returns `true` for VID 0, which identifies no vendor.
+    pub const fn is_unassigned(self) -> bool {
+        self.0 == Self::UNASSIGNED.0
+    }
+}
+
+/// A product ID, assigned by its vendor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+pub struct ProductId(u16);
+
+impl ProductId {
+    /// PID 0, meaning no product is specified.
+    pub const UNASSIGNED: Self = Self(0x0000);
+
+    /// Wraps a raw product ID.
+    pub const fn new(pid: u16) -> Self {
+        Self(pid)
+    }
+
+    /// Returns the raw product ID.
+    pub const fn get(self) -> u16 {
+        self.0
+    }
+
+    /// Returns `true` for PID 0, which identifies no product.
+    pub const fn is_unassigned(self) -> bool {
+        self.0 == Self::UNASSIGNED.0
+    }
+}
+
+impl std::fmt::Display for VendorId {
+    /// Formats the ID in hexadecimal, as VIDs are usually written, e.g. `0xFFF1`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:#06X}", self.0)
+    }
+}
+
+impl std::fmt::Display for ProductId {
+    /// Formats the ID in hexadecimal, e.g. `0x8001`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:#06X}", self.0)
+    }
+}
+
+impl From<u16> for VendorId {
+    fn from(vid: u16) -> Self {
+        Self(vid)
+    }
+}
+
+impl From<VendorId> for u16 {
+    fn from(vid: VendorId) -> Self {
+        vid.0
+    }
+}
+
+impl From<u16> for ProductId {
+    fn from(pid: u16) -> Self {
+        Self(pid)
+    }
+}
+
+impl From<ProductId> for u16 {
+    fn from(pid: ProductId) -> Self {
+        pid.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vendor_id_ranges() {
+        assert!(VendorId::new(0xFFF1).is_test());
+        assert!(VendorId::new(0xFFF4).is_test());
+        assert!(!VendorId::new(0xFFF5).is_test());
+        assert!(!VendorId::new(0x1234).is_test());
+        assert!(VendorId::UNASSIGNED.is_unassigned());
+        assert!(!VendorId::new(0xFFF1).is_unassigned());
+        assert!(ProductId::new(0).is_unassigned());
+
+        assert_eq!(VendorId::new(0xFFF1).to_string(), "0xFFF1");
+        assert_eq!(ProductId::new(0x8001).to_string(), "0x8001");
+    }
+}
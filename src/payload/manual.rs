@@ -1,3 +1,4 @@
+use super::SetupPayload;
 use crate::bit_utils::*;
 use crate::error::{PayloadError, Result};
 use crate::verhoeff;
@@ -34,31 +35,41 @@ impl ManualCodeData {
             return Err(PayloadError::InvalidManualCodeLength(len).into());
         }
 
-        // let data_part = &payload[..len - 1];
+        if let Some((offset, character)) = payload.char_indices().find(|(_, c)| !c.is_ascii_digit())
+        {
+            return Err(PayloadError::InvalidManualCodeDigit { offset, character }.into());
+        }
+
         if !verhoeff::validate(payload)? {
             return Err(PayloadError::InvalidManualCodeChecksum.into());
         }
 
-        let first_digit = payload
-            .chars()
-            .next()
-            .and_then(|c| c.to_digit(10))
-            .ok_or(PayloadError::InvalidManualCodeDigit(payload.to_string()))?;
+        Self::decode_digits(payload)
+    }
 
+    /// Decodes the fields of an 11 or 21 digit string, without verifying its check digit.
+    pub(super) fn decode_digits(payload: &str) -> Result<Self> {
+        let digits = payload.as_bytes();
+        let first_digit = digits[0] - b'0';
         if first_digit > 7 {
             return Err(PayloadError::InvalidManualCodePrefix.into());
         }
 
         let is_long = (first_digit & (1 << 2)) != 0;
+        if is_long && digits.len() != 21 {
+            return Err(PayloadError::ManualCodeLengthMismatch(digits.len()).into());
+        }
 
         // --- Parsing Chunks ---
-        // Helper closure to parse slices
+        // Every character is an ASCII digit at this point, so a chunk only
+        // fails if the string is too short for it.
         let parse_chunk = |range: std::ops::Range<usize>| -> Result<u64> {
-            payload
-                .get(range.clone())
-                .ok_or(PayloadError::InvalidManualCodeDigit(payload.to_string()))?
-                .parse::<u64>()
-                .map_err(|e| PayloadError::InvalidManualCodeDigit(e.to_string()).into())
+            let chunk = digits
+                .get(range)
+                .ok_or(PayloadError::InvalidManualCodeLength(digits.len()))?;
+            Ok(chunk
+                .iter()
+                .fold(0, |acc, &digit| acc * 10 + u64::from(digit - b'0')))
         };
 
         let chunk1 = parse_chunk(0..1)?;
@@ -99,3 +110,273 @@ impl ManualCodeData {
         Ok(container)
     }
 }
+
+/// Which form of manual pairing code to generate.
+///
+/// The form normally follows the commissioning flow: the 11-digit code for
+/// the standard flow, the 21-digit code carrying the VID and PID otherwise.
+/// Some vendors print the 21-digit form for every device; a code generated
+/// that way parses back with the custom flow, since the code only records
+/// that VID and PID are present.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ManualCodeLength {
+    /// The form the commissioning flow calls for
+    #[default]
+    Auto,
+    /// The 11-digit form, for the standard flow only
+    Short,
+    /// The 21-digit form, carrying the VID and PID
+    Long,
+}
+
+/// How [`SetupPayload::to_manual_code_str_with`] generates a manual code.
+///
+/// The default generates the code the specification defines, like
+/// [`SetupPayload::to_manual_code_str`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ManualCodeOptions {
+    /// Which form of code to generate
+    pub length: ManualCodeLength,
+    /// Compatibility with earlier releases: take a long discriminator of
+    /// 15 or less for the short discriminator itself, as command lines
+    /// passing a short discriminator where a long one is expected relied
+    /// on. The specification takes its upper 4 bits, which are 0, so
+    /// commissioners do not match the code to the device.
+    pub compat_short_discriminator: bool,
+}
+
+impl From<ManualCodeLength> for ManualCodeOptions {
+    fn from(length: ManualCodeLength) -> Self {
+        ManualCodeOptions {
+            length,
+            ..ManualCodeOptions::default()
+        }
+    }
+}
+
+/// An 11-digit manual pairing code stored as an integer.
+///
+/// Backends often keep manual codes in integer columns, which drops the
+/// leading zero of codes starting with `0`. This wrapper remembers that the
+/// value stands for exactly 11 digits and restores the zeros on display.
+/// The 21-digit form does not fit in a `u64` and must stay a string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+pub struct ManualCodeNumber(u64);
+
+impl ManualCodeNumber {
+    /// Number of digits the value stands for.
+    pub const DIGITS: usize = 11;
+
+    /// Largest value with 11 digits and a valid first digit (<= 7).
+    const MAX: u64 = 79_999_999_999;
+
+    /// Wraps the integer form of an 11-digit manual code.
+    ///
+    /// The check digit is not validated here; parsing does that.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PayloadError::ManualCodeNumberOutOfRange` if `value` has more
+    /// than 11 digits or a first digit above 7.
+    pub fn new(value: u64) -> Result<Self> {
+        if value > Self::MAX {
+            return Err(PayloadError::ManualCodeNumberOutOfRange(value).into());
+        }
+        Ok(ManualCodeNumber(value))
+    }
+
+    /// Returns the integer value.
+    pub const fn get(self) -> u64 {
+        self.0
+    }
+}
+
+impl std::fmt::Display for ManualCodeNumber {
+    /// Formats the code with its leading zeros, as printed on the device.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:011}", self.0)
+    }
+}
+
+impl From<ManualCodeNumber> for u64 {
+    fn from(code: ManualCodeNumber) -> Self {
+        code.0
+    }
+}
+
+impl TryFrom<u64> for ManualCodeNumber {
+    type Error = crate::MatterPayloadError;
+
+    fn try_from(value: u64) -> Result<Self> {
+        ManualCodeNumber::new(value)
+    }
+}
+
+/// Lengths of the digit groups of a printed manual code, alternating until
+/// the digits run out.
+const DISPLAY_GROUPS: [usize; 2] = [4, 3];
+
+/// A valid manual pairing code: 11 or 21 digits with a correct check digit,
+/// decoding to a setup payload.
+///
+/// Holding a `ManualCode` instead of a `String` records that the code was
+/// validated once, when parsed with [`str::parse`] or generated with
+/// [`SetupPayload::to_manual_code`], which parses its own output the same
+/// way, so that APIs accepting one need not check it again. Since both
+/// check the code as [`SetupPayload::parse_str`] does, it converts back to
+/// its [`SetupPayload`] infallibly.
+///
+/// Printed labels group the digits to make them easier to read out and
+/// type: `XXXX-XXX-XXXX` for the 11-digit form, and the same pattern
+/// continued, `XXXX-XXX-XXXX-XXX-XXXX-XXX`, for the 21-digit form. The
+/// alternate format (`{:#}`) and [`ManualCode::grouped`] produce it; the
+/// plain format produces the bare digits.
+///
+/// # Example
+///
+/// ```
+/// use matter_setup_code::{ManualCode, SetupPayload};
+///
+/// let payload = SetupPayload::parse_str("MT:-24J042C00KA0648G00").unwrap();
+/// let code = payload.to_manual_code().unwrap();
+/// assert_eq!(code.to_string(), "34970112332");
+/// assert_eq!(format!("{code:#}"), "3497-011-2332");
+///
+/// let parsed: ManualCode = "34970112332".parse().unwrap();
+/// assert_eq!(parsed, code);
+/// assert_eq!(SetupPayload::from(&parsed).pincode.get(), 20202021);
+/// assert!("34970112333".parse::<ManualCode>().is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "String", into = "String")
+)]
+pub struct ManualCode(String);
+
+impl ManualCode {
+    /// The bare digits.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns `true` for the 21-digit form, carrying the VID and PID.
+    pub fn is_long(&self) -> bool {
+        self.0.len() == 21
+    }
+
+    /// The digits in their printed grouping, separated by dashes.
+    pub fn grouped(&self) -> String {
+        let mut grouped = String::with_capacity(self.0.len() + 5);
+        let mut rest = self.0.as_str();
+        for len in DISPLAY_GROUPS.iter().cycle() {
+            let (group, tail) = rest.split_at(rest.len().min(*len));
+            if !grouped.is_empty() {
+                grouped.push('-');
+            }
+            grouped.push_str(group);
+            rest = tail;
+            if rest.is_empty() {
+                break;
+            }
+        }
+        grouped
+    }
+}
+
+impl SetupPayload {
+    /// Appends the check digit to a manual code stored without it, as
+    /// installer databases often keep them: 10 digits for the 11-digit form,
+    /// 20 for the 21-digit form.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PayloadError::InvalidManualCodeLength` with the completed
+    /// length if `digits` is not 10 or 20 characters long,
+    /// `PayloadError::InvalidManualCodeDigit` for a non-digit, or the errors
+    /// of parsing the completed code.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use matter_setup_code::SetupPayload;
+    ///
+    /// let code = SetupPayload::complete_manual_code("3497011233").unwrap();
+    /// assert_eq!(code.as_str(), "34970112332");
+    /// ```
+    pub fn complete_manual_code(digits: &str) -> Result<ManualCode> {
+        let len = digits.len() + 1;
+        if len != 11 && len != 21 {
+            return Err(PayloadError::InvalidManualCodeLength(len).into());
+        }
+        if let Some((offset, character)) = digits.char_indices().find(|(_, c)| !c.is_ascii_digit())
+        {
+            return Err(PayloadError::InvalidManualCodeDigit { offset, character }.into());
+        }
+        let check_digit = verhoeff::calculate_checksum(digits)?;
+        format!("{digits}{check_digit}").parse()
+    }
+}
+
+impl std::fmt::Display for ManualCode {
+    /// Formats the bare digits, or the printed grouping with `{:#}`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if f.alternate() {
+            f.write_str(&self.grouped())
+        } else {
+            f.write_str(&self.0)
+        }
+    }
+}
+
+impl std::str::FromStr for ManualCode {
+    type Err = crate::MatterPayloadError;
+
+    /// Parses the bare digits of a manual code, checking them as
+    /// [`SetupPayload::parse_str`] does.
+    fn from_str(code: &str) -> Result<Self> {
+        SetupPayload::from_manual_data(ManualCodeData::parse_from_str(code)?)?
+            .with_valid_passcode()?;
+        Ok(ManualCode(code.to_string()))
+    }
+}
+
+impl TryFrom<String> for ManualCode {
+    type Error = crate::MatterPayloadError;
+
+    fn try_from(code: String) -> Result<Self> {
+        code.parse()
+    }
+}
+
+impl TryFrom<&SetupPayload> for ManualCode {
+    type Error = crate::MatterPayloadError;
+
+    fn try_from(payload: &SetupPayload) -> Result<Self> {
+        payload.to_manual_code()
+    }
+}
+
+impl From<&ManualCode> for SetupPayload {
+    fn from(code: &ManualCode) -> Self {
+        SetupPayload::parse_str(&code.0).expect("manual codes are validated when created")
+    }
+}
+
+impl AsRef<str> for ManualCode {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<ManualCode> for String {
+    fn from(code: ManualCode) -> Self {
+        code.0
+    }
+}
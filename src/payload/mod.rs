@@ -3,12 +3,46 @@
 //! Logic for generating and parsing Matter setup payloads.
 
 // Declare the sub-modules. They are private to the `payload` module.
+mod builder;
 mod common;
+mod compare;
+mod concat;
+mod custom_flow;
+mod discriminator;
+mod explain;
+mod extract;
+mod fields;
+mod forensic;
+mod ids;
 mod manual;
+mod optional;
+mod options;
+mod passcode;
 mod qr;
+mod serial;
+mod setters;
+mod source;
+mod template;
+mod vendor;
 
 // Re-export public-facing types for easier use
-pub use common::CommissioningFlow;
+pub use builder::SetupPayloadBuilder;
+pub use common::{CommissioningFlow, DiscoveryCapabilities};
+pub use compare::FieldDiff;
+pub use discriminator::{Discriminator, short_discriminator_from_long};
+pub use explain::FieldExplanation;
+pub use fields::{FieldName, FieldValue};
+pub use forensic::{Defect, PartialParse};
+pub use ids::{ProductId, VendorId};
+pub use manual::{ManualCode, ManualCodeLength, ManualCodeNumber, ManualCodeOptions};
+pub use optional::OptionalDataElement;
+pub use options::ParseOptions;
+pub use passcode::Passcode;
+pub use qr::QrCodeString;
+pub use serial::SerialNumber;
+pub use source::{ParsedPayload, PayloadSource};
+pub use template::PayloadTemplate;
+pub use vendor::VendorElement;
 
 use crate::base38;
 use crate::bit_utils::{bits_to_u64_be, bytes_to_bits_be};
@@ -18,67 +52,171 @@ use deku::prelude::*;
 use manual::ManualCodeData;
 use qr::QrCodeData;
 
+/// Largest setup passcode allowed by the Matter specification (8 decimal digits, minus 99999999).
+pub(crate) const MAX_PASSCODE: u32 = 99_999_998;
+
+/// Passcodes the Matter specification forbids because they are trivially guessable.
+const FORBIDDEN_PASSCODES: [u32; 12] = [
+    0, 11111111, 22222222, 33333333, 44444444, 55555555, 66666666, 77777777, 88888888, 99999999,
+    12345678, 87654321,
+];
+
+/// Returns `true` if `pincode` is in range and not one of the forbidden trivial values.
+pub(crate) fn is_valid_passcode(pincode: u32) -> bool {
+    pincode <= MAX_PASSCODE && !FORBIDDEN_PASSCODES.contains(&pincode)
+}
+
 /// The primary representation of a Matter setup payload.
 ///
 /// This struct holds all the necessary commissioning information and provides
 /// methods to generate QR codes and manual pairing codes, or to parse them
 /// from a string.
-#[derive(Debug, Clone, PartialEq, Eq)]
+///
+/// Payloads are ordered field by field, in declaration order: discriminator
+/// (long ones before short ones, then by value), passcode, discovery
+/// capabilities (none first), flow (by field value), vendor ID, product ID,
+/// version and optional data. The ordering is stable across releases, so batches of
+/// payloads can be kept in a `BTreeSet` or a `HashSet` for deduplication.
+///
+/// The struct is `#[non_exhaustive]` so that fields added by future revisions
+/// of the specification are not breaking changes: outside this crate, create
+/// payloads with [`SetupPayload::new`], [`SetupPayload::builder`] or by
+/// parsing, and prefer the accessor methods to the fields.
+///
+/// Writing a field directly skips the checks of the `set_*` setters, and
+/// nothing else enforces them. [`Passcode`] and [`Discriminator`] keep their
+/// own values in range, but the fields are not kept consistent with one
+/// another or with the specification: a custom flow may lack its vendor ID,
+/// the discovery capabilities may have reserved bits set. Generation only
+/// rejects what a code cannot carry; [`SetupPayload::validate`] lists every
+/// specification violation.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
 pub struct SetupPayload {
-    /// Long discriminator (12 bits)
-    pub long_discriminator: Option<u16>,
-    /// Short discriminator (8 bits)
-    pub short_discriminator: u8,
+    /// Discriminator, long (12 bits) or short (4 bits)
+    pub discriminator: Discriminator,
     /// Setup PIN code (27 bits)
-    pub pincode: u32,
+    pub pincode: Passcode,
     /// Discovery capabilities bitmask
-    pub discovery: Option<u8>,
+    pub discovery: Option<DiscoveryCapabilities>,
     /// Commissioning flow type
     pub flow: CommissioningFlow,
     /// Vendor ID
-    pub vid: Option<u16>,
+    pub vid: Option<VendorId>,
     /// Product ID
-    pub pid: Option<u16>,
+    pub pid: Option<ProductId>,
+    /// Payload format version (3 bits in a QR code, always 0 in a manual code)
+    ///
+    /// Parsing keeps unknown versions; [`SetupPayload::check_spec_version`]
+    /// rejects them.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub version: u8,
+    /// Optional data elements following the fixed fields of a QR code, such
+    /// as a serial number
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub optional_data: Vec<OptionalDataElement>,
+    /// The optional data section exactly as parsed, kept when encoding
+    /// `optional_data` would not reproduce it, e.g. because it has members
+    /// this crate cannot decode or integers wider than necessary; empty
+    /// otherwise
+    ///
+    /// Generating a QR code writes it back verbatim while it still decodes
+    /// to `optional_data`. Once the elements are edited, they are encoded
+    /// anew, followed by the members of this section they cannot represent.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub raw_optional_data: Vec<u8>,
 }
 
 impl SetupPayload {
+    /// Largest TLV-encoded optional data section generated, in bytes.
+    ///
+    /// The specification sets no limit of its own; this is the crate's,
+    /// checked when generating so that a runaway serial number or vendor
+    /// element fails loudly instead of producing an unreadably dense code.
+    pub const MAX_OPTIONAL_DATA_BYTES: usize = 512;
+
+    /// Largest packed QR code payload generated, in bytes: the 11 bytes of
+    /// fixed fields plus [`SetupPayload::MAX_OPTIONAL_DATA_BYTES`].
+    pub const MAX_QR_PAYLOAD_BYTES: usize = qr::QR_PAYLOAD_BYTES + Self::MAX_OPTIONAL_DATA_BYTES;
+
     /// Creates a new SetupPayload
     ///
     /// # Arguments
     ///
-    /// * `discriminator` - 12-bit discriminator value
-    /// * `pincode` - 27-bit setup PIN code
+    /// * `discriminator` - 12-bit discriminator value; higher bits are ignored
+    /// * `pincode` - Setup PIN code
     /// * `rendezvous` - Discovery capabilities bitmask (default: 4 for OnNetwork)
     /// * `flow` - Commissioning flow type (default: Standard)
     /// * `vid` - Vendor ID (default: None)
     /// * `pid` - Product ID (default: None)
     pub fn new(
         discriminator: u16,
-        pincode: u32,
+        pincode: Passcode,
         rendezvous: Option<u8>,
         flow: Option<CommissioningFlow>,
         vid: Option<u16>,
         pid: Option<u16>,
     ) -> Self {
-        let long_discriminator = if discriminator == 0 {
-            None
-        } else {
-            Some(discriminator)
-        };
-        let short_discriminator = (discriminator >> 8) as u8;
-        let discovery = rendezvous.filter(|&d| d != 0);
+        let discriminator = Discriminator::from_long_field(discriminator);
+        let discovery = rendezvous
+            .filter(|&d| d != 0)
+            .map(DiscoveryCapabilities::from);
 
         SetupPayload {
-            long_discriminator,
-            short_discriminator,
+            discriminator,
             pincode,
             discovery,
             flow: flow.unwrap_or(CommissioningFlow::Standard),
-            vid,
-            pid,
+            vid: vid.map(VendorId::new),
+            pid: pid.map(ProductId::new),
+            version: 0,
+            optional_data: Vec::new(),
+            raw_optional_data: Vec::new(),
         }
     }
 
+    /// The discriminator, long or short.
+    pub fn discriminator(&self) -> Discriminator {
+        self.discriminator
+    }
+
+    /// The setup passcode.
+    pub fn passcode(&self) -> Passcode {
+        self.pincode
+    }
+
+    /// The discovery capabilities, absent for a manual code.
+    pub fn discovery(&self) -> Option<DiscoveryCapabilities> {
+        self.discovery
+    }
+
+    /// The commissioning flow.
+    pub fn flow(&self) -> CommissioningFlow {
+        self.flow
+    }
+
+    /// The vendor ID, if the payload carries one.
+    pub fn vendor_id(&self) -> Option<VendorId> {
+        self.vid
+    }
+
+    /// The product ID, if the payload carries one.
+    pub fn product_id(&self) -> Option<ProductId> {
+        self.pid
+    }
+
+    /// The payload format version.
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+
     /// Parses a string to create a `SetupPayload`.
     ///
     /// The string can be either a QR code payload (starting with "MT:") or
@@ -87,87 +225,336 @@ impl SetupPayload {
     /// # Errors
     ///
     /// Returns an error if the payload string is malformed, has an invalid
-    /// checksum, or cannot be decoded.
+    /// checksum, cannot be decoded, or carries a passcode the specification
+    /// forbids.
     pub fn parse_str(payload_str: &str) -> Result<Self> {
-        if payload_str.starts_with("MT:") {
-            let container = QrCodeData::parse_from_str(payload_str)?;
-            Ok(SetupPayload::new(
-                container.discriminator,
-                container.pincode,
-                Some(container.discovery),
-                Some(container.flow),
-                Some(container.vid),
-                Some(container.pid),
-            ))
-        } else {
-            let container = ManualCodeData::parse_from_str(payload_str)?;
-            let mut payload = SetupPayload::new(
-                container.discriminator.into(),
-                ((container.pincode_msb as u32) << 14) | (container.pincode_lsb as u32),
-                None,
-                if container.vid_pid_present != 0 {
-                    Some(CommissioningFlow::Custom)
-                } else {
-                    None
-                },
-                if container.vid_pid_present != 0 {
-                    container.vid
-                } else {
-                    None
-                },
-                if container.vid_pid_present != 0 {
-                    container.pid
-                } else {
-                    None
-                },
-            );
-            payload.short_discriminator = container.discriminator;
-            payload.long_discriminator = None;
-            payload.discovery = None;
-            Ok(payload)
+        Self::parse_str_with_source(payload_str).map(|parsed| parsed.payload)
+    }
+
+    fn from_qr_data(container: QrCodeData) -> Result<Self> {
+        let mut payload = SetupPayload::new(
+            container.discriminator,
+            Passcode::new_unchecked(container.pincode),
+            Some(container.discovery),
+            Some(CommissioningFlow::from_bits(container.flow)),
+            Some(container.vid),
+            Some(container.pid),
+        );
+        payload.version = container.version;
+        (payload.optional_data, payload.raw_optional_data) =
+            optional::decode(&container.optional_data)?;
+        Ok(payload)
+    }
+
+    fn from_manual_data(container: ManualCodeData) -> Result<Self> {
+        let pincode = Passcode::new_unchecked(
+            ((container.pincode_msb as u32) << 14) | (container.pincode_lsb as u32),
+        );
+        let vid_pid_present = container.vid_pid_present != 0;
+        Ok(SetupPayload {
+            discriminator: Discriminator::from_short_field(container.discriminator),
+            pincode,
+            discovery: None,
+            flow: if vid_pid_present {
+                CommissioningFlow::Custom
+            } else {
+                CommissioningFlow::Standard
+            },
+            vid: container.vid.filter(|_| vid_pid_present).map(VendorId::new),
+            pid: container
+                .pid
+                .filter(|_| vid_pid_present)
+                .map(ProductId::new),
+            version: container.version,
+            optional_data: Vec::new(),
+            raw_optional_data: Vec::new(),
+        })
+    }
+
+    /// Rejects a payload read with a passcode the specification forbids.
+    fn with_valid_passcode(self) -> Result<Self> {
+        self.check_passcode()?;
+        Ok(self)
+    }
+
+    /// Parses a payload from raw ASCII bytes, as received from a scanner or socket.
+    ///
+    /// Trailing ASCII whitespace, such as the CR/LF terminator most
+    /// keyboard-wedge and serial scanners send, is ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PayloadError::NonAsciiByte` for the first byte outside the
+    /// ASCII range, or the same errors as [`SetupPayload::parse_str`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use matter_setup_code::{Discriminator, SetupPayload};
+    ///
+    /// let payload = SetupPayload::parse_ascii(b"MT:-24J042C00KA0648G00\r\n").unwrap();
+    /// assert_eq!(payload.discriminator, Discriminator::from_long(3840).unwrap());
+    /// ```
+    pub fn parse_ascii(bytes: &[u8]) -> Result<Self> {
+        let bytes = bytes.trim_ascii_end();
+        if let Some(offset) = bytes.iter().position(|b| !b.is_ascii()) {
+            return Err(PayloadError::NonAsciiByte {
+                offset,
+                byte: bytes[offset],
+            }
+            .into());
+        }
+        // All bytes are ASCII, so this is always valid UTF-8.
+        let payload_str = std::str::from_utf8(bytes).expect("ASCII is valid UTF-8");
+        Self::parse_str(payload_str)
+    }
+
+    /// Parses an 11-digit manual pairing code stored as an integer.
+    ///
+    /// Codes whose first digit is `0` are accepted even though the integer
+    /// has only 10 significant digits.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PayloadError::ManualCodeNumberOutOfRange` if `code` cannot be
+    /// an 11-digit manual code, or the same errors as [`SetupPayload::parse_str`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use matter_setup_code::SetupPayload;
+    ///
+    /// let payload = SetupPayload::parse_manual_code_u64(34970112332).unwrap();
+    /// assert_eq!(payload.pincode.get(), 20202021);
+    /// ```
+    pub fn parse_manual_code_u64(code: u64) -> Result<Self> {
+        Self::parse_str(&ManualCodeNumber::new(code)?.to_string())
+    }
+
+    /// Generates the manual pairing code as an integer.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`SetupPayload::to_manual_code_str`], and
+    /// `PayloadError::LongManualCodeNumber` for a 21-digit code.
+    pub fn to_manual_code_u64(&self) -> Result<ManualCodeNumber> {
+        let code = self.to_manual_code_str()?;
+        if code.len() != ManualCodeNumber::DIGITS {
+            return Err(PayloadError::LongManualCodeNumber.into());
         }
+        let value = code
+            .bytes()
+            .fold(0, |acc, digit| acc * 10 + u64::from(digit - b'0'));
+        ManualCodeNumber::new(value)
+    }
+
+    /// Returns `true` if a device advertising the 12-bit `discriminator` may be
+    /// the one this payload describes.
+    ///
+    /// Payloads carrying a long discriminator must match it exactly; payloads
+    /// parsed from a manual code only compare its upper 4 bits.
+    pub fn matches_discriminator(&self, discriminator: u16) -> bool {
+        self.discriminator.matches(discriminator)
+    }
+
+    /// Returns `true` if the payload's short discriminator is `short`, as
+    /// when matching a device that only advertises the upper 4 bits.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use matter_setup_code::SetupPayload;
+    ///
+    /// let payload = SetupPayload::parse_str("MT:-24J042C00KA0648G00").unwrap();
+    /// assert!(payload.matches_short(15));
+    /// assert!(!payload.matches_short(4));
+    /// ```
+    pub fn matches_short(&self, short: u8) -> bool {
+        self.discriminator.short() == short
     }
 
     /// Generates the QR code string ("MT:...") for this payload.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PayloadError::MissingVendorId`, `PayloadError::MissingProductId`,
+    /// `PayloadError::MissingDiscovery` or `PayloadError::MissingLongDiscriminator`
+    /// if the payload lacks a field the QR code carries, as payloads parsed
+    /// from a manual code do, `PayloadError::ReservedCommissioningFlow`
+    /// if the flow is a reserved value, `PayloadError::InvalidPasscode` if
+    /// the passcode is forbidden, `PayloadError::DiscriminatorTooLarge` if
+    /// the discriminator does not fit in 12 bits, or `PayloadError::PayloadTooLarge`
+    /// if the optional data makes it exceed [`SetupPayload::MAX_QR_PAYLOAD_BYTES`].
     pub fn to_qr_code_str(&self) -> Result<String> {
+        let bytes = self.to_packed_bytes()?;
+        let encoded = base38::encode(&bytes);
+        Ok(format!("MT:{}", encoded))
+    }
+
+    /// Generates the QR code string like [`SetupPayload::to_qr_code_str`],
+    /// as a [`QrCodeString`].
+    ///
+    /// # Errors
+    /// Returns the same errors as [`SetupPayload::to_qr_code_str`], or
+    /// those of parsing the generated string as a [`QrCodeString`].
+    pub fn to_qr_code(&self) -> Result<QrCodeString> {
+        self.to_qr_code_str()?.parse()
+    }
+
+    /// Packs the QR code fields into the bytes that the QR code
+    /// base38-encodes, as the Matter SDK stores them: the 11 bytes of fixed
+    /// fields, least significant byte first, followed by the TLV-encoded
+    /// optional data, if any. Useful to carry the payload over BLE or NFC, or
+    /// to store it in factory flash.
+    ///
+    /// # Errors
+    /// Returns the same errors as [`SetupPayload::to_qr_code_str`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use matter_setup_code::SetupPayload;
+    ///
+    /// let payload = SetupPayload::parse_str("MT:-24J042C00KA0648G00").unwrap();
+    /// let bytes = payload.to_packed_bytes().unwrap();
+    /// assert_eq!(bytes.len(), 11);
+    /// assert_eq!(SetupPayload::from_packed_bytes(&bytes).unwrap(), payload);
+    /// ```
+    pub fn to_packed_bytes(&self) -> Result<Vec<u8>> {
+        self.check_flow()?;
+        self.check_passcode()?;
+        self.discriminator.checked()?;
         let qr_data = QrCodeData {
-            version: 0,
-            vid: self.vid.expect("VID is required for QR code generation"),
-            pid: self.pid.expect("PID is required for QR code generation"),
-            flow: self.flow,
-            discovery: self
-                .discovery
-                .expect("Discovery is required for QR code generation"),
+            version: self.version,
+            vid: self.vid.ok_or(PayloadError::MissingVendorId)?.get(),
+            pid: self.pid.ok_or(PayloadError::MissingProductId)?.get(),
+            flow: self.flow.bits(),
+            discovery: self.discovery.ok_or(PayloadError::MissingDiscovery)?.bits(),
             discriminator: self
-                .long_discriminator
-                .expect("Long discriminator is required for QR code generation"),
-            pincode: self.pincode,
+                .discriminator
+                .long()
+                .ok_or(PayloadError::MissingLongDiscriminator)?,
+            pincode: self.pincode.get(),
             padding: 0,
+            optional_data: optional::encode(&self.optional_data, &self.raw_optional_data),
         };
+        let packed = qr_data.to_packed()?;
+        if packed.len() > Self::MAX_QR_PAYLOAD_BYTES {
+            return Err(PayloadError::PayloadTooLarge(packed.len()).into());
+        }
+        Ok(packed)
+    }
 
-        let mut bytes = qr_data.to_bytes()?;
-        bytes.reverse();
-        let encoded = base38::encode(&bytes);
-        Ok(format!("MT:{}", encoded))
+    /// The number of base38 characters of the QR code string, "MT:" prefix
+    /// excluded: 19 without optional data. Useful to pick the QR code size
+    /// or to warn about dense codes.
+    ///
+    /// # Errors
+    /// Returns the same errors as [`SetupPayload::to_qr_code_str`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use matter_setup_code::SetupPayload;
+    ///
+    /// let payload =
+    ///     SetupPayload::parse_str("MT:-24J042C00KA064IJ3P0-C670NSXK1S3DO1UXSC1O0").unwrap();
+    /// assert_eq!(payload.qr_code_base38_len().unwrap(), 42);
+    /// ```
+    pub fn qr_code_base38_len(&self) -> Result<usize> {
+        Ok(base38::encoded_len(self.to_packed_bytes()?.len()))
+    }
+
+    /// Unpacks the bytes produced by [`SetupPayload::to_packed_bytes`].
+    ///
+    /// # Errors
+    /// Returns `PayloadError::InvalidPackedLength` if `bytes` is shorter than
+    /// 11 bytes, or an error if the passcode or the optional data is invalid.
+    pub fn from_packed_bytes(bytes: &[u8]) -> Result<Self> {
+        Self::from_qr_data(QrCodeData::from_packed(bytes)?)?.with_valid_passcode()
     }
 
-    /// Generates the numeric manual pairing code string for this payload.
+    /// Generates the numeric manual pairing code string for this payload: 11
+    /// digits for the standard commissioning flow, 21 digits carrying the
+    /// vendor ID and product ID for the other flows.
     ///
     /// # Errors
-    /// Returns an error if the short discriminator is out of range (> 15).
+    /// Returns an error if the discriminator does not fit its field,
+    /// the flow is a reserved value or the passcode is forbidden, and
+    /// `PayloadError::MissingVendorId` or
+    /// `PayloadError::MissingProductId` if a 21-digit code lacks its VID or PID.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use matter_setup_code::{CommissioningFlow, ProductId, SetupPayload};
+    ///
+    /// let mut payload = SetupPayload::parse_str("MT:-24J042C00KA0648G00").unwrap();
+    /// assert_eq!(payload.to_manual_code_str().unwrap(), "34970112332");
+    ///
+    /// payload.flow = CommissioningFlow::Custom;
+    /// payload.pid = Some(ProductId::new(0x8001));
+    /// assert_eq!(payload.to_manual_code_str().unwrap(), "749701123365521327694");
+    /// ```
     pub fn to_manual_code_str(&self) -> Result<String> {
+        self.to_manual_code_str_with(ManualCodeLength::Auto)
+    }
+
+    /// Generates the manual pairing code like [`SetupPayload::to_manual_code_str`],
+    /// as a [`ManualCode`] that can also be displayed in its printed grouping.
+    ///
+    /// # Errors
+    /// Returns the same errors as [`SetupPayload::to_manual_code_str`], or
+    /// those of parsing the generated code as a [`ManualCode`].
+    pub fn to_manual_code(&self) -> Result<ManualCode> {
+        self.to_manual_code_str()?.parse()
+    }
+
+    /// Generates the manual pairing code as `options` say: in the form
+    /// they ask for, whatever the commissioning flow, and with the
+    /// compatibility behaviors they enable. A [`ManualCodeLength`] alone
+    /// selects the form.
+    ///
+    /// # Errors
+    /// Returns the same errors as [`SetupPayload::to_manual_code_str`], and
+    /// `PayloadError::ShortManualCodeFlow` if an 11-digit code is asked for
+    /// a flow other than standard, which it cannot represent.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use matter_setup_code::{ManualCodeLength, SetupPayload};
+    ///
+    /// let payload = SetupPayload::parse_str("MT:-24J042C00KA0648G00").unwrap();
+    /// assert_eq!(
+    ///     payload.to_manual_code_str_with(ManualCodeLength::Long).unwrap(),
+    ///     "749701123365521327694"
+    /// );
+    /// ```
+    pub fn to_manual_code_str_with(&self, options: impl Into<ManualCodeOptions>) -> Result<String> {
+        let options = options.into();
+        self.check_flow()?;
+        self.check_passcode()?;
+        self.discriminator.checked()?;
+        let has_vid_pid = match options.length {
+            ManualCodeLength::Auto => self.flow != CommissioningFlow::Standard,
+            ManualCodeLength::Short if self.flow != CommissioningFlow::Standard => {
+                return Err(PayloadError::ShortManualCodeFlow(self.flow.bits()).into());
+            }
+            ManualCodeLength::Short => false,
+            ManualCodeLength::Long => true,
+        };
+        if has_vid_pid {
+            self.vid.ok_or(PayloadError::MissingVendorId)?;
+            self.pid.ok_or(PayloadError::MissingProductId)?;
+        }
         // 1. Map Payload to ManualCode Struct
-        // WARNING: Divergence from standard/Python implementation
-        // To support round-trip generation via CLI where a user might pass a small integer
-        // (e.g. 2) as 'discriminator' expecting it to be the short discriminator,
-        // we check if the calculated short_discriminator is 0 AND the long_discriminator
-        // is small enough to fit in the 4-bit manual code discriminator field (<= 15).
-        let discriminator_val =
-            if self.short_discriminator == 0 && self.long_discriminator.unwrap_or(0) <= 15 {
-                self.long_discriminator.unwrap_or(0) as u8
-            } else {
-                self.short_discriminator
-            };
+        // The compatibility option keeps the behavior of earlier releases, which
+        // took a long discriminator of 15 or less for the short discriminator.
+        let discriminator_val = match self.discriminator.long() {
+            Some(long) if long <= 15 && options.compat_short_discriminator => long as u8,
+            _ => self.discriminator.short(),
+        };
 
         // Safety check: The discriminator in ManualCode must be 4 bits (0-15).
         if discriminator_val > 15 {
@@ -176,25 +563,21 @@ impl SetupPayload {
 
         let manual_code = ManualCodeData {
             version: 0, // Currently always 0
-            vid_pid_present: if self.flow == CommissioningFlow::Standard {
-                0
-            } else {
-                1
-            },
+            vid_pid_present: u8::from(has_vid_pid),
             // Discriminator in ManualCode is 4 bits.
             discriminator: discriminator_val,
             // Split 27-bit PIN: Bottom 14 bits -> LSB, Top 13 bits -> MSB
-            pincode_lsb: (self.pincode & 0x3FFF) as u16,
-            pincode_msb: ((self.pincode >> 14) & 0x1FFF) as u16,
-            vid: if self.flow == CommissioningFlow::Standard {
-                Some(0)
+            pincode_lsb: (self.pincode.get() & 0x3FFF) as u16,
+            pincode_msb: ((self.pincode.get() >> 14) & 0x1FFF) as u16,
+            vid: if has_vid_pid {
+                self.vid.map(VendorId::get)
             } else {
-                self.vid
-            },
-            pid: if self.flow == CommissioningFlow::Standard {
                 Some(0)
+            },
+            pid: if has_vid_pid {
+                self.pid.map(ProductId::get)
             } else {
-                self.pid
+                Some(0)
             },
             padding: 0,
         };
@@ -221,14 +604,14 @@ impl SetupPayload {
         // Start building the string
         let mut code_string = format!("{}{:05}{:04}", c1, c2, c3);
 
-        // if has_vid_pid {
-        //     // Chunk 4: 16 bits (VID) -> 5 Digits
-        //     let c4 = bits_to_u64_be(&bits[33..49]);
-        //     // Chunk 5: 16 bits (PID) -> 5 Digits
-        //     let c5 = bits_to_u64_be(&bits[49..65]);
+        if has_vid_pid {
+            // Chunk 4: 16 bits (VID) -> 5 Digits
+            let c4 = bits_to_u64_be(&bits[33..49]);
+            // Chunk 5: 16 bits (PID) -> 5 Digits
+            let c5 = bits_to_u64_be(&bits[49..65]);
 
-        //     code_string.push_str(&format!("{:05}{:05}", c4, c5));
-        // }
+            code_string.push_str(&format!("{:05}{:05}", c4, c5));
+        }
 
         // 5. Calculate Checksum (Verhoeff)
         let checksum_digit = calculate_checksum(&code_string)?;
@@ -238,24 +621,44 @@ impl SetupPayload {
 
         Ok(code_string)
     }
+
+    /// Refuses to generate codes with a reserved commissioning flow.
+    fn check_flow(&self) -> Result<()> {
+        if let CommissioningFlow::Reserved(bits) = self.flow {
+            return Err(PayloadError::ReservedCommissioningFlow(bits).into());
+        }
+        Ok(())
+    }
+
+    /// Refuses to generate codes with a passcode the specification forbids,
+    /// which only a leniently parsed payload can hold.
+    fn check_passcode(&self) -> Result<()> {
+        if !self.pincode.is_valid() {
+            return Err(PayloadError::InvalidPasscode(self.pincode.get()).into());
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::MatterPayloadError;
+    use crate::tlv::TlvValue;
 
     use super::*;
 
     // A standard payload for consistent testing
     fn standard_payload() -> SetupPayload {
         SetupPayload {
-            short_discriminator: 4,
-            long_discriminator: Some(1132),
-            pincode: 69414998,
-            vid: Some(0xfff1),
-            pid: Some(0x8000),
+            discriminator: Discriminator::from_long(1132).unwrap(),
+            pincode: Passcode::new(69414998).unwrap(),
+            vid: Some(VendorId::new(0xfff1)),
+            pid: Some(ProductId::new(0x8000)),
             flow: CommissioningFlow::Standard,
-            discovery: Some(4),
+            discovery: Some(DiscoveryCapabilities::ON_NETWORK),
+            version: 0,
+            optional_data: Vec::new(),
+            raw_optional_data: Vec::new(),
         }
     }
 
@@ -290,32 +693,266 @@ mod tests {
 
         // Note: Manual parsing reconstructs the short discriminator into the high bits of the 12-bit field.
         assert_eq!(
-            original_payload.short_discriminator,
-            parsed_payload.short_discriminator
+            original_payload.discriminator.short(),
+            parsed_payload.discriminator.short()
         );
         assert_eq!(original_payload.pincode, parsed_payload.pincode);
     }
 
+    #[test]
+    fn test_long_manual_code() {
+        // ./chip-tool payload generate -d 3840 -p 20202021 -vid 65521 -pid 32769 -cf 2
+        // Manualcode : 749701123365521327694
+        let mut payload = SetupPayload::parse_str("MT:-24J042C00KA0648G00").unwrap();
+        payload.flow = CommissioningFlow::Custom;
+        payload.pid = Some(ProductId::new(0x8001));
+        let manual_str = payload.to_manual_code_str().unwrap();
+        assert_eq!(manual_str, "749701123365521327694");
+
+        let parsed = SetupPayload::parse_str(&manual_str).unwrap();
+        assert_eq!(parsed.flow, CommissioningFlow::Custom);
+        assert_eq!(parsed.vid, payload.vid);
+        assert_eq!(parsed.pid, payload.pid);
+        assert_eq!(parsed.pincode, payload.pincode);
+        assert_eq!(parsed.to_manual_code_str().unwrap(), manual_str);
+
+        // The user intent flow carries VID and PID as well.
+        payload.flow = CommissioningFlow::UserIntent;
+        assert_eq!(payload.to_manual_code_str().unwrap().len(), 21);
+        assert_eq!(
+            payload.to_manual_code_u64().unwrap_err(),
+            MatterPayloadError::Payload(PayloadError::LongManualCodeNumber)
+        );
+
+        payload.pid = None;
+        assert_eq!(
+            payload.to_manual_code_str().unwrap_err(),
+            MatterPayloadError::Payload(PayloadError::MissingProductId)
+        );
+    }
+
+    #[test]
+    fn test_manual_code_length() {
+        let mut payload = SetupPayload::parse_str("MT:-24J042C00KA0648G00").unwrap();
+        assert_eq!(
+            payload
+                .to_manual_code_str_with(ManualCodeLength::Short)
+                .unwrap(),
+            "34970112332"
+        );
+        let long = payload
+            .to_manual_code_str_with(ManualCodeLength::Long)
+            .unwrap();
+        assert_eq!(long, "749701123365521327694");
+        let parsed = SetupPayload::parse_str(&long).unwrap();
+        assert_eq!(parsed.flow, CommissioningFlow::Custom);
+        assert_eq!((parsed.vid, parsed.pid), (payload.vid, payload.pid));
+
+        payload.flow = CommissioningFlow::UserIntent;
+        assert_eq!(
+            payload
+                .to_manual_code_str_with(ManualCodeLength::Short)
+                .unwrap_err(),
+            MatterPayloadError::Payload(PayloadError::ShortManualCodeFlow(1))
+        );
+
+        let mut manual = SetupPayload::parse_str("34970112332").unwrap();
+        assert_eq!(
+            manual
+                .to_manual_code_str_with(ManualCodeLength::Long)
+                .unwrap_err(),
+            MatterPayloadError::Payload(PayloadError::MissingVendorId)
+        );
+        manual.vid = Some(VendorId::new(0xFFF1));
+        manual.pid = Some(ProductId::new(0x8001));
+        assert_eq!(
+            manual
+                .to_manual_code_str_with(ManualCodeLength::Long)
+                .unwrap(),
+            long
+        );
+    }
+
+    #[test]
+    fn test_compat_short_discriminator() {
+        let mut payload = standard_payload();
+        payload.discriminator = Discriminator::from_long(2).unwrap();
+        let mut short = payload.clone();
+        short.discriminator = Discriminator::from_short(2).unwrap();
+
+        // Long discriminator 2 has short discriminator 0.
+        let strict = payload.to_manual_code_str().unwrap();
+        let parsed = SetupPayload::parse_str(&strict).unwrap();
+        assert_eq!(parsed.discriminator, Discriminator::from_short(0).unwrap());
+
+        let compat = payload
+            .to_manual_code_str_with(ManualCodeOptions {
+                compat_short_discriminator: true,
+                ..ManualCodeOptions::default()
+            })
+            .unwrap();
+        assert_ne!(compat, strict);
+        assert_eq!(compat, short.to_manual_code_str().unwrap());
+    }
+
+    #[test]
+    fn test_manual_code_grouping() {
+        let mut payload = SetupPayload::parse_str("MT:-24J042C00KA0648G00").unwrap();
+        let code = payload.to_manual_code().unwrap();
+        assert!(!code.is_long());
+        assert_eq!(code.grouped(), "3497-011-2332");
+
+        payload.flow = CommissioningFlow::Custom;
+        payload.pid = Some(ProductId::new(0x8001));
+        let code = payload.to_manual_code().unwrap();
+        assert!(code.is_long());
+        assert_eq!(code.as_str(), "749701123365521327694");
+        assert_eq!(format!("{code:#}"), "7497-011-2336-552-1327-694");
+        assert_eq!(String::from(code), "749701123365521327694");
+    }
+
+    #[test]
+    fn test_qr_code_string() {
+        let payload = standard_payload();
+        let qr = payload.to_qr_code().unwrap();
+        assert_eq!(qr.as_str(), "MT:Y.K904QI143LH13SH10");
+        assert_eq!(qr.base38(), "Y.K904QI143LH13SH10");
+        assert_eq!(SetupPayload::from(&qr), payload);
+        assert_eq!(
+            "MT:Y.K904QI143LH13SH10".parse::<QrCodeString>().unwrap(),
+            qr
+        );
+
+        assert_eq!(
+            "11237442363".parse::<QrCodeString>().unwrap_err(),
+            MatterPayloadError::Payload(PayloadError::InvalidQrCodePrefix)
+        );
+        assert!(QrCodeString::try_from("MT:Y.K904QI143LH13SH1".to_string()).is_err());
+        assert!(QrCodeString::try_from(&SetupPayload::parse_str("11237442363").unwrap()).is_err());
+
+        // The SDK example code with passcode 12345678, parsed leniently.
+        let lenient =
+            SetupPayload::parse_str_with("MT:-24J042C004QG46Y900", &ParseOptions::LENIENT).unwrap();
+        assert_eq!(
+            lenient.to_qr_code().unwrap_err(),
+            MatterPayloadError::Payload(PayloadError::InvalidPasscode(12345678))
+        );
+    }
+
+    #[test]
+    fn test_manual_code_parse() {
+        let code: ManualCode = "749701123365521327694".parse().unwrap();
+        let payload = SetupPayload::from(&code);
+        assert_eq!(payload.pid, Some(ProductId::new(0x8001)));
+        assert_eq!(ManualCode::try_from(&payload).unwrap(), code);
+
+        assert_eq!(
+            "3497-011-2332".parse::<ManualCode>().unwrap_err(),
+            MatterPayloadError::Payload(PayloadError::InvalidManualCodeLength(13))
+        );
+        assert_eq!(
+            ManualCode::try_from("34970112333".to_string()).unwrap_err(),
+            MatterPayloadError::Payload(PayloadError::InvalidManualCodeChecksum)
+        );
+        assert!("MT:-24J042C00KA0648G00".parse::<ManualCode>().is_err());
+
+        // The SDK example code with passcode 12345678, parsed leniently.
+        let lenient = SetupPayload::parse_str_with("35767807533", &ParseOptions::LENIENT).unwrap();
+        assert_eq!(
+            lenient.to_manual_code().unwrap_err(),
+            MatterPayloadError::Payload(PayloadError::InvalidPasscode(12345678))
+        );
+    }
+
     #[test]
     fn test_short_manual_code() {
         let payload = SetupPayload {
-            short_discriminator: 4,
-            long_discriminator: None,
+            discriminator: Discriminator::from_short(4).unwrap(),
             vid: None,
             pid: None,
-            pincode: 69414998,
+            pincode: Passcode::new(69414998).unwrap(),
             flow: CommissioningFlow::Standard,
-            discovery: Some(0),
+            discovery: Some(DiscoveryCapabilities::from_bits(0)),
+            version: 0,
+            optional_data: Vec::new(),
+            raw_optional_data: Vec::new(),
         };
         let manual_str = payload.to_manual_code_str().unwrap();
         // Python ref: 11237442363
         assert_eq!(manual_str, "11237442363");
 
         let parsed = SetupPayload::parse_str(&manual_str).unwrap();
-        assert_eq!(payload.short_discriminator, parsed.short_discriminator);
+        assert_eq!(payload.discriminator, parsed.discriminator);
         assert_eq!(payload.pincode, parsed.pincode);
     }
 
+    #[test]
+    fn test_qr_code_missing_fields() {
+        let manual = SetupPayload::parse_str("11237442363").unwrap();
+        assert_eq!(
+            manual.to_qr_code_str().unwrap_err(),
+            MatterPayloadError::Payload(PayloadError::MissingVendorId)
+        );
+
+        let mut payload = standard_payload();
+        payload.discovery = None;
+        assert_eq!(
+            payload.to_qr_code_str().unwrap_err(),
+            MatterPayloadError::Payload(PayloadError::MissingDiscovery)
+        );
+
+        let mut payload = standard_payload();
+        payload.discriminator = Discriminator::from_short(4).unwrap();
+        assert_eq!(
+            payload.to_qr_code_str().unwrap_err(),
+            MatterPayloadError::Payload(PayloadError::MissingLongDiscriminator)
+        );
+
+        // Variants built directly are checked when generating.
+        payload.discriminator = Discriminator::Long(0x1000);
+        let too_large = MatterPayloadError::Payload(PayloadError::DiscriminatorTooLarge(0x1000));
+        assert_eq!(payload.to_qr_code_str().unwrap_err(), too_large);
+        assert_eq!(payload.to_manual_code_str().unwrap_err(), too_large);
+        payload.discriminator = Discriminator::Short(16);
+        assert_eq!(
+            payload.to_manual_code_str().unwrap_err(),
+            MatterPayloadError::Payload(PayloadError::DiscriminatorOutOfRange(16))
+        );
+    }
+
+    #[test]
+    fn test_reserved_commissioning_flow() {
+        // The SDK example code with flow value 3.
+        let payload = SetupPayload::parse_str("MT:-24J0UBG00KA0648G00").unwrap();
+        assert_eq!(payload.flow, CommissioningFlow::Reserved(3));
+        assert!(payload.flow.is_reserved());
+        assert_eq!(
+            payload.discriminator,
+            Discriminator::from_long(3840).unwrap()
+        );
+
+        let err = payload.to_qr_code_str().unwrap_err();
+        assert_eq!(
+            err,
+            MatterPayloadError::Payload(PayloadError::ReservedCommissioningFlow(3))
+        );
+        assert!(payload.to_manual_code_str().is_err());
+    }
+
+    #[test]
+    fn test_qr_code_version_roundtrip() {
+        // The SDK example code with version 1.
+        let payload = SetupPayload::parse_str("MT:.24J042C00KA0648G00").unwrap();
+        assert_eq!(payload.version, 1);
+        assert_eq!(payload.to_qr_code_str().unwrap(), "MT:.24J042C00KA0648G00");
+        assert_eq!(
+            SetupPayload::parse_str("MT:-24J042C00KA0648G00")
+                .unwrap()
+                .version,
+            0
+        );
+    }
+
     #[test]
     fn test_invalid_manual_code_errors() {
         // Invalid length
@@ -331,5 +968,273 @@ mod tests {
             err,
             MatterPayloadError::Payload(PayloadError::InvalidManualCodeChecksum)
         ));
+
+        // VID/PID bit set in an 11-digit code with a valid check digit
+        assert_eq!(
+            SetupPayload::parse_str("74970112334").unwrap_err(),
+            MatterPayloadError::Payload(PayloadError::ManualCodeLengthMismatch(11))
+        );
+        assert!(
+            SetupPayload::parse_best_effort("74970112330")
+                .payload
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_parse_ascii() {
+        assert_eq!(
+            SetupPayload::parse_ascii(b"MT:Y.K904QI143LH13SH10\n").unwrap(),
+            standard_payload()
+        );
+        assert_eq!(
+            SetupPayload::parse_ascii(b"11237442363").unwrap(),
+            SetupPayload::parse_str("11237442363").unwrap()
+        );
+
+        let err = SetupPayload::parse_ascii(b"MT:Y.K9\xC304QI143LH13SH10").unwrap_err();
+        assert!(matches!(
+            err,
+            MatterPayloadError::Payload(PayloadError::NonAsciiByte {
+                offset: 7,
+                byte: 0xC3
+            })
+        ));
+    }
+
+    #[test]
+    fn test_manual_code_u64_keeps_leading_zero() {
+        let payload = SetupPayload {
+            discriminator: Discriminator::from_short(0).unwrap(),
+            vid: None,
+            pid: None,
+            pincode: Passcode::new(20202021).unwrap(),
+            flow: CommissioningFlow::Standard,
+            discovery: None,
+            version: 0,
+            optional_data: Vec::new(),
+            raw_optional_data: Vec::new(),
+        };
+        let code = payload.to_manual_code_u64().unwrap();
+        let string = payload.to_manual_code_str().unwrap();
+        assert!(string.starts_with('0'));
+        assert_eq!(code.to_string(), string);
+        assert_eq!(code.get(), string.parse::<u64>().unwrap());
+
+        let parsed = SetupPayload::parse_manual_code_u64(code.get()).unwrap();
+        assert_eq!(parsed.pincode.get(), 20202021);
+        assert_eq!(parsed.discriminator, Discriminator::from_short(0).unwrap());
+
+        let err = SetupPayload::parse_manual_code_u64(80_000_000_000).unwrap_err();
+        assert!(matches!(
+            err,
+            MatterPayloadError::Payload(PayloadError::ManualCodeNumberOutOfRange(80_000_000_000))
+        ));
+    }
+
+    #[test]
+    fn test_matches_discriminator() {
+        let payload = standard_payload();
+        assert!(payload.matches_discriminator(1132));
+        assert!(!payload.matches_discriminator(1133));
+
+        // A manual code only pins down the upper 4 bits.
+        let manual = SetupPayload::parse_str("11237442363").unwrap();
+        assert!(manual.matches_discriminator(1132));
+        assert!(manual.matches_discriminator(0x4FF));
+        assert!(!manual.matches_discriminator(0x500));
+    }
+
+    #[test]
+    fn test_invalid_manual_code_digit() {
+        let err = SetupPayload::parse_str("1123744x363").unwrap_err();
+        assert_eq!(
+            err,
+            MatterPayloadError::Payload(PayloadError::InvalidManualCodeDigit {
+                offset: 7,
+                character: 'x'
+            })
+        );
+        assert_eq!(
+            PayloadError::InvalidManualCodeDigit {
+                offset: 7,
+                character: 'x'
+            }
+            .to_string(),
+            "manual code contains invalid character 'x' at offset 7"
+        );
+    }
+
+    #[test]
+    fn test_newer_discovery_capabilities_roundtrip() {
+        // Same device as the SDK example "MT:-24J042C00KA0648G00", advertising
+        // over the transports added in Matter 1.4.
+        let cases = [
+            (DiscoveryCapabilities::WIFI_PAF, "MT:-24J0M3810KA0648G00"),
+            (DiscoveryCapabilities::NFC, "MT:-24J08IF20KA0648G00"),
+            (
+                DiscoveryCapabilities::BLE
+                    | DiscoveryCapabilities::WIFI_PAF
+                    | DiscoveryCapabilities::NFC,
+                "MT:-24J008Y30KA0648G00",
+            ),
+            // A reserved bit survives the round trip untouched.
+            (
+                DiscoveryCapabilities::from_bits(0x82),
+                "MT:-24J088GJ0KA0648G00",
+            ),
+        ];
+        for (capabilities, qr) in cases {
+            let payload = SetupPayload::new(
+                3840,
+                Passcode::new(20202021).unwrap(),
+                Some(capabilities.bits()),
+                None,
+                Some(0xFFF1),
+                Some(0x8001),
+            );
+            assert_eq!(payload.to_qr_code_str().unwrap(), qr);
+            assert_eq!(
+                SetupPayload::parse_str(qr).unwrap().discovery,
+                Some(capabilities)
+            );
+        }
+    }
+
+    #[test]
+    fn test_discovery_capabilities_names() {
+        let capabilities = DiscoveryCapabilities::BLE | DiscoveryCapabilities::WIFI_PAF;
+        assert_eq!(
+            capabilities.names().collect::<Vec<_>>(),
+            vec!["BLE", "WiFiPAF"]
+        );
+        assert_eq!(capabilities.to_string(), "BLE | WiFiPAF");
+        assert!(capabilities.contains(DiscoveryCapabilities::BLE));
+        assert!(!capabilities.contains(DiscoveryCapabilities::NFC));
+        assert_eq!(capabilities.reserved_bits(), 0);
+
+        let reserved = DiscoveryCapabilities::from_bits(0x84);
+        assert_eq!(reserved.reserved_bits(), 0x80);
+        assert_eq!(reserved.to_string(), "OnNetwork | 0x80");
+        assert_eq!(DiscoveryCapabilities::default().to_string(), "None");
+    }
+
+    #[test]
+    fn test_payload_ordering() {
+        use std::collections::{BTreeSet, HashSet};
+
+        let sdk = SetupPayload::parse_str("MT:-24J042C00KA0648G00").unwrap();
+        let chip_tool = SetupPayload::parse_str("MT:Y.K904QI143LH13SH10").unwrap();
+        let manual = SetupPayload::parse_str("34970112332").unwrap();
+        let batch = [sdk.clone(), manual.clone(), chip_tool.clone(), sdk.clone()];
+
+        assert_eq!(batch.iter().collect::<HashSet<_>>().len(), 3);
+        let sorted: BTreeSet<_> = batch.into_iter().collect();
+        assert!(sorted.into_iter().eq([chip_tool, sdk, manual]));
+        assert!(CommissioningFlow::Custom < CommissioningFlow::Reserved(3));
+    }
+
+    #[test]
+    fn test_packed_bytes() {
+        let payload = SetupPayload::parse_str("MT:Y.K904QI143LH13SH10").unwrap();
+        let bytes = payload.to_packed_bytes().unwrap();
+        assert_eq!(base38::encode(&bytes), "Y.K904QI143LH13SH10");
+        assert_eq!(SetupPayload::from_packed_bytes(&bytes).unwrap(), payload);
+
+        assert_eq!(
+            SetupPayload::from_packed_bytes(&bytes[..10]).unwrap_err(),
+            MatterPayloadError::Payload(PayloadError::InvalidPackedLength(10))
+        );
+    }
+
+    #[test]
+    fn test_qr_code_size_limit() {
+        let mut payload = SetupPayload::parse_str("MT:-24J042C00KA0648G00").unwrap();
+        assert_eq!(payload.qr_code_base38_len().unwrap(), 19);
+
+        // 11 fixed bytes, then a 6-byte TLV overhead around the string.
+        payload.optional_data.push(OptionalDataElement {
+            tag: 0x80,
+            value: TlvValue::Utf8("x".repeat(506)),
+        });
+        assert_eq!(SetupPayload::MAX_QR_PAYLOAD_BYTES, 523);
+        assert_eq!(
+            payload.to_packed_bytes().unwrap().len(),
+            SetupPayload::MAX_QR_PAYLOAD_BYTES
+        );
+        assert_eq!(payload.qr_code_base38_len().unwrap(), 872);
+
+        payload.optional_data[0].value = TlvValue::Utf8("x".repeat(507));
+        assert_eq!(
+            payload.to_qr_code_str().unwrap_err(),
+            MatterPayloadError::Payload(PayloadError::PayloadTooLarge(524))
+        );
+    }
+
+    #[test]
+    fn test_accessors() {
+        let payload = SetupPayload::parse_str("MT:-24J042C00KA0648G00").unwrap();
+        assert_eq!(
+            payload.discriminator(),
+            Discriminator::from_long(3840).unwrap()
+        );
+        assert_eq!(payload.passcode().get(), 20202021);
+        assert_eq!(payload.discovery(), Some(DiscoveryCapabilities::BLE));
+        assert_eq!(payload.flow(), CommissioningFlow::Standard);
+        assert_eq!(payload.vendor_id(), Some(VendorId::new(0xFFF1)));
+        assert_eq!(payload.product_id(), Some(ProductId::new(0x8001)));
+        assert_eq!(payload.version(), 0);
+    }
+
+    #[test]
+    fn test_qr_code_optional_data() {
+        // The SDK example code with serial number "SN1234" and vendor tag 0x80 = 42.
+        let qr = "MT:-24J042C00KA064IJ3P0-C670NSXK1S3DO1UXSC1O0";
+        let payload = SetupPayload::parse_str(qr).unwrap();
+        assert_eq!(
+            payload.optional_data,
+            [
+                OptionalDataElement {
+                    tag: 0x00,
+                    value: TlvValue::Utf8("SN1234".to_string()),
+                },
+                OptionalDataElement {
+                    tag: 0x80,
+                    value: TlvValue::Unsigned(42),
+                },
+            ]
+        );
+        let fixed = SetupPayload::parse_str("MT:-24J042C00KA0648G00").unwrap();
+        assert_eq!(payload.to_qr_code_str().unwrap(), qr);
+        assert_eq!(
+            SetupPayload::from_packed_bytes(&payload.to_packed_bytes().unwrap()).unwrap(),
+            payload
+        );
+        assert_eq!(
+            SetupPayload {
+                optional_data: Vec::new(),
+                raw_optional_data: Vec::new(),
+                ..payload
+            },
+            fixed
+        );
+    }
+
+    #[test]
+    fn test_qr_code_unknown_optional_data_roundtrip() {
+        // Serial number "SN1", vendor tag 0x80 = 42 written on 2 bytes, and
+        // vendor tag 0x81 = false, which the crate does not decode.
+        let qr = "MT:-24J042C00KA064IJ3P027M30NSXK1VXSC1-S924O0";
+        let mut payload = SetupPayload::parse_str(qr).unwrap();
+        assert_eq!(payload.optional_data.len(), 2);
+        assert!(!payload.raw_optional_data.is_empty());
+        assert_eq!(payload.to_qr_code_str().unwrap(), qr);
+
+        // Editing an element re-encodes it but keeps the boolean.
+        payload.optional_data[1].value = TlvValue::Unsigned(43);
+        assert_eq!(
+            payload.to_qr_code_str().unwrap(),
+            "MT:-24J042C00KA064IJ3P027M30NSXK1GA-D1A5AT0"
+        );
     }
 }
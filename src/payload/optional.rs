@@ -0,0 +1,244 @@
+//! The optional data a QR code may carry after its fixed fields.
+//!
+//! Device makers append TLV-encoded elements to the 88-bit payload, most
+//! often a serial number. The section is an anonymous TLV structure holding
+//! context-tagged elements: tags below 0x80 are defined by the
+//! specification, tags from 0x80 are vendor-specific.
+//!
+//! Members outside the subset [`crate::tlv`] decodes, such as booleans,
+//! byte strings or nested containers, are not exposed as elements but are
+//! kept in their encoded form, so that re-labeling a device does not drop
+//! them.
+
+use crate::error::{PayloadError, Result};
+use crate::tlv::{self, Decoder, Element, Tag, TlvValue};
+
+/// An element of the optional data of a QR code.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OptionalDataElement {
+    /// Context tag, e.g. 0x00 for the serial number
+    pub tag: u8,
+    /// The element's value
+    pub value: TlvValue,
+}
+
+fn error(offset: usize, reason: &'static str) -> crate::MatterPayloadError {
+    PayloadError::InvalidOptionalData { offset, reason }.into()
+}
+
+/// Splits an optional data section into the elements it decodes to and the
+/// encodings of the members it has outside the supported subset.
+fn split(bytes: &[u8]) -> Result<(Vec<OptionalDataElement>, Vec<&[u8]>)> {
+    let mut elements = Vec::new();
+    let mut unknown = Vec::new();
+    if bytes.is_empty() {
+        return Ok((elements, unknown));
+    }
+    let mut decoder = Decoder::new(bytes);
+    if decoder.enter_structure()? != Some(Tag::Anonymous) {
+        return Err(error(0, "expected an anonymous structure"));
+    }
+
+    loop {
+        let mut probe = decoder.clone();
+        match probe.read_element() {
+            Ok(None) => {
+                decoder = probe;
+                break;
+            }
+            Ok(Some(Element {
+                tag: Tag::Context(tag),
+                value,
+            })) if !matches!(value, TlvValue::Structure(_)) => {
+                elements.push(OptionalDataElement { tag, value });
+                decoder = probe;
+            }
+            _ => {
+                if let Some(member) = decoder.skip_element()? {
+                    unknown.push(member);
+                }
+            }
+        }
+    }
+
+    if !decoder.is_at_end() {
+        return Err(error(
+            decoder.offset(),
+            "trailing bytes after the structure",
+        ));
+    }
+    Ok((elements, unknown))
+}
+
+/// Decodes the optional data section following the fixed fields of a QR
+/// code; an empty section decodes to no elements.
+///
+/// Also returns the section itself when encoding the elements would not
+/// reproduce it, as [`SetupPayload::raw_optional_data`](super::SetupPayload::raw_optional_data)
+/// keeps it, and an empty vector otherwise.
+pub(super) fn decode(bytes: &[u8]) -> Result<(Vec<OptionalDataElement>, Vec<u8>)> {
+    let (elements, _) = split(bytes)?;
+    let raw = if encode(&elements, &[]) == bytes {
+        Vec::new()
+    } else {
+        bytes.to_vec()
+    };
+    Ok((elements, raw))
+}
+
+/// Encodes optional data elements into the section following the fixed
+/// fields of a QR code; no elements encode to an empty section.
+///
+/// `raw` is the section as parsed, if kept. It is reproduced verbatim while
+/// it still decodes to `elements`; once they have changed, the elements are
+/// encoded anew and followed by the members of `raw` outside the supported
+/// subset.
+pub(super) fn encode(elements: &[OptionalDataElement], raw: &[u8]) -> Vec<u8> {
+    let unknown = match split(raw) {
+        Ok((decoded, _)) if decoded == elements => return raw.to_vec(),
+        Ok((_, unknown)) => unknown,
+        Err(_) => Vec::new(),
+    };
+    if elements.is_empty() && unknown.is_empty() {
+        return Vec::new();
+    }
+    let members = elements
+        .iter()
+        .map(|element| Element {
+            tag: Tag::Context(element.tag),
+            value: element.value.clone(),
+        })
+        .collect();
+    let mut bytes = tlv::encode(&[Element {
+        tag: Tag::Anonymous,
+        value: TlvValue::Structure(members),
+    }]);
+    let end_of_container = bytes.pop();
+    for member in unknown {
+        bytes.extend_from_slice(member);
+    }
+    bytes.extend(end_of_container);
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MatterPayloadError;
+    use crate::error::TlvError;
+
+    #[test]
+    fn test_decode_optional_data() {
+        assert_eq!(decode(&[]).unwrap(), (Vec::new(), Vec::new()));
+
+        let bytes = [
+            0x15, // anonymous structure
+            0x2C, 0x00, 0x03, b'S', b'N', b'1', // serial number "SN1"
+            0x24, 0x80, 0x2A, // vendor tag 0x80, unsigned 42
+            0x20, 0x81, 0xFE, // vendor tag 0x81, signed -2
+            0x18, // end of container
+        ];
+        let (elements, raw) = decode(&bytes).unwrap();
+        assert_eq!(
+            elements,
+            [
+                OptionalDataElement {
+                    tag: 0x00,
+                    value: TlvValue::Utf8("SN1".to_string()),
+                },
+                OptionalDataElement {
+                    tag: 0x80,
+                    value: TlvValue::Unsigned(42),
+                },
+                OptionalDataElement {
+                    tag: 0x81,
+                    value: TlvValue::Signed(-2),
+                },
+            ]
+        );
+        assert!(raw.is_empty());
+        assert_eq!(encode(&elements, &[]), bytes);
+
+        assert_eq!(
+            decode(&bytes[..5]).unwrap_err(),
+            MatterPayloadError::Tlv(TlvError::Truncated { offset: 4 })
+        );
+        assert_eq!(
+            decode(&[0x2C, 0x00, 0x00]).unwrap_err(),
+            MatterPayloadError::Payload(PayloadError::InvalidOptionalData {
+                offset: 0,
+                reason: "expected an anonymous structure",
+            })
+        );
+        assert_eq!(
+            decode(&[0x15, 0x18, 0x00]).unwrap_err(),
+            MatterPayloadError::Payload(PayloadError::InvalidOptionalData {
+                offset: 2,
+                reason: "trailing bytes after the structure",
+            })
+        );
+    }
+
+    #[test]
+    fn test_unknown_members_preserved() {
+        let bytes = [
+            0x15, // anonymous structure
+            0x25, 0x80, 0x2A, 0x00, // vendor tag 0x80, unsigned 42 on 2 bytes
+            0x04, 0x07, // anonymous unsigned 7
+            0x28, 0x81, // vendor tag 0x81, false
+            0x35, 0x82, 0x24, 0x00, 0x01, 0x18, // vendor tag 0x82, structure
+            0x18, // end of container
+        ];
+        let (mut elements, raw) = decode(&bytes).unwrap();
+        assert_eq!(
+            elements,
+            [OptionalDataElement {
+                tag: 0x80,
+                value: TlvValue::Unsigned(42),
+            }]
+        );
+        assert_eq!(raw, bytes);
+        assert_eq!(encode(&elements, &raw), bytes);
+
+        elements[0].value = TlvValue::Unsigned(43);
+        assert_eq!(
+            encode(&elements, &raw),
+            [
+                0x15, 0x24, 0x80, 0x2B, 0x04, 0x07, 0x28, 0x81, 0x35, 0x82, 0x24, 0x00, 0x01, 0x18,
+                0x18,
+            ]
+        );
+        assert_eq!(
+            encode(&[], &raw),
+            [
+                0x15, 0x04, 0x07, 0x28, 0x81, 0x35, 0x82, 0x24, 0x00, 0x01, 0x18, 0x18
+            ]
+        );
+    }
+
+    #[test]
+    fn test_encode_integer_widths() {
+        for value in [0, 0xFF, 0x100, 0xFFFF_FFFF, u64::MAX] {
+            let elements = vec![OptionalDataElement {
+                tag: 0x80,
+                value: TlvValue::Unsigned(value),
+            }];
+            assert_eq!(
+                decode(&encode(&elements, &[])).unwrap(),
+                (elements, Vec::new())
+            );
+        }
+        assert_eq!(
+            encode(
+                &[OptionalDataElement {
+                    tag: 0x82,
+                    value: TlvValue::Unsigned(0x1234),
+                }],
+                &[]
+            ),
+            [0x15, 0x25, 0x82, 0x34, 0x12, 0x18]
+        );
+        assert!(encode(&[], &[]).is_empty());
+    }
+}
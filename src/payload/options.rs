@@ -0,0 +1,303 @@
+use super::manual::ManualCodeData;
+use super::qr::QrCodeData;
+use super::{ParsedPayload, PayloadSource, SetupPayload};
+use crate::error::{PayloadError, Result, SpecError};
+
+/// How strictly [`SetupPayload::parse_str_with`] treats its input.
+///
+/// Generators checking their own output want every oddity reported, while
+/// commissioners reading devices in the field want to accept what they can.
+/// The default, [`ParseOptions::DEFAULT`], matches [`SetupPayload::parse_str`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseOptions {
+    /// Accept payload format versions other than 0
+    pub allow_unknown_version: bool,
+    /// Accept the reserved commissioning flow value
+    pub allow_reserved_flow: bool,
+    /// Accept a passcode the specification forbids, such as 00000000 or 12345678
+    pub allow_invalid_passcode: bool,
+    /// Reject QR codes whose padding bits are not all zero
+    pub require_zero_padding: bool,
+    /// Ignore leading and trailing whitespace, control characters and
+    /// zero-width characters, which barcode scanners add around what they read
+    pub allow_whitespace: bool,
+    /// Ignore dashes and spaces between the digits of a manual code, as in
+    /// the printed grouping `1123-744-2363`
+    pub allow_separators: bool,
+    /// Accept an `mt:` prefix and lowercase base38 letters, as produced by
+    /// scanner apps that lowercase what they read
+    pub ignore_case: bool,
+    /// Accept a manual code stored without its check digit, 10 or 20
+    /// digits, and append it as [`SetupPayload::complete_manual_code`] does
+    pub append_check_digit: bool,
+}
+
+impl ParseOptions {
+    /// What [`SetupPayload::parse_str`] accepts: any version, flow and
+    /// padding, but only an allowed passcode and the exact text of a code.
+    pub const DEFAULT: Self = ParseOptions {
+        allow_unknown_version: true,
+        allow_reserved_flow: true,
+        allow_invalid_passcode: false,
+        require_zero_padding: false,
+        allow_whitespace: false,
+        allow_separators: false,
+        ignore_case: false,
+        append_check_digit: false,
+    };
+
+    /// Rejects anything a conforming generator would not produce.
+    pub const STRICT: Self = ParseOptions {
+        allow_unknown_version: false,
+        allow_reserved_flow: false,
+        allow_invalid_passcode: false,
+        require_zero_padding: true,
+        allow_whitespace: false,
+        allow_separators: false,
+        ignore_case: false,
+        append_check_digit: false,
+    };
+
+    /// Accepts anything that decodes.
+    pub const LENIENT: Self = ParseOptions {
+        allow_unknown_version: true,
+        allow_reserved_flow: true,
+        allow_invalid_passcode: true,
+        require_zero_padding: false,
+        allow_whitespace: true,
+        allow_separators: true,
+        ignore_case: true,
+        append_check_digit: true,
+    };
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Whether `c` is one of the characters barcode scanners and copy-paste
+/// leave around a payload: whitespace, line endings, control codes such as
+/// STX and ETX framing, and zero-width spaces, joiners and byte order marks.
+fn is_scanner_artifact(c: char) -> bool {
+    c.is_whitespace()
+        || c.is_control()
+        || matches!(c, '\u{200B}'..='\u{200D}' | '\u{2060}' | '\u{FEFF}')
+}
+
+impl SetupPayload {
+    /// Parses a string like [`SetupPayload::parse_str`], as strictly as `options` say.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`SetupPayload::parse_str`], and
+    /// `SpecError::UnknownPayloadVersion`, `PayloadError::ReservedCommissioningFlow`,
+    /// `PayloadError::InvalidPasscode` or `PayloadError::NonZeroPadding` for
+    /// what `options` rejects.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use matter_setup_code::{ParseOptions, SetupPayload};
+    ///
+    /// // Version 1 of the SDK example code.
+    /// let qr = " MT:.24J042C00KA0648G00\n";
+    /// assert!(SetupPayload::parse_str_with(qr, &ParseOptions::LENIENT).is_ok());
+    /// assert!(SetupPayload::parse_str_with(qr.trim(), &ParseOptions::STRICT).is_err());
+    /// ```
+    pub fn parse_str_with(payload_str: &str, options: &ParseOptions) -> Result<Self> {
+        Self::parse_with_source(payload_str, options).map(|parsed| parsed.payload)
+    }
+
+    /// Parses a QR code or a manual code as `options` say, reporting which
+    /// one it was. Every parsing entry point goes through here.
+    pub(super) fn parse_with_source(
+        payload_str: &str,
+        options: &ParseOptions,
+    ) -> Result<ParsedPayload> {
+        let payload_str = if options.allow_whitespace {
+            payload_str.trim_matches(is_scanner_artifact)
+        } else {
+            payload_str
+        };
+        let uppercase;
+        let payload_str = if options.ignore_case {
+            uppercase = payload_str.to_ascii_uppercase();
+            uppercase.as_str()
+        } else {
+            payload_str
+        };
+
+        let (payload, source) = if payload_str.starts_with("MT:") {
+            let container = QrCodeData::parse_from_str(payload_str)?;
+            if options.require_zero_padding && container.padding != 0 {
+                return Err(PayloadError::NonZeroPadding(container.padding).into());
+            }
+            (Self::from_qr_data(container)?, PayloadSource::QrCode)
+        } else {
+            let mut digits: String = if options.allow_separators {
+                payload_str
+                    .chars()
+                    .filter(|&c| c != '-' && c != ' ')
+                    .collect()
+            } else {
+                payload_str.to_string()
+            };
+            if options.append_check_digit && matches!(digits.len(), 10 | 20) {
+                digits = SetupPayload::complete_manual_code(&digits)?.into();
+            }
+            let payload = Self::from_manual_data(ManualCodeData::parse_from_str(&digits)?)?;
+            (payload, PayloadSource::ManualCode)
+        };
+
+        if !options.allow_unknown_version && payload.version != 0 {
+            return Err(SpecError::UnknownPayloadVersion(payload.version).into());
+        }
+        if !options.allow_reserved_flow && payload.flow.is_reserved() {
+            return Err(PayloadError::ReservedCommissioningFlow(payload.flow.bits()).into());
+        }
+        let payload = if options.allow_invalid_passcode {
+            payload
+        } else {
+            payload.with_valid_passcode()?
+        };
+        Ok(ParsedPayload { payload, source })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MatterPayloadError;
+
+    #[test]
+    fn test_default_options_match_parse_str() {
+        for input in [
+            "MT:-24J042C00KA0648G00",
+            "MT:.24J042C00KA0648G00",
+            "MT:-24J0UBG00KA0648G00",
+            "34970112332",
+        ] {
+            assert_eq!(
+                SetupPayload::parse_str_with(input, &ParseOptions::default()),
+                SetupPayload::parse_str(input)
+            );
+        }
+        assert!(SetupPayload::parse_str_with(" 34970112332", &ParseOptions::default()).is_err());
+        assert!(SetupPayload::parse_str_with(" 34970112332", &ParseOptions::LENIENT).is_ok());
+    }
+
+    #[test]
+    fn test_scanner_artifacts() {
+        let expected = SetupPayload::parse_str("MT:-24J042C00KA0648G00").unwrap();
+        for input in [
+            "MT:-24J042C00KA0648G00\n",
+            "\u{FEFF}MT:-24J042C00KA0648G00\r\n",
+            "\u{2}MT:-24J042C00KA0648G00\u{3}",
+            "\u{200B} MT:-24J042C00KA0648G00\u{200D}\t",
+        ] {
+            assert_eq!(
+                SetupPayload::parse_str_with(input, &ParseOptions::LENIENT).unwrap(),
+                expected
+            );
+            assert!(SetupPayload::parse_str(input).is_err());
+        }
+        assert!(
+            SetupPayload::parse_str_with("MT:-24J042\u{200B}C00KA0648G00", &ParseOptions::LENIENT)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_append_check_digit() {
+        let expected = SetupPayload::parse_str("749701123365521327694").unwrap();
+        for input in ["74970112336552132769", "7497-011-2336-552-1327-69"] {
+            assert_eq!(
+                SetupPayload::parse_str_with(input, &ParseOptions::LENIENT).unwrap(),
+                expected
+            );
+            assert!(SetupPayload::parse_str(input).is_err());
+        }
+        // A complete code is still checked.
+        assert_eq!(
+            SetupPayload::parse_str_with("34970112333", &ParseOptions::LENIENT).unwrap_err(),
+            MatterPayloadError::Payload(PayloadError::InvalidManualCodeChecksum)
+        );
+        assert_eq!(
+            SetupPayload::complete_manual_code("349701123").unwrap_err(),
+            MatterPayloadError::Payload(PayloadError::InvalidManualCodeLength(10))
+        );
+        assert_eq!(
+            SetupPayload::complete_manual_code("34970x1233").unwrap_err(),
+            MatterPayloadError::Payload(PayloadError::InvalidManualCodeDigit {
+                offset: 5,
+                character: 'x',
+            })
+        );
+    }
+
+    #[test]
+    fn test_manual_code_separators() {
+        let expected = SetupPayload::parse_str("11237442363").unwrap();
+        for input in ["1123-744-2363", "1123 744 2363", "11237-442363"] {
+            assert_eq!(
+                SetupPayload::parse_str_with(input, &ParseOptions::LENIENT).unwrap(),
+                expected
+            );
+            assert!(SetupPayload::parse_str_with(input, &ParseOptions::STRICT).is_err());
+        }
+        assert!(SetupPayload::parse_str_with("1123.744.2363", &ParseOptions::LENIENT).is_err());
+    }
+
+    #[test]
+    fn test_ignore_case() {
+        let expected = SetupPayload::parse_str("MT:Y.K904QI143LH13SH10").unwrap();
+        for input in ["mt:Y.K904QI143LH13SH10", "mt:y.k904qi143lh13sh10"] {
+            assert_eq!(
+                SetupPayload::parse_str_with(input, &ParseOptions::LENIENT).unwrap(),
+                expected
+            );
+            assert!(SetupPayload::parse_str_with(input, &ParseOptions::STRICT).is_err());
+            assert!(SetupPayload::parse_str_with(input, &ParseOptions::default()).is_err());
+        }
+    }
+
+    #[test]
+    fn test_strict_options() {
+        let strict = ParseOptions::STRICT;
+        assert!(SetupPayload::parse_str_with("MT:-24J042C00KA0648G00", &strict).is_ok());
+        assert_eq!(
+            SetupPayload::parse_str_with("MT:.24J042C00KA0648G00", &strict).unwrap_err(),
+            MatterPayloadError::Spec(SpecError::UnknownPayloadVersion(1))
+        );
+        assert_eq!(
+            SetupPayload::parse_str_with("MT:-24J0UBG00KA0648G00", &strict).unwrap_err(),
+            MatterPayloadError::Payload(PayloadError::ReservedCommissioningFlow(3))
+        );
+        // The SDK example code with padding 0b0101.
+        assert_eq!(
+            SetupPayload::parse_str_with("MT:-24J042C00KA0646NE0", &strict).unwrap_err(),
+            MatterPayloadError::Payload(PayloadError::NonZeroPadding(5))
+        );
+    }
+
+    #[test]
+    fn test_invalid_passcode() {
+        // The SDK example codes with passcode 12345678.
+        for input in ["MT:-24J042C004QG46Y900", "35767807533"] {
+            let payload = SetupPayload::parse_str_with(input, &ParseOptions::LENIENT).unwrap();
+            assert_eq!(payload.pincode.get(), 12345678);
+            let invalid = MatterPayloadError::Payload(PayloadError::InvalidPasscode(12345678));
+            for options in [ParseOptions::DEFAULT, ParseOptions::STRICT] {
+                assert_eq!(
+                    SetupPayload::parse_str_with(input, &options).unwrap_err(),
+                    invalid
+                );
+            }
+            // A leniently parsed passcode is never generated again.
+            assert_eq!(payload.to_qr_code_str().unwrap_err(), invalid);
+            assert_eq!(payload.to_manual_code_str().unwrap_err(), invalid);
+        }
+    }
+}
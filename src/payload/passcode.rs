@@ -0,0 +1,113 @@
+use super::is_valid_passcode;
+use crate::error::{PayloadError, Result};
+
+/// A setup passcode the Matter specification allows.
+///
+/// The 27-bit payload field could hold values up to 0x7FFFFFF, but the
+/// specification limits passcodes to 8 decimal digits (at most 99999998)
+/// and forbids 00000000, 11111111 through 99999999, 12345678 and 87654321,
+/// which commissioners refuse. [`Passcode::new`] only wraps allowed values;
+/// a code parsed with [`ParseOptions::allow_invalid_passcode`] may carry any
+/// 27-bit value, which [`Passcode::is_valid`] tells apart.
+///
+/// [`ParseOptions::allow_invalid_passcode`]: crate::ParseOptions::allow_invalid_passcode
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "u32", into = "u32")
+)]
+pub struct Passcode(u32);
+
+impl Passcode {
+    /// Wraps `value` if the specification allows it as a passcode.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PayloadError::InvalidPasscode` if `value` is out of range or forbidden.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use matter_setup_code::Passcode;
+    ///
+    /// assert_eq!(Passcode::new(20202021).unwrap().get(), 20202021);
+    /// assert!(Passcode::new(0).is_err());
+    /// assert!(Passcode::new(12345678).is_err());
+    /// ```
+    pub fn new(value: u32) -> Result<Self> {
+        if !is_valid_passcode(value) {
+            return Err(PayloadError::InvalidPasscode(value).into());
+        }
+        Ok(Passcode(value))
+    }
+
+    /// Wraps `value` as read from a payload, without checking it.
+    pub(crate) const fn new_unchecked(value: u32) -> Self {
+        Passcode(value)
+    }
+
+    /// Returns the integer value.
+    pub const fn get(self) -> u32 {
+        self.0
+    }
+
+    /// Whether the specification allows this passcode.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use matter_setup_code::{ParseOptions, SetupPayload};
+    ///
+    /// // The SDK example code with passcode 12345678.
+    /// let payload =
+    ///     SetupPayload::parse_str_with("MT:-24J042C004QG46Y900", &ParseOptions::LENIENT).unwrap();
+    /// assert_eq!(payload.pincode.get(), 12345678);
+    /// assert!(!payload.pincode.is_valid());
+    /// ```
+    pub fn is_valid(self) -> bool {
+        is_valid_passcode(self.0)
+    }
+}
+
+impl std::fmt::Display for Passcode {
+    /// Formats the passcode with its leading zeros, as 8 digits.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:08}", self.0)
+    }
+}
+
+impl From<Passcode> for u32 {
+    fn from(passcode: Passcode) -> Self {
+        passcode.0
+    }
+}
+
+impl TryFrom<u32> for Passcode {
+    type Error = crate::MatterPayloadError;
+
+    fn try_from(value: u32) -> Result<Self> {
+        Passcode::new(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MatterPayloadError;
+
+    #[test]
+    fn test_passcode_validity() {
+        for value in [1, 20202021, 99_999_998] {
+            assert_eq!(Passcode::new(value).unwrap().get(), value);
+        }
+        for value in [0, 33333333, 99999999, 12345678, 87654321, 0x7FF_FFFF] {
+            assert_eq!(
+                Passcode::new(value).unwrap_err(),
+                MatterPayloadError::Payload(PayloadError::InvalidPasscode(value))
+            );
+        }
+        assert_eq!(Passcode::new(1234).unwrap().to_string(), "00001234");
+        assert!(!Passcode::new_unchecked(12345678).is_valid());
+    }
+}
@@ -1,7 +1,10 @@
 use deku::prelude::*;
+use super::SetupPayload;
 use crate::base38;
 use crate::error::{PayloadError, Result};
-use super::common::CommissioningFlow;
+
+/// Length in bytes of the packed QR payload.
+pub(super) const QR_PAYLOAD_BYTES: usize = 11;
 
 /// Represents the binary structure of a Matter QR code payload.
 /// This struct is an internal detail and is not exposed publicly.
@@ -16,13 +19,17 @@ pub(super) struct QrCodeData {
     pub discriminator: u16,
     #[deku(bits = "8")]
     pub discovery: u8,
-    pub flow: CommissioningFlow,
+    #[deku(bits = "2")]
+    pub flow: u8,
     #[deku(bits = "16")]
     pub pid: u16,
     #[deku(bits = "16")]
     pub vid: u16,
     #[deku(bits = "3")]
     pub version: u8,
+    /// The TLV-encoded optional data following the fixed fields
+    #[deku(skip)]
+    pub optional_data: Vec<u8>,
 }
 
 impl QrCodeData {
@@ -33,11 +40,134 @@ impl QrCodeData {
         }
 
         let encoded = &payload[3..];
-        let mut decoded_bytes = base38::decode(encoded)?;
-        decoded_bytes.reverse();
+        let decoded_bytes = base38::decode(encoded)?;
+        Self::from_packed(&decoded_bytes)
+    }
+
+    /// Reads the structure from the packed payload bytes: the fixed fields,
+    /// least significant byte first, then any optional data.
+    ///
+    /// Fails with `PayloadError::InvalidPackedLength` if the fixed fields are
+    /// cut short. Bytes past them are always handed on as optional data, so
+    /// that decoding it rejects trailing garbage rather than dropping it.
+    pub(super) fn from_packed(packed: &[u8]) -> Result<Self> {
+        if packed.len() < QR_PAYLOAD_BYTES {
+            return Err(PayloadError::InvalidPackedLength(packed.len()).into());
+        }
+        let (fixed, optional) = packed.split_at(QR_PAYLOAD_BYTES);
+        let mut bytes = fixed.to_vec();
+        bytes.reverse();
 
         // Deku reads from a bit slice. The `from_bytes` helper creates this for us.
-        let (_rest, data) = QrCodeData::from_bytes((&decoded_bytes, 0))?;
+        // The fields fill the 88 bits exactly, so nothing is left over.
+        let (_, mut data) = QrCodeData::from_bytes((&bytes, 0))?;
+        data.optional_data = optional.to_vec();
         Ok(data)
     }
-}
\ No newline at end of file
+
+    /// Writes the packed payload bytes: the fixed fields, least significant
+    /// byte first, then any optional data.
+    pub(super) fn to_packed(&self) -> Result<Vec<u8>> {
+        let mut bytes = self.to_bytes()?;
+        bytes.reverse();
+        bytes.extend_from_slice(&self.optional_data);
+        Ok(bytes)
+    }
+}
+
+/// Prefix of every QR code string.
+const QR_CODE_PREFIX: &str = "MT:";
+
+/// A valid QR code string: "MT:" followed by base38 text decoding to a setup
+/// payload.
+///
+/// Holding a `QrCodeString` instead of a `String` records that the string
+/// was validated once, when parsed with [`str::parse`] or generated with
+/// [`SetupPayload::to_qr_code`], which parses its own output the same way,
+/// so that APIs accepting one need not check it again. Since both check the
+/// string as [`SetupPayload::parse_str`] does, it converts back to its
+/// [`SetupPayload`] infallibly.
+///
+/// # Example
+///
+/// ```
+/// use matter_setup_code::{QrCodeString, SetupPayload};
+///
+/// let qr: QrCodeString = "MT:-24J042C00KA0648G00".parse().unwrap();
+/// assert_eq!(SetupPayload::from(&qr).pincode.get(), 20202021);
+/// assert_eq!(qr.to_string(), "MT:-24J042C00KA0648G00");
+/// assert!("34970112332".parse::<QrCodeString>().is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "String", into = "String")
+)]
+pub struct QrCodeString(String);
+
+impl QrCodeString {
+    /// The whole string, "MT:" prefix included.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The base38 text after the "MT:" prefix.
+    pub fn base38(&self) -> &str {
+        &self.0[QR_CODE_PREFIX.len()..]
+    }
+}
+
+impl std::fmt::Display for QrCodeString {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::str::FromStr for QrCodeString {
+    type Err = crate::MatterPayloadError;
+
+    /// Parses a QR code string, checking it as [`SetupPayload::parse_str`]
+    /// does.
+    fn from_str(qr: &str) -> Result<Self> {
+        if !qr.starts_with(QR_CODE_PREFIX) {
+            return Err(PayloadError::InvalidQrCodePrefix.into());
+        }
+        SetupPayload::parse_str(qr)?;
+        Ok(QrCodeString(qr.to_string()))
+    }
+}
+
+impl TryFrom<String> for QrCodeString {
+    type Error = crate::MatterPayloadError;
+
+    fn try_from(qr: String) -> Result<Self> {
+        qr.parse()
+    }
+}
+
+impl TryFrom<&SetupPayload> for QrCodeString {
+    type Error = crate::MatterPayloadError;
+
+    fn try_from(payload: &SetupPayload) -> Result<Self> {
+        payload.to_qr_code()
+    }
+}
+
+impl From<&QrCodeString> for SetupPayload {
+    fn from(qr: &QrCodeString) -> Self {
+        SetupPayload::parse_str(&qr.0).expect("QR code strings are validated when created")
+    }
+}
+
+impl AsRef<str> for QrCodeString {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<QrCodeString> for String {
+    fn from(qr: QrCodeString) -> Self {
+        qr.0
+    }
+}
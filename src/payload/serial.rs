@@ -0,0 +1,171 @@
+use std::fmt;
+
+use super::{OptionalDataElement, SetupPayload};
+use crate::error::{PayloadError, Result};
+use crate::tlv::TlvValue;
+
+/// Optional data tag of the serial number.
+const SERIAL_NUMBER_TAG: u8 = 0x00;
+/// Longest serial number string the specification allows, in bytes.
+const MAX_SERIAL_NUMBER_LENGTH: usize = 32;
+
+/// A device serial number, which a QR code may carry as a string or as an
+/// unsigned integer.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum SerialNumber {
+    /// A UTF-8 serial number of at most 32 bytes
+    Text(String),
+    /// A numeric serial number
+    Number(u32),
+}
+
+impl fmt::Display for SerialNumber {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SerialNumber::Text(text) => f.write_str(text),
+            SerialNumber::Number(number) => write!(f, "{number}"),
+        }
+    }
+}
+
+impl From<&str> for SerialNumber {
+    fn from(text: &str) -> Self {
+        SerialNumber::Text(text.to_string())
+    }
+}
+
+impl From<String> for SerialNumber {
+    fn from(text: String) -> Self {
+        SerialNumber::Text(text)
+    }
+}
+
+impl From<u32> for SerialNumber {
+    fn from(number: u32) -> Self {
+        SerialNumber::Number(number)
+    }
+}
+
+impl SetupPayload {
+    /// The serial number carried in the QR code's optional data, or `None`
+    /// if there is none or its value is neither a string nor a 32-bit
+    /// unsigned integer.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use matter_setup_code::{SerialNumber, SetupPayload};
+    ///
+    /// let payload =
+    ///     SetupPayload::parse_str("MT:-24J042C00KA064IJ3P0-C670NSXK1S3DO1UXSC1O0").unwrap();
+    /// assert_eq!(payload.serial_number(), Some(SerialNumber::from("SN1234")));
+    /// ```
+    pub fn serial_number(&self) -> Option<SerialNumber> {
+        let element = self
+            .optional_data
+            .iter()
+            .find(|element| element.tag == SERIAL_NUMBER_TAG)?;
+        match &element.value {
+            TlvValue::Utf8(text) => Some(SerialNumber::Text(text.clone())),
+            TlvValue::Unsigned(number) => u32::try_from(*number).ok().map(SerialNumber::Number),
+            _ => None,
+        }
+    }
+
+    /// Sets the serial number, replacing any the optional data already has.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PayloadError::SerialNumberTooLong` if a string serial number
+    /// exceeds 32 bytes; the payload is then left unchanged.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use matter_setup_code::SetupPayload;
+    ///
+    /// let mut payload = SetupPayload::parse_str("MT:-24J042C00KA0648G00").unwrap();
+    /// payload.set_serial_number("SN1234").unwrap();
+    /// assert_eq!(payload.serial_number().unwrap().to_string(), "SN1234");
+    /// payload.set_serial_number(1234u32).unwrap();
+    /// assert_eq!(payload.serial_number().unwrap().to_string(), "1234");
+    /// ```
+    pub fn set_serial_number(&mut self, serial_number: impl Into<SerialNumber>) -> Result<()> {
+        let value = match serial_number.into() {
+            SerialNumber::Text(text) if text.len() > MAX_SERIAL_NUMBER_LENGTH => {
+                return Err(PayloadError::SerialNumberTooLong(text.len()).into());
+            }
+            SerialNumber::Text(text) => TlvValue::Utf8(text),
+            SerialNumber::Number(number) => TlvValue::Unsigned(number.into()),
+        };
+        match self
+            .optional_data
+            .iter_mut()
+            .find(|element| element.tag == SERIAL_NUMBER_TAG)
+        {
+            Some(element) => element.value = value,
+            None => self.optional_data.insert(
+                0,
+                OptionalDataElement {
+                    tag: SERIAL_NUMBER_TAG,
+                    value,
+                },
+            ),
+        }
+        Ok(())
+    }
+
+    /// Removes the serial number from the optional data, returning it.
+    pub fn remove_serial_number(&mut self) -> Option<SerialNumber> {
+        let serial_number = self.serial_number();
+        self.optional_data
+            .retain(|element| element.tag != SERIAL_NUMBER_TAG);
+        serial_number
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MatterPayloadError;
+
+    #[test]
+    fn test_serial_number() {
+        let qr = "MT:-24J042C00KA064IJ3P0-C670NSXK1S3DO1UXSC1O0";
+        let mut payload = SetupPayload::parse_str(qr).unwrap();
+        assert_eq!(
+            payload.serial_number(),
+            Some(SerialNumber::Text("SN1234".to_string()))
+        );
+
+        payload.set_serial_number(0xFFFF_FFFF_u32).unwrap();
+        assert_eq!(
+            payload.serial_number(),
+            Some(SerialNumber::Number(0xFFFF_FFFF))
+        );
+        assert_eq!(payload.optional_data.len(), 2);
+
+        let before = payload.clone();
+        assert_eq!(
+            payload.set_serial_number("X".repeat(33)).unwrap_err(),
+            MatterPayloadError::Payload(PayloadError::SerialNumberTooLong(33))
+        );
+        assert_eq!(payload, before);
+
+        assert_eq!(
+            payload.remove_serial_number(),
+            Some(SerialNumber::Number(0xFFFF_FFFF))
+        );
+        assert_eq!(payload.serial_number(), None);
+        payload.set_serial_number("SN1234").unwrap();
+        assert_eq!(payload.to_qr_code_str().unwrap(), qr);
+
+        payload.optional_data[0].value = TlvValue::Signed(-1);
+        assert_eq!(payload.serial_number(), None);
+    }
+}
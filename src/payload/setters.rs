@@ -0,0 +1,169 @@
+//! Validating setters for [`SetupPayload`].
+
+use super::{
+    CommissioningFlow, DiscoveryCapabilities, Discriminator, Passcode, ProductId, SetupPayload,
+    VendorId,
+};
+use crate::error::{PayloadError, Result, SpecError};
+
+impl SetupPayload {
+    /// Sets the setup passcode.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PayloadError::InvalidPasscode` if `passcode` is out of range
+    /// or forbidden; the payload is then left unchanged.
+    pub fn set_passcode(&mut self, passcode: u32) -> Result<()> {
+        self.pincode = Passcode::new(passcode)?;
+        Ok(())
+    }
+
+    /// Sets the 12-bit discriminator, which also determines the short
+    /// discriminator of the manual code.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PayloadError::DiscriminatorTooLarge` if `discriminator` does
+    /// not fit in 12 bits; the payload is then left unchanged.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use matter_setup_code::SetupPayload;
+    ///
+    /// let mut payload = SetupPayload::parse_str("34970112332").unwrap();
+    /// payload.set_discriminator(1132).unwrap();
+    /// assert_eq!(payload.discriminator.short(), 4);
+    /// assert!(payload.set_discriminator(0x1000).is_err());
+    /// ```
+    pub fn set_discriminator(&mut self, discriminator: u16) -> Result<()> {
+        self.discriminator = Discriminator::from_long(discriminator)?;
+        Ok(())
+    }
+
+    /// Completes a payload with the 12-bit discriminator a device advertises,
+    /// typically a payload parsed from a manual code, which only carries the
+    /// short discriminator, once the device is found over DNS-SD or BLE.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PayloadError::DiscriminatorTooLarge` if `discriminator` does
+    /// not fit in 12 bits, or `PayloadError::DiscriminatorMismatch` if it
+    /// disagrees with the discriminator the payload already has.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use matter_setup_code::{Discriminator, SetupPayload};
+    ///
+    /// let manual = SetupPayload::parse_str("11237442363").unwrap();
+    /// let upgraded = manual.with_long_discriminator(1132).unwrap();
+    /// assert_eq!(upgraded.discriminator, Discriminator::from_long(1132).unwrap());
+    /// assert!(manual.with_long_discriminator(3840).is_err());
+    /// ```
+    pub fn with_long_discriminator(&self, discriminator: u16) -> Result<Self> {
+        let long = Discriminator::from_long(discriminator)?;
+        if !self.discriminator.matches(discriminator) {
+            return Err(PayloadError::DiscriminatorMismatch(discriminator).into());
+        }
+        Ok(SetupPayload {
+            discriminator: long,
+            ..self.clone()
+        })
+    }
+
+    /// Sets the discovery capabilities.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SpecError::ReservedDiscoveryBits` if `discovery` has bits the
+    /// specification does not define; the payload is then left unchanged.
+    pub fn set_discovery(&mut self, discovery: DiscoveryCapabilities) -> Result<()> {
+        if discovery.reserved_bits() != 0 {
+            return Err(SpecError::ReservedDiscoveryBits(discovery.reserved_bits()).into());
+        }
+        self.discovery = Some(discovery);
+        Ok(())
+    }
+
+    /// Sets the vendor ID and product ID together.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PayloadError::CustomFlowWithoutVidPid` if the flow is
+    /// [`CommissioningFlow::Custom`] and either ID is 0, as the manual code
+    /// would then carry no usable IDs; the payload is then left unchanged.
+    pub fn set_vendor_product(&mut self, vid: u16, pid: u16) -> Result<()> {
+        let (vid, pid) = (VendorId::new(vid), ProductId::new(pid));
+        if self.flow == CommissioningFlow::Custom && (vid.is_unassigned() || pid.is_unassigned()) {
+            return Err(PayloadError::CustomFlowWithoutVidPid.into());
+        }
+        self.vid = Some(vid);
+        self.pid = Some(pid);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MatterPayloadError;
+
+    #[test]
+    fn test_setters() {
+        let mut payload = SetupPayload::parse_str("34970112332").unwrap();
+        payload.set_discriminator(3840).unwrap();
+        payload.set_passcode(20202021).unwrap();
+        payload.set_discovery(DiscoveryCapabilities::BLE).unwrap();
+        payload.set_vendor_product(0xFFF1, 0x8001).unwrap();
+        assert_eq!(payload.to_qr_code_str().unwrap(), "MT:-24J042C00KA0648G00");
+
+        let before = payload.clone();
+        assert_eq!(
+            payload.set_passcode(12345678).unwrap_err(),
+            MatterPayloadError::Payload(PayloadError::InvalidPasscode(12345678))
+        );
+        assert_eq!(
+            payload.set_discriminator(0x1000).unwrap_err(),
+            MatterPayloadError::Payload(PayloadError::DiscriminatorTooLarge(0x1000))
+        );
+        assert_eq!(
+            payload
+                .set_discovery(DiscoveryCapabilities::from_bits(0x82))
+                .unwrap_err(),
+            MatterPayloadError::Spec(SpecError::ReservedDiscoveryBits(0x80))
+        );
+        payload.flow = CommissioningFlow::Custom;
+        assert_eq!(
+            payload.set_vendor_product(0xFFF1, 0).unwrap_err(),
+            MatterPayloadError::Payload(PayloadError::CustomFlowWithoutVidPid)
+        );
+        payload.flow = CommissioningFlow::Standard;
+        assert_eq!(payload, before);
+    }
+
+    #[test]
+    fn test_with_long_discriminator() {
+        let manual = SetupPayload::parse_str("34970112332").unwrap();
+        let upgraded = manual.with_long_discriminator(3840).unwrap();
+        assert_eq!(
+            upgraded.discriminator,
+            Discriminator::from_long(3840).unwrap()
+        );
+        assert_eq!(upgraded.pincode, manual.pincode);
+        assert_eq!(upgraded.with_long_discriminator(3840).unwrap(), upgraded);
+
+        assert_eq!(
+            upgraded.with_long_discriminator(3841).unwrap_err(),
+            MatterPayloadError::Payload(PayloadError::DiscriminatorMismatch(3841))
+        );
+        assert_eq!(
+            manual.with_long_discriminator(1132).unwrap_err(),
+            MatterPayloadError::Payload(PayloadError::DiscriminatorMismatch(1132))
+        );
+        assert_eq!(
+            manual.with_long_discriminator(0x1F00).unwrap_err(),
+            MatterPayloadError::Payload(PayloadError::DiscriminatorTooLarge(0x1F00))
+        );
+    }
+}
@@ -0,0 +1,69 @@
+use super::{ParseOptions, SetupPayload};
+use crate::error::Result;
+
+/// The format a payload was parsed from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum PayloadSource {
+    /// An `MT:` QR code payload, usually scanned.
+    QrCode,
+    /// A numeric manual pairing code, usually typed. It carries no long
+    /// discriminator and no discovery capabilities.
+    ManualCode,
+}
+
+/// A parsed payload together with the format it came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ParsedPayload {
+    /// The parsed fields
+    pub payload: SetupPayload,
+    /// The format of the input
+    pub source: PayloadSource,
+}
+
+impl SetupPayload {
+    /// Parses a string like [`SetupPayload::parse_str`], also reporting
+    /// whether it was a QR code or a manual code.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`SetupPayload::parse_str`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use matter_setup_code::{PayloadSource, SetupPayload};
+    ///
+    /// let parsed = SetupPayload::parse_str_with_source("34970112332").unwrap();
+    /// assert_eq!(parsed.source, PayloadSource::ManualCode);
+    /// ```
+    pub fn parse_str_with_source(payload_str: &str) -> Result<ParsedPayload> {
+        Self::parse_with_source(payload_str, &ParseOptions::DEFAULT)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_with_source() {
+        let qr = SetupPayload::parse_str_with_source("MT:-24J042C00KA0648G00").unwrap();
+        assert_eq!(qr.source, PayloadSource::QrCode);
+        assert_eq!(
+            qr.payload,
+            SetupPayload::parse_str("MT:-24J042C00KA0648G00").unwrap()
+        );
+
+        let manual = SetupPayload::parse_str_with_source("34970112332").unwrap();
+        assert_eq!(manual.source, PayloadSource::ManualCode);
+        assert_eq!(manual.payload.pincode.get(), 20202021);
+
+        assert!(SetupPayload::parse_str_with_source("MT:").is_err());
+    }
+}
@@ -0,0 +1,88 @@
+use super::{CommissioningFlow, DiscoveryCapabilities, ProductId, SetupPayload, VendorId};
+use crate::error::Result;
+
+/// The fields shared by every device of a product line, from which each
+/// device's payload is stamped with [`PayloadTemplate::instantiate`].
+///
+/// # Example
+///
+/// ```
+/// use matter_setup_code::{CommissioningFlow, DiscoveryCapabilities, PayloadTemplate, ProductId, VendorId};
+///
+/// let template = PayloadTemplate {
+///     vid: VendorId::new(0xFFF1),
+///     pid: ProductId::new(0x8001),
+///     discovery: DiscoveryCapabilities::BLE,
+///     flow: CommissioningFlow::Standard,
+/// };
+/// let payload = template.instantiate(20202021, 3840).unwrap();
+/// assert_eq!(payload.to_qr_code_str().unwrap(), "MT:-24J042C00KA0648G00");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PayloadTemplate {
+    /// Vendor ID
+    pub vid: VendorId,
+    /// Product ID
+    pub pid: ProductId,
+    /// Discovery capabilities bitmask
+    pub discovery: DiscoveryCapabilities,
+    /// Commissioning flow type
+    pub flow: CommissioningFlow,
+}
+
+impl PayloadTemplate {
+    /// Takes the shared fields of an existing payload, e.g. one parsed from
+    /// a golden sample, or `None` if it lacks any of them.
+    pub fn from_payload(payload: &SetupPayload) -> Option<Self> {
+        Some(PayloadTemplate {
+            vid: payload.vid?,
+            pid: payload.pid?,
+            discovery: payload.discovery?,
+            flow: payload.flow,
+        })
+    }
+
+    /// Produces the payload of one device from its passcode and 12-bit discriminator.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`SetupPayloadBuilder::build`](super::SetupPayloadBuilder::build).
+    pub fn instantiate(&self, passcode: u32, discriminator: u16) -> Result<SetupPayload> {
+        SetupPayload::builder()
+            .discriminator(discriminator)
+            .passcode(passcode)
+            .vendor_id(self.vid.get())
+            .product_id(self.pid.get())
+            .discovery(self.discovery)
+            .flow(self.flow)
+            .build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MatterPayloadError;
+    use crate::error::PayloadError;
+
+    #[test]
+    fn test_instantiate() {
+        let sample = SetupPayload::parse_str("MT:Y.K904QI143LH13SH10").unwrap();
+        let template = PayloadTemplate::from_payload(&sample).unwrap();
+        assert_eq!(template.instantiate(69414998, 1132).unwrap(), sample);
+
+        let stamped = template.instantiate(20202021, 3840).unwrap();
+        assert_eq!(stamped.vid, sample.vid);
+        assert_eq!(stamped.discovery, sample.discovery);
+
+        assert_eq!(
+            template.instantiate(12345678, 3840).unwrap_err(),
+            MatterPayloadError::Payload(PayloadError::InvalidPasscode(12345678))
+        );
+        assert!(
+            PayloadTemplate::from_payload(&SetupPayload::parse_str("34970112332").unwrap())
+                .is_none()
+        );
+    }
+}
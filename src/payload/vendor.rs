@@ -0,0 +1,153 @@
+use super::{OptionalDataElement, SetupPayload};
+use crate::error::{PayloadError, Result};
+use crate::tlv::TlvValue;
+
+/// First optional data tag reserved for vendors; tags below it are defined
+/// by the specification.
+const FIRST_VENDOR_TAG: u8 = 0x80;
+
+/// A vendor-defined element of a QR code's optional data, whose tag is
+/// guaranteed to lie in the vendor range 0x80–0xFF.
+///
+/// # Example
+///
+/// ```
+/// use matter_setup_code::{SetupPayload, TlvValue, VendorElement};
+///
+/// let mut payload = SetupPayload::parse_str("MT:-24J042C00KA0648G00").unwrap();
+/// payload.set_vendor_element(VendorElement::new(0x80, TlvValue::Unsigned(42)).unwrap());
+/// assert_eq!(payload.vendor_element(0x80), Some(&TlvValue::Unsigned(42)));
+/// assert!(VendorElement::new(0x00, TlvValue::Unsigned(42)).is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct VendorElement {
+    tag: u8,
+    value: TlvValue,
+}
+
+impl VendorElement {
+    /// Creates an element with a vendor-range tag.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PayloadError::InvalidVendorTag` if `tag` is below 0x80.
+    pub fn new(tag: u8, value: TlvValue) -> Result<Self> {
+        if tag < FIRST_VENDOR_TAG {
+            return Err(PayloadError::InvalidVendorTag(tag).into());
+        }
+        Ok(VendorElement { tag, value })
+    }
+
+    /// The element's context tag, from 0x80 to 0xFF.
+    pub fn tag(&self) -> u8 {
+        self.tag
+    }
+
+    /// The element's value.
+    pub fn value(&self) -> &TlvValue {
+        &self.value
+    }
+}
+
+impl TryFrom<OptionalDataElement> for VendorElement {
+    type Error = crate::MatterPayloadError;
+
+    fn try_from(element: OptionalDataElement) -> Result<Self> {
+        VendorElement::new(element.tag, element.value)
+    }
+}
+
+impl From<VendorElement> for OptionalDataElement {
+    fn from(element: VendorElement) -> Self {
+        OptionalDataElement {
+            tag: element.tag,
+            value: element.value,
+        }
+    }
+}
+
+impl SetupPayload {
+    /// The vendor-defined elements of the optional data, in encoding order.
+    pub fn vendor_elements(&self) -> Vec<VendorElement> {
+        self.optional_data
+            .iter()
+            .filter(|element| element.tag >= FIRST_VENDOR_TAG)
+            .map(|element| VendorElement {
+                tag: element.tag,
+                value: element.value.clone(),
+            })
+            .collect()
+    }
+
+    /// The value of the vendor-defined element with `tag`, if present.
+    pub fn vendor_element(&self, tag: u8) -> Option<&TlvValue> {
+        if tag < FIRST_VENDOR_TAG {
+            return None;
+        }
+        self.optional_data
+            .iter()
+            .find(|element| element.tag == tag)
+            .map(|element| &element.value)
+    }
+
+    /// Adds a vendor-defined element, replacing any with the same tag.
+    pub fn set_vendor_element(&mut self, element: VendorElement) {
+        match self
+            .optional_data
+            .iter_mut()
+            .find(|existing| existing.tag == element.tag)
+        {
+            Some(existing) => existing.value = element.value,
+            None => self.optional_data.push(element.into()),
+        }
+    }
+
+    /// Removes the vendor-defined element with `tag`, returning it.
+    pub fn remove_vendor_element(&mut self, tag: u8) -> Option<VendorElement> {
+        if tag < FIRST_VENDOR_TAG {
+            return None;
+        }
+        let index = self
+            .optional_data
+            .iter()
+            .position(|element| element.tag == tag)?;
+        VendorElement::try_from(self.optional_data.remove(index)).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MatterPayloadError;
+
+    #[test]
+    fn test_vendor_elements() {
+        let qr = "MT:-24J042C00KA064IJ3P0-C670NSXK1S3DO1UXSC1O0";
+        let mut payload = SetupPayload::parse_str(qr).unwrap();
+        let element = VendorElement::new(0x80, TlvValue::Unsigned(42)).unwrap();
+        assert_eq!(payload.vendor_elements(), std::slice::from_ref(&element));
+        assert_eq!(payload.vendor_element(0x00), None);
+
+        payload.set_vendor_element(VendorElement::new(0xFF, TlvValue::Signed(-7)).unwrap());
+        payload.set_vendor_element(VendorElement::new(0x80, TlvValue::Unsigned(43)).unwrap());
+        assert_eq!(payload.optional_data.len(), 3);
+        assert_eq!(payload.vendor_element(0x80), Some(&TlvValue::Unsigned(43)));
+
+        assert_eq!(payload.remove_vendor_element(0x00), None);
+        assert!(payload.remove_vendor_element(0xFF).is_some());
+        payload.set_vendor_element(element);
+        assert_eq!(payload.to_qr_code_str().unwrap(), qr);
+
+        assert_eq!(
+            VendorElement::new(0x7F, TlvValue::Unsigned(0)).unwrap_err(),
+            MatterPayloadError::Payload(PayloadError::InvalidVendorTag(0x7F))
+        );
+        assert!(
+            VendorElement::try_from(OptionalDataElement {
+                tag: 0x00,
+                value: TlvValue::Utf8("SN1234".to_string()),
+            })
+            .is_err()
+        );
+    }
+}
@@ -0,0 +1,252 @@
+//! Vendor payload profiles loaded from TOML.
+//!
+//! A profile captures the per-product defaults of a setup payload (vendor ID,
+//! product ID, commissioning flow, discovery capabilities and whether the QR
+//! code carries a serial number), so a manufacturer can keep one reviewed
+//! file describing every SKU and only supply the per-device discriminator,
+//! passcode and serial number at generation time.
+//!
+//! ```toml
+//! [profile.smart-plug]
+//! vendor_id = 0xFFF1
+//! product_id = 0x8000
+//! flow = "standard"
+//! discovery = 4
+//! serial_number = "text"
+//! ```
+
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+
+use crate::error::{ProfileError, Result};
+use crate::payload::{CommissioningFlow, Passcode, SerialNumber, SetupPayload};
+
+/// How a profile's payloads carry the device serial number in the QR code
+/// optional data.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SerialNumberPolicy {
+    /// The QR code carries no serial number
+    #[default]
+    Omit,
+    /// The serial number is encoded as a UTF-8 string
+    Text,
+    /// The serial number is encoded as an unsigned integer
+    Number,
+}
+
+/// The per-product defaults applied to every payload of a given SKU.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Profile {
+    /// Vendor ID
+    pub vendor_id: u16,
+    /// Product ID
+    pub product_id: u16,
+    /// Commissioning flow type (default: Standard)
+    #[serde(default)]
+    pub flow: CommissioningFlow,
+    /// Discovery capabilities bitmask (default: None)
+    #[serde(default)]
+    pub discovery: Option<u8>,
+    /// Serial number encoding in the QR code optional data (default: Omit)
+    #[serde(default)]
+    pub serial_number: SerialNumberPolicy,
+}
+
+/// A named collection of profiles, as read from a `[profile.<name>]` TOML file.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Profiles {
+    #[serde(default, rename = "profile")]
+    profiles: BTreeMap<String, Profile>,
+}
+
+impl Profile {
+    /// Parses a single profile from a TOML document holding its fields at the top level.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the document is not valid TOML or does not describe a profile.
+    pub fn from_toml_str(toml: &str) -> Result<Self> {
+        toml::from_str(toml).map_err(|e| ProfileError::Toml(e).into())
+    }
+}
+
+impl Profiles {
+    /// Parses every `[profile.<name>]` table of a TOML document.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the document is not valid TOML or one of the
+    /// profiles is malformed.
+    pub fn from_toml_str(toml: &str) -> Result<Self> {
+        toml::from_str(toml).map_err(|e| ProfileError::Toml(e).into())
+    }
+
+    /// Looks up a profile by name.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ProfileError::UnknownProfile` if no profile has this name.
+    pub fn get(&self, name: &str) -> Result<&Profile> {
+        self.profiles
+            .get(name)
+            .ok_or_else(|| ProfileError::UnknownProfile(name.to_string()).into())
+    }
+
+    /// Returns an iterator over the profile names and their definitions, sorted by name.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Profile)> {
+        self.profiles
+            .iter()
+            .map(|(name, profile)| (name.as_str(), profile))
+    }
+}
+
+impl SetupPayload {
+    /// Creates a new SetupPayload from a product profile and the per-device values.
+    ///
+    /// # Arguments
+    ///
+    /// * `profile` - Product defaults (VID, PID, flow, discovery)
+    /// * `discriminator` - 12-bit discriminator value
+    /// * `pincode` - Setup PIN code
+    pub fn from_profile(profile: &Profile, discriminator: u16, pincode: Passcode) -> Self {
+        SetupPayload::new(
+            discriminator,
+            pincode,
+            profile.discovery,
+            Some(profile.flow),
+            Some(profile.vendor_id),
+            Some(profile.product_id),
+        )
+    }
+
+    /// Like [`SetupPayload::from_profile`], adding the device serial number
+    /// to the optional data as the profile's [`SerialNumberPolicy`] says.
+    ///
+    /// Under `Omit` the serial number is left out. Under `Text` a numeric
+    /// serial number is written as its decimal string.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ProfileError::NonNumericSerialNumber` under `Number` if the
+    /// serial number is a string that is not a 32-bit unsigned integer, or
+    /// `PayloadError::SerialNumberTooLong` for a string over 32 bytes.
+    pub fn from_profile_with_serial(
+        profile: &Profile,
+        discriminator: u16,
+        pincode: Passcode,
+        serial_number: impl Into<SerialNumber>,
+    ) -> Result<Self> {
+        let mut payload = SetupPayload::from_profile(profile, discriminator, pincode);
+        let serial_number = match (profile.serial_number, serial_number.into()) {
+            (SerialNumberPolicy::Omit, _) => return Ok(payload),
+            (SerialNumberPolicy::Text, serial_number) => {
+                SerialNumber::Text(serial_number.to_string())
+            }
+            (SerialNumberPolicy::Number, SerialNumber::Text(text)) => match text.parse() {
+                Ok(number) => SerialNumber::Number(number),
+                Err(_) => return Err(ProfileError::NonNumericSerialNumber(text).into()),
+            },
+            (SerialNumberPolicy::Number, number) => number,
+        };
+        payload.set_serial_number(serial_number)?;
+        Ok(payload)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MatterPayloadError;
+
+    const PROFILES: &str = r#"
+        [profile.smart-plug]
+        vendor_id = 0xFFF1
+        product_id = 0x8000
+        discovery = 4
+
+        [profile.bridge]
+        vendor_id = 0xFFF1
+        product_id = 0x8001
+        flow = "user-intent"
+        discovery = 2
+        serial_number = "number"
+    "#;
+
+    #[test]
+    fn test_profiles_from_toml() {
+        let profiles = Profiles::from_toml_str(PROFILES).unwrap();
+        let names: Vec<_> = profiles.iter().map(|(name, _)| name).collect();
+        assert_eq!(names, vec!["bridge", "smart-plug"]);
+
+        let bridge = profiles.get("bridge").unwrap();
+        assert_eq!(bridge.product_id, 0x8001);
+        assert_eq!(bridge.flow, CommissioningFlow::UserIntent);
+
+        assert_eq!(bridge.serial_number, SerialNumberPolicy::Number);
+
+        let plug = profiles.get("smart-plug").unwrap();
+        assert_eq!(plug.flow, CommissioningFlow::Standard);
+        assert_eq!(plug.serial_number, SerialNumberPolicy::Omit);
+    }
+
+    #[test]
+    fn test_serial_number_policy() {
+        let profiles = Profiles::from_toml_str(PROFILES).unwrap();
+        let passcode = Passcode::new(69414998).unwrap();
+
+        let plug = profiles.get("smart-plug").unwrap();
+        let payload =
+            SetupPayload::from_profile_with_serial(plug, 1132, passcode, "SN1234").unwrap();
+        assert_eq!(payload.serial_number(), None);
+        assert_eq!(payload.to_qr_code_str().unwrap(), "MT:Y.K904QI143LH13SH10");
+
+        let bridge = profiles.get("bridge").unwrap();
+        let payload =
+            SetupPayload::from_profile_with_serial(bridge, 1132, passcode, "1234").unwrap();
+        assert_eq!(payload.serial_number(), Some(SerialNumber::Number(1234)));
+        assert_eq!(
+            SetupPayload::from_profile_with_serial(bridge, 1132, passcode, "SN1234").unwrap_err(),
+            MatterPayloadError::Profile(ProfileError::NonNumericSerialNumber("SN1234".to_string()))
+        );
+
+        let text = Profile {
+            serial_number: SerialNumberPolicy::Text,
+            ..bridge.clone()
+        };
+        let payload =
+            SetupPayload::from_profile_with_serial(&text, 1132, passcode, 1234u32).unwrap();
+        assert_eq!(payload.serial_number(), Some(SerialNumber::from("1234")));
+    }
+
+    #[test]
+    fn test_from_profile_matches_reference() {
+        let profiles = Profiles::from_toml_str(PROFILES).unwrap();
+        let payload =
+            SetupPayload::from_profile(
+                profiles.get("smart-plug").unwrap(),
+                1132,
+                Passcode::new(69414998).unwrap(),
+            );
+        assert_eq!(payload.to_qr_code_str().unwrap(), "MT:Y.K904QI143LH13SH10");
+    }
+
+    #[test]
+    fn test_profile_errors() {
+        let profiles = Profiles::from_toml_str(PROFILES).unwrap();
+        assert_eq!(
+            profiles.get("lamp").unwrap_err(),
+            MatterPayloadError::Profile(ProfileError::UnknownProfile("lamp".to_string()))
+        );
+
+        let err = Profile::from_toml_str("vendor_id = 1\nproduct_id = 2\nflow = \"sideways\"")
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            MatterPayloadError::Profile(ProfileError::Toml(_))
+        ));
+    }
+}
@@ -0,0 +1,180 @@
+use qrcode::Color;
+
+use super::{QUIET_ZONE_MODULES, RenderOptions, symbol};
+use crate::error::Result;
+use crate::payload::SetupPayload;
+
+/// A QR code drawn as a packed 1-bit bitmap, the format of monochrome OLED
+/// and e-ink framebuffers.
+///
+/// Pixels are stored row by row, 8 per byte, the leftmost pixel in the most
+/// significant bit. Every row starts on a byte boundary, the unused low
+/// bits of its last byte being 0. A set bit is a dark module. This is the
+/// layout `embedded-graphics` expects of a `BinaryColor` raw image.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Bitmap {
+    width: u32,
+    height: u32,
+    data: Vec<u8>,
+}
+
+impl Bitmap {
+    /// Draws a square symbol `modules` modules wide, surrounded by `border`
+    /// light modules, each module `scale` pixels square; `is_dark` tells
+    /// whether the module at a column and a row is dark.
+    pub(super) fn from_modules(
+        modules: u32,
+        border: u32,
+        scale: u32,
+        is_dark: impl Fn(u32, u32) -> bool,
+    ) -> Bitmap {
+        let size = (modules + 2 * border) * scale;
+        let bytes_per_row = size.div_ceil(8) as usize;
+        let mut data = vec![0; bytes_per_row * size as usize];
+
+        for row in 0..modules {
+            for column in (0..modules).filter(|&column| is_dark(column, row)) {
+                let left = (column + border) * scale;
+                let top = (row + border) * scale;
+                for y in top..top + scale {
+                    for x in left..left + scale {
+                        data[y as usize * bytes_per_row + x as usize / 8] |= 0x80 >> (x % 8);
+                    }
+                }
+            }
+        }
+
+        Bitmap {
+            width: size,
+            height: size,
+            data,
+        }
+    }
+
+    /// Width, in pixels.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Height, in pixels.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Number of bytes holding each row.
+    pub fn bytes_per_row(&self) -> usize {
+        self.width.div_ceil(8) as usize
+    }
+
+    /// The packed pixels, `bytes_per_row() * height()` bytes.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Whether the pixel at column `x` and row `y` is dark; pixels outside
+    /// the bitmap are light.
+    pub fn is_dark(&self, x: u32, y: u32) -> bool {
+        if x >= self.width || y >= self.height {
+            return false;
+        }
+        let byte = self.data[y as usize * self.bytes_per_row() + x as usize / 8];
+        byte & (0x80 >> (x % 8)) != 0
+    }
+
+    /// Borrows the bitmap as an `embedded-graphics` image, to be drawn on
+    /// any `DrawTarget` with `BinaryColor` pixels or converted ones.
+    #[cfg(feature = "embedded-graphics")]
+    pub fn as_image_raw(
+        &self,
+    ) -> embedded_graphics::image::ImageRaw<'_, embedded_graphics::pixelcolor::BinaryColor> {
+        embedded_graphics::image::ImageRaw::new(&self.data, self.width)
+    }
+}
+
+impl SetupPayload {
+    /// Draws the payload's QR code as a packed 1-bit [`Bitmap`], for devices
+    /// showing their own setup code on a monochrome display.
+    ///
+    /// Each module is `options.module_size` pixels square, so that a small
+    /// display can scale the symbol to its resolution.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error [`SetupPayload::to_qr_code_str`] returns if the
+    /// payload cannot be encoded, or `RenderError::Symbol` if the QR code
+    /// string does not fit in a QR symbol.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use matter_setup_code::SetupPayload;
+    /// use matter_setup_code::render::RenderOptions;
+    ///
+    /// let payload = SetupPayload::parse_str("MT:-24J042C00KA0648G00").unwrap();
+    /// let options = RenderOptions {
+    ///     module_size: 2,
+    ///     ..RenderOptions::default()
+    /// };
+    /// let bitmap = payload.to_qr_bitmap(&options).unwrap();
+    /// assert_eq!((bitmap.width(), bitmap.height()), (66, 66));
+    /// assert_eq!(bitmap.data().len(), 9 * 66);
+    /// ```
+    pub fn to_qr_bitmap(&self, options: &RenderOptions) -> Result<Bitmap> {
+        let code = symbol(self, options.error_correction)?;
+        let border = if options.quiet_zone {
+            QUIET_ZONE_MODULES
+        } else {
+            0
+        };
+        let modules = code.width() as u32;
+        let colors = code.to_colors();
+        Ok(Bitmap::from_modules(
+            modules,
+            border,
+            options.module_size.max(1),
+            |x, y| colors[(y * modules + x) as usize] == Color::Dark,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_qr_bitmap() {
+        let payload = SetupPayload::parse_str("MT:-24J042C00KA0648G00").unwrap();
+        let options = RenderOptions {
+            module_size: 1,
+            quiet_zone: false,
+            ..RenderOptions::default()
+        };
+        let bitmap = payload.to_qr_bitmap(&options).unwrap();
+        assert_eq!((bitmap.width(), bitmap.height()), (25, 25));
+        assert_eq!(bitmap.bytes_per_row(), 4);
+        // The finder pattern's top edge: 7 dark modules, then the light
+        // separator, and the row padding left clear.
+        assert_eq!(bitmap.data()[0], 0xFE);
+        assert_eq!(bitmap.data()[3] & 0x7F, 0);
+        assert!(bitmap.is_dark(6, 0));
+        assert!(!bitmap.is_dark(7, 0));
+        assert!(!bitmap.is_dark(25, 0));
+
+        let scaled = payload
+            .to_qr_bitmap(&RenderOptions {
+                module_size: 3,
+                ..RenderOptions::default()
+            })
+            .unwrap();
+        assert_eq!(scaled.width(), 99);
+        for y in 0..scaled.height() {
+            for x in 0..scaled.width() {
+                let (column, row) = ((x / 3) as i64 - 4, (y / 3) as i64 - 4);
+                let expected = (0..25).contains(&column)
+                    && (0..25).contains(&row)
+                    && bitmap.is_dark(column as u32, row as u32);
+                assert_eq!(scaled.is_dark(x, y), expected);
+            }
+        }
+    }
+}
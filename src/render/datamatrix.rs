@@ -0,0 +1,461 @@
+use super::{Bitmap, RenderOptions};
+use crate::error::Result;
+use crate::payload::SetupPayload;
+
+/// A square Data Matrix ECC 200 symbol size.
+struct SymbolSize {
+    /// Width and height, in modules
+    modules: u32,
+    /// Width and height of each data region, in modules
+    region: u32,
+    /// Data codewords
+    data: usize,
+    /// Error correction codewords
+    error_correction: usize,
+    /// Interleaved Reed-Solomon blocks
+    blocks: usize,
+}
+
+const fn size(
+    modules: u32,
+    region: u32,
+    data: usize,
+    error_correction: usize,
+    blocks: usize,
+) -> SymbolSize {
+    SymbolSize {
+        modules,
+        region,
+        data,
+        error_correction,
+        blocks,
+    }
+}
+
+/// The square ECC 200 symbol sizes, from the smallest (ISO/IEC 16022,
+/// table 7).
+const SYMBOL_SIZES: [SymbolSize; 24] = [
+    size(10, 8, 3, 5, 1),
+    size(12, 10, 5, 7, 1),
+    size(14, 12, 8, 10, 1),
+    size(16, 14, 12, 12, 1),
+    size(18, 16, 18, 14, 1),
+    size(20, 18, 22, 18, 1),
+    size(22, 20, 30, 20, 1),
+    size(24, 22, 36, 24, 1),
+    size(26, 24, 44, 28, 1),
+    size(32, 14, 62, 36, 1),
+    size(36, 16, 86, 42, 1),
+    size(40, 18, 114, 48, 1),
+    size(44, 20, 144, 56, 1),
+    size(48, 22, 174, 68, 1),
+    size(52, 24, 204, 84, 2),
+    size(64, 14, 280, 112, 2),
+    size(72, 16, 368, 144, 4),
+    size(80, 18, 456, 192, 4),
+    size(88, 20, 576, 224, 4),
+    size(96, 22, 696, 272, 4),
+    size(104, 24, 816, 336, 6),
+    size(120, 18, 1050, 408, 6),
+    size(132, 20, 1304, 496, 8),
+    size(144, 22, 1558, 620, 10),
+];
+
+/// Width of the quiet zone around a symbol, in modules.
+const QUIET_ZONE_MODULES: u32 = 1;
+
+/// Encodes `text`, ASCII, in ASCII encodation: two digits in a row share a
+/// codeword, any other character takes one.
+fn encode(text: &[u8]) -> Vec<u8> {
+    let mut codewords = Vec::with_capacity(text.len());
+    let mut index = 0;
+    while index < text.len() {
+        match (text[index], text.get(index + 1)) {
+            (first, Some(&second)) if first.is_ascii_digit() && second.is_ascii_digit() => {
+                codewords.push(130 + (first - b'0') * 10 + (second - b'0'));
+                index += 2;
+            }
+            (character, _) => {
+                codewords.push(character + 1);
+                index += 1;
+            }
+        }
+    }
+    codewords
+}
+
+/// Fills `codewords` up to `capacity` with the pad codeword, then with its
+/// pseudo-randomized form.
+fn pad(codewords: &mut Vec<u8>, capacity: usize) {
+    if codewords.len() < capacity {
+        codewords.push(129);
+    }
+    while codewords.len() < capacity {
+        let position = codewords.len() + 1;
+        let pad = 129 + (149 * position) % 253 + 1;
+        let pad = if pad > 254 { pad - 254 } else { pad };
+        codewords.push(pad as u8);
+    }
+}
+
+/// Product of `a` and `b` in GF(256), modulo x^8 + x^5 + x^3 + x^2 + 1.
+fn gf_mul(mut a: u8, mut b: u8) -> u8 {
+    let mut product = 0;
+    while b != 0 {
+        if b & 1 != 0 {
+            product ^= a;
+        }
+        let carry = a & 0x80 != 0;
+        a <<= 1;
+        if carry {
+            a ^= 0x2D;
+        }
+        b >>= 1;
+    }
+    product
+}
+
+/// The `len` Reed-Solomon error correction codewords of `data`.
+fn error_correction(data: &[u8], len: usize) -> Vec<u8> {
+    // The generator polynomial, (x + 2)(x + 2^2)...(x + 2^len), highest
+    // degree first.
+    let mut generator = vec![0; len + 1];
+    generator[0] = 1;
+    let mut root = 1;
+    for degree in 1..=len {
+        root = gf_mul(root, 2);
+        for index in (1..=degree).rev() {
+            generator[index] ^= gf_mul(generator[index - 1], root);
+        }
+    }
+
+    let mut remainder = vec![0; len];
+    for &codeword in data {
+        let factor = codeword ^ remainder[0];
+        remainder.rotate_left(1);
+        remainder[len - 1] = 0;
+        for (value, &coefficient) in remainder.iter_mut().zip(&generator[1..]) {
+            *value ^= gf_mul(coefficient, factor);
+        }
+    }
+    remainder
+}
+
+/// Appends the error correction codewords to `codewords`, the data of a
+/// symbol of `size`, interleaving the blocks.
+fn add_error_correction(codewords: &mut Vec<u8>, size: &SymbolSize) {
+    let block_len = size.error_correction / size.blocks;
+    codewords.resize(size.data + size.error_correction, 0);
+    for block in 0..size.blocks {
+        let data: Vec<u8> = codewords[..size.data]
+            .iter()
+            .skip(block)
+            .step_by(size.blocks)
+            .copied()
+            .collect();
+        for (index, codeword) in error_correction(&data, block_len).into_iter().enumerate() {
+            codewords[size.data + block + index * size.blocks] = codeword;
+        }
+    }
+}
+
+/// The codeword and bit, 0 being the most significant, each module of the
+/// `side` by `side` mapping matrix holds (ISO/IEC 16022, annex F).
+///
+/// The modules of the bottom right corner no codeword reaches are `None`.
+fn placement(side: usize) -> Vec<Option<(usize, u8)>> {
+    let n = side as isize;
+    let mut cells = vec![None; side * side];
+    let place = |cells: &mut Vec<Option<(usize, u8)>>, modules: [(isize, isize); 8], codeword| {
+        for (bit, (mut row, mut col)) in (0..).zip(modules) {
+            if row < 0 {
+                row += n;
+                col += 4 - (n + 4) % 8;
+            }
+            if col < 0 {
+                col += n;
+                row += 4 - (n + 4) % 8;
+            }
+            cells[(row * n + col) as usize] = Some((codeword, bit));
+        }
+    };
+    let utah = |row: isize, col: isize| {
+        [
+            (row - 2, col - 2),
+            (row - 2, col - 1),
+            (row - 1, col - 2),
+            (row - 1, col - 1),
+            (row - 1, col),
+            (row, col - 2),
+            (row, col - 1),
+            (row, col),
+        ]
+    };
+    let corners = [
+        [
+            (n - 1, 0),
+            (n - 1, 1),
+            (n - 1, 2),
+            (0, n - 2),
+            (0, n - 1),
+            (1, n - 1),
+            (2, n - 1),
+            (3, n - 1),
+        ],
+        [
+            (n - 3, 0),
+            (n - 2, 0),
+            (n - 1, 0),
+            (0, n - 4),
+            (0, n - 3),
+            (0, n - 2),
+            (0, n - 1),
+            (1, n - 1),
+        ],
+        [
+            (n - 3, 0),
+            (n - 2, 0),
+            (n - 1, 0),
+            (0, n - 2),
+            (0, n - 1),
+            (1, n - 1),
+            (2, n - 1),
+            (3, n - 1),
+        ],
+        [
+            (n - 1, 0),
+            (n - 1, n - 1),
+            (0, n - 3),
+            (0, n - 2),
+            (0, n - 1),
+            (1, n - 3),
+            (1, n - 2),
+            (1, n - 1),
+        ],
+    ];
+
+    let (mut row, mut col, mut codeword) = (4, 0, 0);
+    loop {
+        let corner = match (row - n, col) {
+            (0, 0) => Some(0),
+            (-2, 0) if n % 4 != 0 => Some(1),
+            (-2, 0) if n % 8 == 4 => Some(2),
+            (4, 2) if n % 8 == 0 => Some(3),
+            _ => None,
+        };
+        if let Some(corner) = corner {
+            place(&mut cells, corners[corner], codeword);
+            codeword += 1;
+        }
+        // Sweep up and to the right, then down and to the left.
+        loop {
+            if row < n && col >= 0 && cells[(row * n + col) as usize].is_none() {
+                place(&mut cells, utah(row, col), codeword);
+                codeword += 1;
+            }
+            row -= 2;
+            col += 2;
+            if row < 0 || col >= n {
+                break;
+            }
+        }
+        row += 1;
+        col += 3;
+        loop {
+            if row >= 0 && col < n && cells[(row * n + col) as usize].is_none() {
+                place(&mut cells, utah(row, col), codeword);
+                codeword += 1;
+            }
+            row += 2;
+            col -= 2;
+            if row >= n || col < 0 {
+                break;
+            }
+        }
+        row += 3;
+        col += 1;
+        if row >= n && col >= n {
+            break;
+        }
+    }
+    cells
+}
+
+/// The smallest square symbol holding `codewords` data codewords.
+fn symbol_size(codewords: usize) -> &'static SymbolSize {
+    // A QR code string within the size limit takes at most 875 codewords.
+    SYMBOL_SIZES
+        .iter()
+        .find(|size| size.data >= codewords)
+        .unwrap_or(&SYMBOL_SIZES[SYMBOL_SIZES.len() - 1])
+}
+
+impl SetupPayload {
+    /// Width and height, in modules, of the Data Matrix symbol
+    /// [`SetupPayload::to_data_matrix_bitmap`] draws, quiet zone excluded.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`SetupPayload::to_qr_code_str`].
+    pub fn data_matrix_modules(&self) -> Result<u32> {
+        let text = self.to_qr_code_str()?;
+        Ok(symbol_size(encode(text.as_bytes()).len()).modules)
+    }
+
+    /// Draws the payload's QR code string as a square Data Matrix ECC 200
+    /// symbol, in the packed 1-bit [`Bitmap`] format.
+    ///
+    /// The Matter specification only defines QR codes, so Matter
+    /// commissioners will not scan this symbol: it is meant for industrial
+    /// labels with no room for a QR code, read back by the label owner's own
+    /// tooling. The symbol holds the exact "MT:" string, and is often much
+    /// smaller than the QR code: 18 modules wide instead of 25 for a payload
+    /// without optional data.
+    ///
+    /// The module size and quiet zone of `options` apply, the quiet zone
+    /// being the single module Data Matrix requires. Data Matrix has a fixed
+    /// error correction level per symbol size, so `options.error_correction`
+    /// is ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`SetupPayload::to_qr_code_str`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use matter_setup_code::SetupPayload;
+    /// use matter_setup_code::render::RenderOptions;
+    ///
+    /// let payload = SetupPayload::parse_str("MT:-24J042C00KA0648G00").unwrap();
+    /// assert_eq!(payload.data_matrix_modules().unwrap(), 18);
+    /// let bitmap = payload.to_data_matrix_bitmap(&RenderOptions::default()).unwrap();
+    /// assert_eq!(bitmap.width(), 20 * 8);
+    /// ```
+    pub fn to_data_matrix_bitmap(&self, options: &RenderOptions) -> Result<Bitmap> {
+        let text = self.to_qr_code_str()?;
+        let mut codewords = encode(text.as_bytes());
+        let size = symbol_size(codewords.len());
+        pad(&mut codewords, size.data);
+        add_error_correction(&mut codewords, size);
+
+        let regions = size.modules / (size.region + 2);
+        let side = (regions * size.region) as usize;
+        let cells = placement(side);
+        let border = if options.quiet_zone {
+            QUIET_ZONE_MODULES
+        } else {
+            0
+        };
+        Ok(Bitmap::from_modules(
+            size.modules,
+            border,
+            options.module_size.max(1),
+            |x, y| {
+                let (column, row) = (x % (size.region + 2), y % (size.region + 2));
+                if column == 0 || row == size.region + 1 {
+                    // The solid L of the finder pattern.
+                    true
+                } else if row == 0 {
+                    // The alternating top and right edges.
+                    column % 2 == 0
+                } else if column == size.region + 1 {
+                    row % 2 == 1
+                } else {
+                    let row = (y / (size.region + 2) * size.region + row - 1) as usize;
+                    let col = (x / (size.region + 2) * size.region + column - 1) as usize;
+                    match cells[row * side + col] {
+                        Some((codeword, bit)) => codewords[codeword] & (0x80 >> bit) != 0,
+                        // The fixed pattern of the bottom right corner.
+                        None => row == col,
+                    }
+                }
+            },
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode() {
+        assert_eq!(encode(b"123456"), [142, 164, 186]);
+        assert_eq!(encode(b"MT:-24J0"), [78, 85, 59, 46, 154, 75, 49]);
+
+        let mut codewords = encode(b"MT");
+        pad(&mut codewords, 5);
+        assert_eq!(codewords, [78, 85, 129, 220, 115]);
+    }
+
+    #[test]
+    fn test_error_correction() {
+        // The worked example of ISO/IEC 16022, annex O.
+        assert_eq!(error_correction(&[142, 164, 186], 5), [114, 25, 5, 88, 102]);
+    }
+
+    #[test]
+    fn test_placement() {
+        for size in &SYMBOL_SIZES {
+            let regions = size.modules / (size.region + 2);
+            let side = (regions * size.region) as usize;
+            let cells = placement(side);
+            let total = size.data + size.error_correction;
+
+            // Every bit of every codeword has exactly one module.
+            let mut placed: Vec<(usize, u8)> = cells.iter().flatten().copied().collect();
+            placed.sort_unstable();
+            let expected: Vec<(usize, u8)> = (0..total)
+                .flat_map(|codeword| (0..8).map(move |bit| (codeword, bit)))
+                .collect();
+            assert_eq!(placed, expected, "{} modules", size.modules);
+
+            // The modules left over, if any, are the bottom right 2x2 square.
+            let unused: Vec<usize> = (0..cells.len()).filter(|&i| cells[i].is_none()).collect();
+            assert!(
+                unused.is_empty()
+                    || unused
+                        == [
+                            (side - 2) * side + side - 2,
+                            (side - 2) * side + side - 1,
+                            side * side - 2,
+                            side * side - 1,
+                        ],
+                "{} modules",
+                size.modules
+            );
+        }
+    }
+
+    #[test]
+    fn test_data_matrix_bitmap() {
+        let payload = SetupPayload::parse_str("MT:-24J042C00KA0648G00").unwrap();
+        let options = RenderOptions {
+            module_size: 1,
+            quiet_zone: false,
+            ..RenderOptions::default()
+        };
+        let bitmap = payload.to_data_matrix_bitmap(&options).unwrap();
+        assert_eq!((bitmap.width(), bitmap.height()), (18, 18));
+        // The finder pattern: a solid left and bottom edge, an alternating
+        // top and right edge.
+        for i in 0..18 {
+            assert!(bitmap.is_dark(0, i));
+            assert!(bitmap.is_dark(i, 17));
+            assert_eq!(bitmap.is_dark(i, 0), i % 2 == 0);
+            assert_eq!(bitmap.is_dark(17, i), i % 2 == 1);
+        }
+
+        let scaled = payload
+            .to_data_matrix_bitmap(&RenderOptions {
+                module_size: 2,
+                ..RenderOptions::default()
+            })
+            .unwrap();
+        assert_eq!(scaled.width(), 40);
+        assert!(!scaled.is_dark(1, 1));
+        assert!(scaled.is_dark(2, 2));
+        assert!(scaled.is_dark(3, 37));
+    }
+}
@@ -0,0 +1,133 @@
+use embedded_graphics::Drawable;
+use embedded_graphics::draw_target::DrawTarget;
+use embedded_graphics::geometry::{Dimensions, Point, Size};
+use embedded_graphics::image::Image;
+use embedded_graphics::pixelcolor::BinaryColor;
+use embedded_graphics::primitives::Rectangle;
+use embedded_graphics::transform::Transform;
+
+use super::Bitmap;
+
+/// A [`Bitmap`] placed on an `embedded-graphics` display, drawn with
+/// [`BinaryColor::On`] for dark pixels and [`BinaryColor::Off`] for light
+/// ones.
+///
+/// A display with other pixels can draw it through
+/// `DrawTargetExt::color_converted`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PlacedBitmap {
+    bitmap: Bitmap,
+    top_left: Point,
+}
+
+impl PlacedBitmap {
+    /// The bitmap drawn.
+    pub fn bitmap(&self) -> &Bitmap {
+        &self.bitmap
+    }
+}
+
+impl Bitmap {
+    /// Places the bitmap with its top left corner at `top_left`, ready to
+    /// be drawn on any `DrawTarget`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use embedded_graphics::mock_display::MockDisplay;
+    /// use embedded_graphics::pixelcolor::BinaryColor;
+    /// use embedded_graphics::prelude::*;
+    /// use matter_setup_code::SetupPayload;
+    /// use matter_setup_code::render::RenderOptions;
+    ///
+    /// let payload = SetupPayload::parse_str("MT:-24J042C00KA0648G00").unwrap();
+    /// let options = RenderOptions { module_size: 1, ..RenderOptions::default() };
+    ///
+    /// let mut display = MockDisplay::<BinaryColor>::new();
+    /// let qr_code = payload.to_qr_bitmap(&options).unwrap().at(Point::new(2, 2));
+    /// qr_code.draw(&mut display).unwrap();
+    /// assert_eq!(display.get_pixel(Point::new(6, 6)), Some(BinaryColor::On));
+    /// ```
+    pub fn at(self, top_left: Point) -> PlacedBitmap {
+        PlacedBitmap {
+            bitmap: self,
+            top_left,
+        }
+    }
+}
+
+impl Dimensions for PlacedBitmap {
+    fn bounding_box(&self) -> Rectangle {
+        Rectangle::new(
+            self.top_left,
+            Size::new(self.bitmap.width(), self.bitmap.height()),
+        )
+    }
+}
+
+impl Transform for PlacedBitmap {
+    fn translate(&self, by: Point) -> Self {
+        PlacedBitmap {
+            bitmap: self.bitmap.clone(),
+            top_left: self.top_left + by,
+        }
+    }
+
+    fn translate_mut(&mut self, by: Point) -> &mut Self {
+        self.top_left += by;
+        self
+    }
+}
+
+impl Drawable for PlacedBitmap {
+    type Color = BinaryColor;
+    type Output = ();
+
+    fn draw<D>(&self, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = BinaryColor>,
+    {
+        Image::new(&self.bitmap.as_image_raw(), self.top_left).draw(target)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::payload::SetupPayload;
+    use crate::render::RenderOptions;
+    use embedded_graphics::mock_display::MockDisplay;
+
+    #[test]
+    fn test_placed_bitmap() {
+        let payload = SetupPayload::parse_str("MT:-24J042C00KA0648G00").unwrap();
+        let options = RenderOptions {
+            module_size: 1,
+            quiet_zone: false,
+            ..RenderOptions::default()
+        };
+        let bitmap = payload.to_qr_bitmap(&options).unwrap();
+        let placed = bitmap.clone().at(Point::new(2, 3));
+        assert_eq!(
+            placed.bounding_box(),
+            Rectangle::new(Point::new(2, 3), Size::new(25, 25))
+        );
+
+        let mut display = MockDisplay::new();
+        placed.draw(&mut display).unwrap();
+        for y in 0..25 {
+            for x in 0..25 {
+                let expected = BinaryColor::from(bitmap.is_dark(x, y));
+                let point = Point::new(x as i32 + 2, y as i32 + 3);
+                assert_eq!(display.get_pixel(point), Some(expected));
+            }
+        }
+        // Nothing is drawn outside the bitmap.
+        assert_eq!(display.get_pixel(Point::new(1, 3)), None);
+        assert_eq!(display.get_pixel(Point::new(27, 3)), None);
+
+        let moved = placed.translate(Point::new(10, 0));
+        assert_eq!(moved.bounding_box().top_left, Point::new(12, 3));
+        assert_eq!(moved.bitmap(), &bitmap);
+    }
+}
@@ -0,0 +1,181 @@
+use image::imageops::{self, FilterType};
+use image::{DynamicImage, Luma, Rgba, RgbaImage};
+
+use super::style::{Rgb, Style, styled_symbol};
+use super::{QUIET_ZONE_MODULES, RenderOptions, symbol};
+use crate::error::Result;
+use crate::payload::SetupPayload;
+
+impl From<Rgb> for Rgba<u8> {
+    fn from(color: Rgb) -> Self {
+        Rgba([color.0, color.1, color.2, 255])
+    }
+}
+
+impl SetupPayload {
+    /// Draws the payload's QR code as a black-on-white grayscale image, ready
+    /// to be saved as a PNG or composed into a label.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error [`SetupPayload::to_qr_code_str`] returns if the
+    /// payload cannot be encoded, or `RenderError::Symbol` if the QR code
+    /// string does not fit in a QR symbol.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use matter_setup_code::SetupPayload;
+    /// use matter_setup_code::render::RenderOptions;
+    ///
+    /// let payload = SetupPayload::parse_str("MT:-24J042C00KA0648G00").unwrap();
+    /// let image = payload.to_qr_image(&RenderOptions::default()).unwrap();
+    /// // A 25-module symbol and its quiet zone, at 8 pixels per module.
+    /// assert_eq!(image.width(), 33 * 8);
+    /// ```
+    pub fn to_qr_image(&self, options: &RenderOptions) -> Result<DynamicImage> {
+        let module_size = options.module_size.max(1);
+        let image = symbol(self, options.error_correction)?
+            .render::<Luma<u8>>()
+            .quiet_zone(options.quiet_zone)
+            .module_dimensions(module_size, module_size)
+            .build();
+        Ok(DynamicImage::ImageLuma8(image))
+    }
+
+    /// Draws the payload's QR code as an RGBA image in `style`, with `logo`
+    /// scaled to fit the square at its center.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors [`SetupPayload::to_qr_image`] returns, or
+    /// `RenderError::LowContrast` or `RenderError::LogoTooLarge` if the
+    /// style would keep scanners from reading the code.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use matter_setup_code::SetupPayload;
+    /// use matter_setup_code::render::{RenderOptions, Rgb, Style};
+    ///
+    /// let payload = SetupPayload::parse_str("MT:-24J042C00KA0648G00").unwrap();
+    /// let style = Style {
+    ///     foreground: Rgb(0, 51, 102),
+    ///     rounded_modules: true,
+    ///     ..Style::default()
+    /// };
+    /// let image = payload
+    ///     .to_qr_image_styled(&RenderOptions::default(), &style, None)
+    ///     .unwrap();
+    /// assert_eq!(image.width(), 33 * 8);
+    /// ```
+    pub fn to_qr_image_styled(
+        &self,
+        options: &RenderOptions,
+        style: &Style,
+        logo: Option<&DynamicImage>,
+    ) -> Result<DynamicImage> {
+        let symbol = styled_symbol(self, options, style, logo.is_some())?;
+        let scale = options.module_size.max(1);
+        let border = if options.quiet_zone {
+            QUIET_ZONE_MODULES
+        } else {
+            0
+        };
+        let size = (symbol.code.width() as u32 + 2 * border) * scale;
+        let mut image = RgbaImage::from_pixel(size, size, style.background.into());
+
+        let radius = f64::from(scale) / 2.0;
+        for (x, y, round) in symbol.dark_modules() {
+            let left = (x as u32 + border) * scale;
+            let top = (y as u32 + border) * scale;
+            for dy in 0..scale {
+                for dx in 0..scale {
+                    let (cx, cy) = (f64::from(dx) + 0.5 - radius, f64::from(dy) + 0.5 - radius);
+                    if !round || cx * cx + cy * cy <= radius * radius {
+                        image.put_pixel(left + dx, top + dy, style.foreground.into());
+                    }
+                }
+            }
+        }
+
+        if let (Some(logo), Some(area)) = (logo, symbol.logo) {
+            let width = area.len() as u32 * scale;
+            let logo = logo.resize(width, width, FilterType::Triangle).into_rgba8();
+            let left = (area.start as u32 + border) * scale + (width - logo.width()) / 2;
+            let top = (area.start as u32 + border) * scale + (width - logo.height()) / 2;
+            imageops::overlay(&mut image, &logo, left.into(), top.into());
+        }
+        Ok(DynamicImage::ImageRgba8(image))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_qr_image() {
+        let payload = SetupPayload::parse_str("MT:-24J042C00KA0648G00").unwrap();
+        let image = payload
+            .to_qr_image(&RenderOptions::default())
+            .unwrap()
+            .into_luma8();
+        assert_eq!(image.dimensions(), (264, 264));
+        // The quiet zone is light, the finder pattern's corner dark.
+        assert_eq!(image.get_pixel(0, 0), &Luma([255]));
+        assert_eq!(image.get_pixel(32, 32), &Luma([0]));
+
+        let options = RenderOptions {
+            module_size: 2,
+            quiet_zone: false,
+            ..RenderOptions::default()
+        };
+        let image = payload.to_qr_image(&options).unwrap();
+        assert_eq!(image.width(), 50);
+        assert_eq!(image.height(), 50);
+    }
+
+    #[test]
+    fn test_qr_image_styled() {
+        let payload = SetupPayload::parse_str("MT:-24J042C00KA0648G00").unwrap();
+        let options = RenderOptions {
+            module_size: 4,
+            ..RenderOptions::default()
+        };
+        let style = Style {
+            foreground: Rgb(0, 51, 102),
+            background: Rgb(255, 236, 179),
+            rounded_modules: true,
+            ..Style::default()
+        };
+        let foreground = Rgba([0, 51, 102, 255]);
+        let background = Rgba([255, 236, 179, 255]);
+
+        let image = payload
+            .to_qr_image_styled(&options, &style, None)
+            .unwrap()
+            .into_rgba8();
+        assert_eq!(image.dimensions(), (132, 132));
+        assert_eq!(image.get_pixel(0, 0), &background);
+        // The finder pattern's corner module is a full square.
+        assert_eq!(image.get_pixel(16, 16), &foreground);
+        // Data modules are dots: their corners are background.
+        let symbol = styled_symbol(&payload, &options, &style, false).unwrap();
+        let (x, y, _) = symbol.dark_modules().find(|module| module.2).unwrap();
+        let (left, top) = ((x as u32 + 4) * 4, (y as u32 + 4) * 4);
+        assert_eq!(image.get_pixel(left + 1, top + 1), &foreground);
+        assert_eq!(image.get_pixel(left, top), &background);
+
+        // A red logo fills the 5-module square at the center.
+        let logo = DynamicImage::ImageRgba8(RgbaImage::from_pixel(10, 10, Rgba([255, 0, 0, 255])));
+        let image = payload
+            .to_qr_image_styled(&options, &Style::default(), Some(&logo))
+            .unwrap()
+            .into_rgba8();
+        assert_eq!(image.dimensions(), (132, 132));
+        assert_eq!(image.get_pixel(56, 56), &Rgba([255, 0, 0, 255]));
+        assert_eq!(image.get_pixel(75, 75), &Rgba([255, 0, 0, 255]));
+        assert_ne!(image.get_pixel(76, 76), &Rgba([255, 0, 0, 255]));
+    }
+}
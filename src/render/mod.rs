@@ -0,0 +1,92 @@
+//! Drawing setup payloads as QR code symbols.
+//!
+//! [`SetupPayload::to_qr_code_str`] only produces the text a QR code holds.
+//! The renderers here encode that text into a QR symbol, with the
+//! [`qrcode`] crate, and draw it in the format a label pipeline, a web page
+//! or a screen needs. [`RenderOptions`] sets the module size, the quiet
+//! zone and the error correction level of the image renderers.
+//!
+//! Packaging often wants a branded code: the `_styled` variants of the
+//! raster and SVG renderers take a [`Style`], with colors, dotted modules
+//! and a centered logo, and refuse styles a scanner may not read.
+//!
+//! [`SetupPayload::to_qr_terminal`] and [`SetupPayload::to_qr_bitmap`], for
+//! monochrome displays, are always available. Each other format has its
+//! own feature, so that only the dependencies it needs are pulled in:
+//!
+//! - `image`: raster images, with [`SetupPayload::to_qr_image`]
+//! - `svg`: SVG documents, with [`SetupPayload::to_qr_svg`]
+//! - `embedded-graphics`: [`Bitmap::at`], placing a bitmap on a display as
+//!   an `embedded-graphics` `Drawable`, and [`Bitmap::as_image_raw`]
+//! - `off-spec-data-matrix`: `SetupPayload::to_data_matrix_bitmap`, which
+//!   draws the QR code string as a Data Matrix symbol instead, for labels
+//!   too small for a QR code; no Matter commissioner scans it
+
+mod bitmap;
+#[cfg(feature = "off-spec-data-matrix")]
+mod datamatrix;
+#[cfg(feature = "embedded-graphics")]
+mod drawable;
+#[cfg(feature = "image")]
+mod image;
+#[cfg(any(feature = "image", feature = "svg"))]
+mod style;
+#[cfg(feature = "svg")]
+mod svg;
+mod terminal;
+
+pub use crate::symbol::ErrorCorrection;
+pub use bitmap::Bitmap;
+#[cfg(feature = "embedded-graphics")]
+pub use drawable::PlacedBitmap;
+#[cfg(any(feature = "image", feature = "svg"))]
+pub use style::{Rgb, Style};
+
+use qrcode::{EcLevel, QrCode};
+
+use crate::error::{RenderError, Result};
+use crate::payload::SetupPayload;
+
+/// Width of the quiet zone around a symbol, in modules.
+const QUIET_ZONE_MODULES: u32 = 4;
+
+impl From<ErrorCorrection> for EcLevel {
+    fn from(level: ErrorCorrection) -> Self {
+        match level {
+            ErrorCorrection::Low => EcLevel::L,
+            ErrorCorrection::Medium => EcLevel::M,
+            ErrorCorrection::Quartile => EcLevel::Q,
+            ErrorCorrection::High => EcLevel::H,
+        }
+    }
+}
+
+/// How a QR code symbol is drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RenderOptions {
+    /// Width and height of one module, in pixels; 0 is taken as 1
+    pub module_size: u32,
+    /// Surround the symbol with the 4-module light border scanners need to
+    /// find it; leave it out only if the label design provides one
+    pub quiet_zone: bool,
+    /// Error correction level
+    pub error_correction: ErrorCorrection,
+}
+
+impl Default for RenderOptions {
+    /// 8-pixel modules, with a quiet zone and medium error correction.
+    fn default() -> Self {
+        RenderOptions {
+            module_size: 8,
+            quiet_zone: true,
+            error_correction: ErrorCorrection::Medium,
+        }
+    }
+}
+
+/// Encodes the QR code string of `payload` into a QR symbol.
+fn symbol(payload: &SetupPayload, error_correction: ErrorCorrection) -> Result<QrCode> {
+    let text = payload.to_qr_code_str()?;
+    QrCode::with_error_correction_level(text, error_correction.into())
+        .map_err(|error| RenderError::Symbol(error).into())
+}
@@ -0,0 +1,244 @@
+use std::ops::Range;
+
+use qrcode::{Color, QrCode};
+
+use super::{ErrorCorrection, RenderOptions, symbol};
+use crate::error::{RenderError, Result};
+use crate::payload::SetupPayload;
+
+/// Smallest difference in relative luminance between the background and
+/// the foreground: the 40% symbol contrast ISO/IEC 15415 still grades C.
+const MIN_CONTRAST: f64 = 0.4;
+
+/// An sRGB color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    /// Black, the default foreground.
+    pub const BLACK: Rgb = Rgb(0, 0, 0);
+    /// White, the default background.
+    pub const WHITE: Rgb = Rgb(255, 255, 255);
+
+    /// Relative luminance, from 0 for black to 1 for white.
+    fn luminance(self) -> f64 {
+        let linear = |channel: u8| {
+            let channel = f64::from(channel) / 255.0;
+            if channel <= 0.04045 {
+                channel / 12.92
+            } else {
+                ((channel + 0.055) / 1.055).powf(2.4)
+            }
+        };
+        0.2126 * linear(self.0) + 0.7152 * linear(self.1) + 0.0722 * linear(self.2)
+    }
+}
+
+/// How a branded QR code symbol looks.
+///
+/// Styling must not get in the way of scanning, so the styled renderers
+/// check that the foreground stays darker than the background, with a
+/// contrast of at least 40% in relative luminance, and that error
+/// correction can make up for the modules a logo hides.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Style {
+    /// Color of the dark modules
+    pub foreground: Rgb,
+    /// Color of the light modules and of the quiet zone
+    pub background: Rgb,
+    /// Draw the data modules as dots; the finder, timing and alignment
+    /// patterns stay square for scanners to locate the symbol
+    pub rounded_modules: bool,
+    /// Width of the logo's square, as a percentage of the symbol width,
+    /// quiet zone excluded; only used when a logo is drawn
+    pub logo_width: u8,
+}
+
+impl Default for Style {
+    /// Black on white, square modules, and a logo a fifth of the symbol
+    /// wide.
+    fn default() -> Self {
+        Style {
+            foreground: Rgb::BLACK,
+            background: Rgb::WHITE,
+            rounded_modules: false,
+            logo_width: 20,
+        }
+    }
+}
+
+/// Fraction of its modules a symbol of `level` recovers.
+fn recovery(level: ErrorCorrection) -> f64 {
+    match level {
+        ErrorCorrection::Low => 0.07,
+        ErrorCorrection::Medium => 0.15,
+        ErrorCorrection::Quartile => 0.25,
+        ErrorCorrection::High => 0.30,
+    }
+}
+
+/// A QR symbol checked for drawing in a [`Style`].
+pub(super) struct StyledSymbol {
+    pub(super) code: QrCode,
+    /// Columns, and rows, of the square of modules the logo hides
+    pub(super) logo: Option<Range<usize>>,
+    rounded_modules: bool,
+}
+
+impl StyledSymbol {
+    /// The dark modules to draw, as their column, their row and whether
+    /// they are drawn as dots. Modules under the logo are left out.
+    pub(super) fn dark_modules(&self) -> impl Iterator<Item = (usize, usize, bool)> + '_ {
+        let width = self.code.width();
+        let hidden = |x: usize, y: usize| {
+            self.logo
+                .as_ref()
+                .is_some_and(|logo| logo.contains(&x) && logo.contains(&y))
+        };
+        (0..width * width)
+            .map(move |index| (index % width, index / width))
+            .filter(move |&(x, y)| self.code[(x, y)] == Color::Dark && !hidden(x, y))
+            .map(|(x, y)| (x, y, self.rounded_modules && !self.code.is_functional(x, y)))
+    }
+}
+
+/// Encodes the QR code string of `payload` into a symbol drawn in
+/// `style`, with a logo if `with_logo`.
+///
+/// The modules under a logo are lost outright, so a logo raises error
+/// correction to at least [`ErrorCorrection::Quartile`], and further until
+/// the level recovers twice the modules the logo hides: the margin makes up
+/// for the modules its edges damage and for label wear. The logo must not
+/// hide any finder, timing or alignment pattern either.
+pub(super) fn styled_symbol(
+    payload: &SetupPayload,
+    options: &RenderOptions,
+    style: &Style,
+    with_logo: bool,
+) -> Result<StyledSymbol> {
+    if style.background.luminance() - style.foreground.luminance() < MIN_CONTRAST {
+        return Err(RenderError::LowContrast.into());
+    }
+    if !with_logo {
+        return Ok(StyledSymbol {
+            code: symbol(payload, options.error_correction)?,
+            logo: None,
+            rounded_modules: style.rounded_modules,
+        });
+    }
+
+    let lowest = options.error_correction.max(ErrorCorrection::Quartile);
+    for level in ErrorCorrection::ALL {
+        if level < lowest {
+            continue;
+        }
+        let code = symbol(payload, level)?;
+        let modules = code.width();
+        let width = (modules * usize::from(style.logo_width)).div_ceil(100);
+        if 2.0 * (width * width) as f64 > recovery(level) * (modules * modules) as f64 {
+            continue;
+        }
+        let start = (modules - width) / 2;
+        let logo = start..start + width;
+        if logo
+            .clone()
+            .any(|y| logo.clone().any(|x| code.is_functional(x, y)))
+        {
+            continue;
+        }
+        return Ok(StyledSymbol {
+            code,
+            logo: Some(logo),
+            rounded_modules: style.rounded_modules,
+        });
+    }
+    Err(RenderError::LogoTooLarge(style.logo_width).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MatterPayloadError;
+    use qrcode::EcLevel;
+
+    #[test]
+    fn test_styled_symbol() {
+        let payload = SetupPayload::parse_str("MT:-24J042C00KA0648G00").unwrap();
+        let options = RenderOptions::default();
+
+        let symbol = styled_symbol(&payload, &options, &Style::default(), false).unwrap();
+        assert_eq!(symbol.code.width(), 25);
+        assert_eq!(symbol.logo, None);
+        assert!(symbol.dark_modules().all(|(_, _, round)| !round));
+
+        // A logo a fifth of the symbol wide: Quartile error correction
+        // covers it, in the same version 2 symbol.
+        let symbol = styled_symbol(&payload, &options, &Style::default(), true).unwrap();
+        assert_eq!(symbol.code.error_correction_level(), EcLevel::Q);
+        assert_eq!(symbol.code.width(), 25);
+        assert_eq!(symbol.logo, Some(10..15));
+        assert!(
+            symbol
+                .dark_modules()
+                .all(|(x, y, _)| !(10..15).contains(&x) || !(10..15).contains(&y))
+        );
+
+        // A wider one needs High error correction, and a version 3 symbol.
+        let style = Style {
+            logo_width: 35,
+            ..Style::default()
+        };
+        let symbol = styled_symbol(&payload, &options, &style, true).unwrap();
+        assert_eq!(symbol.code.error_correction_level(), EcLevel::H);
+        assert_eq!(symbol.code.width(), 29);
+        assert_eq!(symbol.logo, Some(9..20));
+
+        let style = Style {
+            rounded_modules: true,
+            ..Style::default()
+        };
+        let symbol = styled_symbol(&payload, &options, &style, false).unwrap();
+        // The finder pattern's corner stays square, data modules are dots.
+        assert!(symbol.dark_modules().any(|module| module == (0, 0, false)));
+        assert!(symbol.dark_modules().any(|(_, _, round)| round));
+    }
+
+    #[test]
+    fn test_styled_symbol_scannability() {
+        let payload = SetupPayload::parse_str("MT:-24J042C00KA0648G00").unwrap();
+        let options = RenderOptions::default();
+
+        // Inverted colors, and colors too close.
+        for (foreground, background) in [
+            (Rgb::WHITE, Rgb::BLACK),
+            (Rgb(90, 90, 90), Rgb(160, 160, 160)),
+        ] {
+            let style = Style {
+                foreground,
+                background,
+                ..Style::default()
+            };
+            assert!(matches!(
+                styled_symbol(&payload, &options, &style, false),
+                Err(MatterPayloadError::Render(RenderError::LowContrast))
+            ));
+        }
+        let style = Style {
+            foreground: Rgb(0, 51, 102),
+            background: Rgb(255, 236, 179),
+            ..Style::default()
+        };
+        assert!(styled_symbol(&payload, &options, &style, false).is_ok());
+
+        let style = Style {
+            logo_width: 40,
+            ..Style::default()
+        };
+        assert!(matches!(
+            styled_symbol(&payload, &options, &style, true),
+            Err(MatterPayloadError::Render(RenderError::LogoTooLarge(40)))
+        ));
+        // The logo is only checked when there is one.
+        assert!(styled_symbol(&payload, &options, &style, false).is_ok());
+    }
+}
@@ -0,0 +1,187 @@
+use std::fmt::Write;
+
+use qrcode::render::svg::Color;
+
+use super::style::{Rgb, Style, styled_symbol};
+use super::{QUIET_ZONE_MODULES, RenderOptions, symbol};
+use crate::error::Result;
+use crate::payload::SetupPayload;
+
+/// `color` in hexadecimal notation, as in "#0033ff".
+fn hex(color: Rgb) -> String {
+    format!("#{:02x}{:02x}{:02x}", color.0, color.1, color.2)
+}
+
+/// Escapes `text` for an XML attribute value.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('"', "&quot;")
+}
+
+impl SetupPayload {
+    /// Draws the payload's QR code as a standalone SVG document, black on
+    /// white. The document has a view box, so it scales without loss; the
+    /// module size only sets its nominal width and height.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error [`SetupPayload::to_qr_code_str`] returns if the
+    /// payload cannot be encoded, or `RenderError::Symbol` if the QR code
+    /// string does not fit in a QR symbol.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use matter_setup_code::SetupPayload;
+    /// use matter_setup_code::render::RenderOptions;
+    ///
+    /// let payload = SetupPayload::parse_str("MT:-24J042C00KA0648G00").unwrap();
+    /// let svg = payload.to_qr_svg(&RenderOptions::default()).unwrap();
+    /// assert!(svg.contains(r#"viewBox="0 0 264 264""#));
+    /// ```
+    pub fn to_qr_svg(&self, options: &RenderOptions) -> Result<String> {
+        let module_size = options.module_size.max(1);
+        Ok(symbol(self, options.error_correction)?
+            .render::<Color>()
+            .quiet_zone(options.quiet_zone)
+            .module_dimensions(module_size, module_size)
+            .build())
+    }
+
+    /// Draws the payload's QR code as a standalone SVG document in `style`.
+    ///
+    /// `logo` is the URL of an image, or a `data:` URL embedding it, fitted
+    /// to the square at the symbol's center.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors [`SetupPayload::to_qr_svg`] returns, or
+    /// `RenderError::LowContrast` or `RenderError::LogoTooLarge` if the
+    /// style would keep scanners from reading the code.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use matter_setup_code::SetupPayload;
+    /// use matter_setup_code::render::{RenderOptions, Style};
+    ///
+    /// let payload = SetupPayload::parse_str("MT:-24J042C00KA0648G00").unwrap();
+    /// let svg = payload
+    ///     .to_qr_svg_styled(&RenderOptions::default(), &Style::default(), Some("logo.png"))
+    ///     .unwrap();
+    /// assert!(svg.contains(r#"href="logo.png""#));
+    /// ```
+    pub fn to_qr_svg_styled(
+        &self,
+        options: &RenderOptions,
+        style: &Style,
+        logo: Option<&str>,
+    ) -> Result<String> {
+        let symbol = styled_symbol(self, options, style, logo.is_some())?;
+        let scale = options.module_size.max(1);
+        let border = if options.quiet_zone {
+            QUIET_ZONE_MODULES
+        } else {
+            0
+        };
+        let size = (symbol.code.width() as u32 + 2 * border) * scale;
+
+        let mut svg = format!(
+            concat!(
+                r#"<?xml version="1.0" standalone="yes"?>"#,
+                r#"<svg xmlns="http://www.w3.org/2000/svg""#,
+                r#" version="1.1" width="{size}" height="{size}" viewBox="0 0 {size} {size}">"#,
+                r#"<rect x="0" y="0" width="{size}" height="{size}" fill="{background}"/>"#,
+                r#"<path fill="{foreground}" d=""#,
+            ),
+            size = size,
+            background = hex(style.background),
+            foreground = hex(style.foreground),
+        );
+        let radius = f64::from(scale) / 2.0;
+        for (x, y, round) in symbol.dark_modules() {
+            let left = (x as u32 + border) * scale;
+            let top = (y as u32 + border) * scale;
+            if round {
+                let middle = f64::from(top) + radius;
+                write!(
+                    svg,
+                    "M{left} {middle}a{radius} {radius} 0 1 0 {scale} 0a{radius} {radius} 0 1 0 -{scale} 0z"
+                )
+                .unwrap();
+            } else {
+                write!(svg, "M{left} {top}h{scale}v{scale}h-{scale}z").unwrap();
+            }
+        }
+        svg.push_str(r#""/>"#);
+
+        if let (Some(logo), Some(area)) = (logo, symbol.logo) {
+            let start = (area.start as u32 + border) * scale;
+            let width = area.len() as u32 * scale;
+            write!(
+                svg,
+                r#"<image href="{}" x="{start}" y="{start}" width="{width}" height="{width}"/>"#,
+                escape(logo)
+            )
+            .unwrap();
+        }
+        svg.push_str("</svg>");
+        Ok(svg)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::ErrorCorrection;
+
+    #[test]
+    fn test_qr_svg() {
+        let payload = SetupPayload::parse_str("MT:-24J042C00KA0648G00").unwrap();
+        let svg = payload.to_qr_svg(&RenderOptions::default()).unwrap();
+        assert!(svg.starts_with("<?xml"));
+        assert!(svg.ends_with("</svg>"));
+        assert!(svg.contains(r#"width="264" height="264""#));
+
+        // High error correction needs a version 3 symbol, 29 modules wide.
+        let options = RenderOptions {
+            module_size: 1,
+            quiet_zone: false,
+            error_correction: ErrorCorrection::High,
+        };
+        let svg = payload.to_qr_svg(&options).unwrap();
+        assert!(svg.contains(r#"viewBox="0 0 29 29""#));
+    }
+
+    #[test]
+    fn test_qr_svg_styled() {
+        let payload = SetupPayload::parse_str("MT:-24J042C00KA0648G00").unwrap();
+        let options = RenderOptions {
+            module_size: 2,
+            ..RenderOptions::default()
+        };
+        let style = Style {
+            foreground: Rgb(0, 51, 102),
+            background: Rgb(255, 236, 179),
+            rounded_modules: true,
+            ..Style::default()
+        };
+        let svg = payload.to_qr_svg_styled(&options, &style, None).unwrap();
+        assert!(svg.starts_with("<?xml"));
+        assert!(svg.ends_with("</svg>"));
+        assert!(svg.contains(r#"viewBox="0 0 66 66""#));
+        assert!(svg.contains(r##"fill="#ffecb3""##));
+        assert!(svg.contains(r##"<path fill="#003366" d="M8 8h2v2h-2z"##));
+        assert!(svg.contains("a1 1 0 1 0 2 0a1 1 0 1 0 -2 0z"));
+        assert!(!svg.contains("<image"));
+
+        // The 5-module square at the center holds the logo.
+        let svg = payload
+            .to_qr_svg_styled(&options, &Style::default(), Some("logo.svg?a=1&b=2"))
+            .unwrap();
+        assert!(svg.contains(
+            r#"<image href="logo.svg?a=1&amp;b=2" x="28" y="28" width="10" height="10"/></svg>"#
+        ));
+    }
+}
@@ -0,0 +1,56 @@
+use qrcode::render::unicode::Dense1x2;
+
+use super::{ErrorCorrection, symbol};
+use crate::error::Result;
+use crate::payload::SetupPayload;
+
+impl SetupPayload {
+    /// Draws the payload's QR code with Unicode half blocks, two modules per
+    /// character cell, for printing to a terminal.
+    ///
+    /// Terminals are mostly light text on a dark background, so the light
+    /// modules and the quiet zone are the ones drawn as blocks: scanners
+    /// read the result as a regular dark-on-light code.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error [`SetupPayload::to_qr_code_str`] returns if the
+    /// payload cannot be encoded, or `RenderError::Symbol` if the QR code
+    /// string does not fit in a QR symbol.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use matter_setup_code::SetupPayload;
+    ///
+    /// let payload = SetupPayload::parse_str("MT:-24J042C00KA0648G00").unwrap();
+    /// println!("{}", payload.to_qr_terminal().unwrap());
+    /// ```
+    pub fn to_qr_terminal(&self) -> Result<String> {
+        Ok(symbol(self, ErrorCorrection::Medium)?
+            .render::<Dense1x2>()
+            .dark_color(Dense1x2::Light)
+            .light_color(Dense1x2::Dark)
+            .quiet_zone(true)
+            .build())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_qr_terminal() {
+        let payload = SetupPayload::parse_str("MT:-24J042C00KA0648G00").unwrap();
+        let text = payload.to_qr_terminal().unwrap();
+        // A 25-module symbol and its quiet zone: 33 columns, 17 lines.
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 17);
+        assert!(lines.iter().all(|line| line.chars().count() == 33));
+        assert!(lines[0].chars().all(|c| c == '\u{2588}'));
+        // Line 2 holds rows 4 and 5: after the light quiet zone, the finder
+        // pattern's dark corner, then its top edge over its light ring.
+        assert!(lines[2].starts_with("\u{2588}\u{2588}\u{2588}\u{2588} \u{2584}"));
+    }
+}
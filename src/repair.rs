@@ -0,0 +1,272 @@
+//! Suggesting corrections for mistyped or misread codes.
+//!
+//! A manual code whose check digit is wrong was almost always typed with one
+//! wrong digit or two neighbors swapped, the two error kinds the Verhoeff
+//! check digit is designed to catch. [`SetupPayload::suggest_manual_code_corrections`]
+//! tries each such edit and keeps those giving a valid code, so that field
+//! support can offer a short list instead of asking for a new photo.
+//!
+//! A QR code string transcribed from a photo has no check digit, but OCR
+//! tends to confuse the same few characters. [`SetupPayload::suggest_qr_code_corrections`]
+//! swaps each of them in turn and keeps the strings that decode to a
+//! plausible payload.
+
+use crate::error::{PayloadError, Result};
+use crate::payload::{ManualCode, QrCodeString, SetupPayload};
+
+/// Characters OCR commonly reads as one another, both being Base38 digits.
+const OCR_CONFUSIONS: [(char, char); 3] = [('O', '0'), ('I', '1'), ('B', '8')];
+
+/// A single edit turning an invalid code into a valid one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Correction {
+    /// The characters at `offset` and `offset + 1` were swapped.
+    Transposition {
+        /// Byte offset of the first swapped character
+        offset: usize,
+    },
+    /// The character at `offset` was `from` instead of `to`.
+    Substitution {
+        /// Byte offset of the replaced character
+        offset: usize,
+        /// The character in the input
+        from: char,
+        /// The character in the suggested code
+        to: char,
+    },
+}
+
+/// A valid code close to an invalid input, and the edit between them.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Suggestion<T> {
+    /// The suggested code
+    pub code: T,
+    /// How the suggested code differs from the input
+    pub correction: Correction,
+}
+
+impl SetupPayload {
+    /// Suggests the valid manual codes one typo away from `input`, most
+    /// likely first.
+    ///
+    /// Adjacent transpositions come first: a single swap explaining two
+    /// wrong digits is the likelier story. Substitutions follow, from the
+    /// first digit to the last. Only codes that parse are suggested, so a
+    /// candidate with a forbidden passcode is left out. A valid `input` has
+    /// no suggestions.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PayloadError::InvalidManualCodeLength` unless `input` has 11
+    /// or 21 characters, or `PayloadError::InvalidManualCodeDigit` for a
+    /// non-digit.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use matter_setup_code::SetupPayload;
+    /// use matter_setup_code::repair::Correction;
+    ///
+    /// // "34970112332" with its 7th and 8th digits swapped.
+    /// let suggestions = SetupPayload::suggest_manual_code_corrections("34970121332").unwrap();
+    /// assert!(suggestions.iter().any(|s| s.code.as_str() == "34970112332"
+    ///     && s.correction == Correction::Transposition { offset: 6 }));
+    /// ```
+    pub fn suggest_manual_code_corrections(input: &str) -> Result<Vec<Suggestion<ManualCode>>> {
+        if input.len() != 11 && input.len() != 21 {
+            return Err(PayloadError::InvalidManualCodeLength(input.len()).into());
+        }
+        if let Some((offset, character)) = input.char_indices().find(|(_, c)| !c.is_ascii_digit()) {
+            return Err(PayloadError::InvalidManualCodeDigit { offset, character }.into());
+        }
+        if input.parse::<ManualCode>().is_ok() {
+            return Ok(Vec::new());
+        }
+
+        let digits = input.as_bytes();
+        let transpositions = (0..digits.len() - 1)
+            .filter(|&offset| digits[offset] != digits[offset + 1])
+            .map(|offset| {
+                let mut candidate = digits.to_vec();
+                candidate.swap(offset, offset + 1);
+                (candidate, Correction::Transposition { offset })
+            });
+        let substitutions = (0..digits.len()).flat_map(|offset| {
+            (b'0'..=b'9')
+                .filter(move |&digit| digit != digits[offset])
+                .map(move |digit| {
+                    let mut candidate = digits.to_vec();
+                    candidate[offset] = digit;
+                    let correction = Correction::Substitution {
+                        offset,
+                        from: char::from(digits[offset]),
+                        to: char::from(digit),
+                    };
+                    (candidate, correction)
+                })
+        });
+
+        Ok(transpositions
+            .chain(substitutions)
+            .filter_map(|(candidate, correction)| {
+                let code = String::from_utf8(candidate).ok()?.parse().ok()?;
+                Some(Suggestion { code, correction })
+            })
+            .collect())
+    }
+
+    /// Suggests the QR code strings one OCR confusion away from `input`, in
+    /// input order, when `input` does not decode or decodes to an
+    /// implausible payload.
+    ///
+    /// Each of O/0, I/1 and B/8 after the "MT:" prefix is replaced by its
+    /// look-alike in turn. A candidate is kept if it parses and
+    /// [`SetupPayload::validate`] finds nothing wrong with it, which rules
+    /// out the reserved flows, discovery bits and versions a misread tends to
+    /// produce. A plausible `input` has no suggestions.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PayloadError::InvalidQrCodePrefix` if `input` does not start
+    /// with "MT:".
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use matter_setup_code::SetupPayload;
+    /// use matter_setup_code::repair::Correction;
+    ///
+    /// // "MT:-24J042C00KA0648G00" with a zero read as the letter O.
+    /// let suggestions = SetupPayload::suggest_qr_code_corrections("MT:-24J042C0OKA0648G00").unwrap();
+    /// assert_eq!(suggestions.len(), 1);
+    /// assert_eq!(suggestions[0].code.as_str(), "MT:-24J042C00KA0648G00");
+    /// assert_eq!(
+    ///     suggestions[0].correction,
+    ///     Correction::Substitution { offset: 12, from: 'O', to: '0' }
+    /// );
+    /// ```
+    pub fn suggest_qr_code_corrections(input: &str) -> Result<Vec<Suggestion<QrCodeString>>> {
+        if !input.starts_with("MT:") {
+            return Err(PayloadError::InvalidQrCodePrefix.into());
+        }
+        let plausible = |candidate: &str| {
+            candidate
+                .parse::<QrCodeString>()
+                .ok()
+                .filter(|code| SetupPayload::from(code).validate().is_empty())
+        };
+        if plausible(input).is_some() {
+            return Ok(Vec::new());
+        }
+
+        Ok(input
+            .char_indices()
+            .skip("MT:".len())
+            .filter_map(|(offset, from)| {
+                let to = OCR_CONFUSIONS.iter().find_map(|&(a, b)| {
+                    if from == a {
+                        Some(b)
+                    } else if from == b {
+                        Some(a)
+                    } else {
+                        None
+                    }
+                })?;
+                // Every confused character is ASCII, one byte long.
+                let mut candidate = input.to_string();
+                candidate.replace_range(offset..=offset, &to.to_string());
+                let code = plausible(&candidate)?;
+                Some(Suggestion {
+                    code,
+                    correction: Correction::Substitution { offset, from, to },
+                })
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MatterPayloadError;
+
+    #[test]
+    fn test_manual_code_suggestions() {
+        // "11237442363" with its 3rd digit mistyped.
+        let suggestions = SetupPayload::suggest_manual_code_corrections("11737442363").unwrap();
+        assert!(suggestions.contains(&Suggestion {
+            code: "11237442363".parse().unwrap(),
+            correction: Correction::Substitution {
+                offset: 2,
+                from: '7',
+                to: '2',
+            },
+        }));
+        let first_substitution = suggestions
+            .iter()
+            .position(|s| matches!(s.correction, Correction::Substitution { .. }))
+            .unwrap();
+        assert!(
+            suggestions[first_substitution..]
+                .iter()
+                .all(|s| matches!(s.correction, Correction::Substitution { .. }))
+        );
+        for suggestion in &suggestions {
+            assert!(SetupPayload::parse_str(suggestion.code.as_str()).is_ok());
+        }
+
+        assert!(
+            SetupPayload::suggest_manual_code_corrections("11237442363")
+                .unwrap()
+                .is_empty()
+        );
+        assert_eq!(
+            SetupPayload::suggest_manual_code_corrections("1123744236").unwrap_err(),
+            MatterPayloadError::Payload(PayloadError::InvalidManualCodeLength(10))
+        );
+    }
+
+    #[test]
+    fn test_manual_code_suggestions_skip_undecodable_candidates() {
+        // "34970112332" with its first digit mistyped as 7, which announces a
+        // VID and PID that 11 digits cannot hold; so does every candidate
+        // keeping the 7.
+        assert_eq!(
+            SetupPayload::suggest_manual_code_corrections("74970112332").unwrap(),
+            [Suggestion {
+                code: "34970112332".parse().unwrap(),
+                correction: Correction::Substitution {
+                    offset: 0,
+                    from: '7',
+                    to: '3',
+                },
+            }]
+        );
+    }
+
+    #[test]
+    fn test_qr_code_suggestions() {
+        // A zero read as O, decoding to a reserved flow and discovery bits.
+        assert_eq!(
+            SetupPayload::suggest_qr_code_corrections("MT:-24J042CO0KA0648G00").unwrap(),
+            [Suggestion {
+                code: "MT:-24J042C00KA0648G00".parse().unwrap(),
+                correction: Correction::Substitution {
+                    offset: 11,
+                    from: 'O',
+                    to: '0',
+                },
+            }]
+        );
+
+        assert!(
+            SetupPayload::suggest_qr_code_corrections("MT:-24J042C00KA0648G00")
+                .unwrap()
+                .is_empty()
+        );
+        assert_eq!(
+            SetupPayload::suggest_qr_code_corrections("-24J042C0OKA0648G00").unwrap_err(),
+            MatterPayloadError::Payload(PayloadError::InvalidQrCodePrefix)
+        );
+    }
+}
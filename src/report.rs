@@ -0,0 +1,224 @@
+//! Conformance reports in JUnit XML.
+//!
+//! Certification labs attach test evidence to test-management tools, most of
+//! which import the JUnit XML report format. [`ConformanceReport`] parses a
+//! set of payload strings, checks each against a specification revision, and
+//! renders the outcome as one `<testcase>` per payload with
+//! [`ConformanceReport::to_junit_xml`].
+
+use std::error::Error;
+use std::fmt::Write;
+
+use crate::error::MatterPayloadError;
+use crate::payload::SetupPayload;
+use crate::spec::SpecVersion;
+
+/// The outcome of checking one payload string.
+#[derive(Debug, PartialEq, Eq)]
+pub struct ConformanceCase {
+    /// The payload string as given
+    pub input: String,
+    /// The parsed payload, or why it was rejected
+    pub result: Result<SetupPayload, MatterPayloadError>,
+}
+
+impl ConformanceCase {
+    /// Returns the error message including its cause, e.g.
+    /// `Payload processing error: manual code check digit is invalid`.
+    pub fn failure_message(&self) -> Option<String> {
+        self.result.as_ref().err().map(error_chain)
+    }
+}
+
+/// Parse and conformance results for a set of payloads.
+#[derive(Debug, PartialEq, Eq)]
+pub struct ConformanceReport {
+    /// Name of the test suite, e.g. the product or lot under test
+    pub suite: String,
+    /// Revision the payloads were checked against
+    pub version: SpecVersion,
+    /// One case per input, in input order
+    pub cases: Vec<ConformanceCase>,
+}
+
+impl ConformanceReport {
+    /// Parses and checks every input against `version`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use matter_setup_code::report::ConformanceReport;
+    /// use matter_setup_code::spec::SpecVersion;
+    ///
+    /// let report = ConformanceReport::run(
+    ///     "lot-42",
+    ///     SpecVersion::V1_3,
+    ///     ["MT:-24J042C00KA0648G00", "20000000031"],
+    /// );
+    /// assert_eq!(report.failures(), 1);
+    /// assert!(report.to_junit_xml().contains(r#"<testsuite name="lot-42" tests="2" failures="1">"#));
+    /// ```
+    pub fn run<'a>(
+        suite: impl Into<String>,
+        version: SpecVersion,
+        inputs: impl IntoIterator<Item = &'a str>,
+    ) -> Self {
+        let cases = inputs
+            .into_iter()
+            .map(|input| ConformanceCase {
+                input: input.to_string(),
+                result: SetupPayload::parse_str_for_spec(input, version),
+            })
+            .collect();
+        ConformanceReport {
+            suite: suite.into(),
+            version,
+            cases,
+        }
+    }
+
+    /// Number of rejected payloads.
+    pub fn failures(&self) -> usize {
+        self.cases.iter().filter(|c| c.result.is_err()).count()
+    }
+
+    /// Renders the report as a JUnit XML document.
+    ///
+    /// The checked revision and the crate version are recorded as suite
+    /// properties; parsed fields of accepted payloads go to `<system-out>`.
+    pub fn to_junit_xml(&self) -> String {
+        let mut xml = String::new();
+        // Writing to a String cannot fail, so the results are ignored below.
+        let _ = writeln!(xml, r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+        let _ = writeln!(xml, "<testsuites>");
+        let _ = writeln!(
+            xml,
+            r#"  <testsuite name="{}" tests="{}" failures="{}">"#,
+            escape(&self.suite),
+            self.cases.len(),
+            self.failures()
+        );
+        let _ = writeln!(xml, "    <properties>");
+        let _ = writeln!(
+            xml,
+            r#"      <property name="spec_version" value="{}"/>"#,
+            self.version
+        );
+        let _ = writeln!(
+            xml,
+            r#"      <property name="matter_setup_code_version" value="{}"/>"#,
+            env!("CARGO_PKG_VERSION")
+        );
+        let _ = writeln!(xml, "    </properties>");
+        for case in &self.cases {
+            let _ = write!(
+                xml,
+                r#"    <testcase name="{}" classname="{}">"#,
+                escape(&case.input),
+                escape(&self.suite)
+            );
+            match &case.result {
+                Ok(payload) => {
+                    let _ = write!(
+                        xml,
+                        "<system-out>{}</system-out>",
+                        escape(&describe(payload))
+                    );
+                }
+                Err(error) => {
+                    let _ = write!(
+                        xml,
+                        r#"<failure message="{}"/>"#,
+                        escape(&error_chain(error))
+                    );
+                }
+            }
+            let _ = writeln!(xml, "</testcase>");
+        }
+        let _ = writeln!(xml, "  </testsuite>");
+        let _ = writeln!(xml, "</testsuites>");
+        xml
+    }
+}
+
+/// Joins an error's message with those of its causes.
+fn error_chain(error: &MatterPayloadError) -> String {
+    let mut message = error.to_string();
+    let mut source = error.source();
+    while let Some(cause) = source {
+        let _ = write!(message, ": {cause}");
+        source = cause.source();
+    }
+    message
+}
+
+/// One-line summary of the parsed fields.
+fn describe(payload: &SetupPayload) -> String {
+    let mut line = match payload.discriminator.long() {
+        Some(long) => format!("discriminator={long}"),
+        None => format!("short_discriminator={}", payload.discriminator.short()),
+    };
+    if let Some(vid) = payload.vid {
+        let _ = write!(line, " vid={:#06x}", vid.get());
+    }
+    if let Some(pid) = payload.pid {
+        let _ = write!(line, " pid={:#06x}", pid.get());
+    }
+    if let Some(discovery) = payload.discovery {
+        let _ = write!(line, " discovery={discovery}");
+    }
+    let _ = write!(line, " flow={:?}", payload.flow);
+    line
+}
+
+/// Escapes the characters XML reserves in text and attribute values.
+fn escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_junit_report() {
+        let report = ConformanceReport::run(
+            "lot <A&B>",
+            SpecVersion::V1_1,
+            [
+                "MT:-24J042C00KA0648G00",
+                "MT:-24J0M3810KA0648G00",
+                "20000000031",
+            ],
+        );
+        assert_eq!(report.cases.len(), 3);
+        assert_eq!(report.failures(), 2);
+        assert_eq!(
+            report.cases[2].failure_message().unwrap(),
+            "Payload processing error: manual code check digit is invalid"
+        );
+
+        let xml = report.to_junit_xml();
+        assert!(xml.starts_with(r#"<?xml version="1.0" encoding="UTF-8"?>"#));
+        assert!(xml.contains(r#"<testsuite name="lot &lt;A&amp;B&gt;" tests="3" failures="2">"#));
+        assert!(xml.contains(r#"<property name="spec_version" value="Matter 1.1"/>"#));
+        assert!(xml.contains(
+            "<system-out>discriminator=3840 vid=0xfff1 pid=0x8001 discovery=BLE flow=Standard</system-out>"
+        ));
+        assert!(xml.contains(
+            r#"<failure message="Specification version error: discovery capabilities WiFiPAF are not defined in Matter 1.1"/>"#
+        ));
+        assert_eq!(xml.matches("<testcase ").count(), 3);
+    }
+}
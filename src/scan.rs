@@ -0,0 +1,109 @@
+//! Reading setup payloads from photos and scans of QR codes.
+//!
+//! [`SetupPayload::from_qr_image`] locates the QR symbols in an image with
+//! [`rqrr`], decodes them and parses the first one holding a setup payload,
+//! taking test labs from a photo of a label to a payload in one call.
+//! [`SetupPayload::from_qr_image_bytes`] accepts the PNG or JPEG file itself.
+
+use image::DynamicImage;
+
+use crate::error::{Result, ScanError};
+use crate::payload::SetupPayload;
+
+impl SetupPayload {
+    /// Parses the setup payload of the first QR code found in `image`.
+    ///
+    /// Symbols that do not decode, or whose text is not a setup payload,
+    /// such as a product URL printed next to the setup code, are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ScanError::NoQrCode` if the image holds no QR symbol, or,
+    /// if no symbol holds a setup payload, the error of the first one:
+    /// `ScanError::Symbol` if it does not decode, or its parsing error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use matter_setup_code::SetupPayload;
+    /// use matter_setup_code::render::RenderOptions;
+    ///
+    /// let payload = SetupPayload::parse_str("MT:-24J042C00KA0648G00").unwrap();
+    /// let image = payload.to_qr_image(&RenderOptions::default()).unwrap();
+    /// assert_eq!(SetupPayload::from_qr_image(&image).unwrap(), payload);
+    /// ```
+    pub fn from_qr_image(image: &DynamicImage) -> Result<SetupPayload> {
+        let luma = image.to_luma8();
+        let mut prepared = rqrr::PreparedImage::prepare_from_greyscale(
+            luma.width() as usize,
+            luma.height() as usize,
+            |x, y| luma.get_pixel(x as u32, y as u32).0[0],
+        );
+
+        let mut first_error = None;
+        for grid in prepared.detect_grids() {
+            let parsed = grid
+                .decode()
+                .map_err(|error| ScanError::Symbol(error.to_string()).into())
+                .and_then(|(_, text)| SetupPayload::parse_str(&text));
+            match parsed {
+                Ok(payload) => return Ok(payload),
+                Err(error) => {
+                    first_error.get_or_insert(error);
+                }
+            }
+        }
+        Err(first_error.unwrap_or_else(|| ScanError::NoQrCode.into()))
+    }
+
+    /// Like [`SetupPayload::from_qr_image`], from the contents of an image
+    /// file, PNG or JPEG.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ScanError::Image` if the image cannot be decoded, or the
+    /// errors of [`SetupPayload::from_qr_image`].
+    pub fn from_qr_image_bytes(bytes: &[u8]) -> Result<SetupPayload> {
+        let image =
+            image::load_from_memory(bytes).map_err(|error| ScanError::Image(error.to_string()))?;
+        SetupPayload::from_qr_image(&image)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use image::{ImageFormat, Luma};
+
+    use super::*;
+    use crate::MatterPayloadError;
+    use crate::render::RenderOptions;
+
+    #[test]
+    fn test_from_qr_image() {
+        let payload =
+            SetupPayload::parse_str("MT:-24J042C00KA064IJ3P0-C670NSXK1S3DO1UXSC1O0").unwrap();
+        let image = payload
+            .to_qr_image(&RenderOptions {
+                module_size: 4,
+                ..RenderOptions::default()
+            })
+            .unwrap();
+        let mut png = Vec::new();
+        image
+            .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+            .unwrap();
+        assert_eq!(SetupPayload::from_qr_image_bytes(&png).unwrap(), payload);
+
+        let blank = DynamicImage::ImageLuma8(image::ImageBuffer::from_pixel(64, 64, Luma([255])));
+        assert_eq!(
+            SetupPayload::from_qr_image(&blank).unwrap_err(),
+            MatterPayloadError::Scan(ScanError::NoQrCode)
+        );
+        assert!(matches!(
+            SetupPayload::from_qr_image_bytes(b"not an image").unwrap_err(),
+            MatterPayloadError::Scan(ScanError::Image(_))
+        ));
+    }
+}
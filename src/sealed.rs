@@ -0,0 +1,195 @@
+//! At-rest encryption of the secret part of a provisioning record.
+//!
+//! The passcode (and the SPAKE2+ verifier derived from it, when the factory
+//! computes one) must not sit in clear text in a manufacturing database.
+//! [`seal`] encrypts them with AES-256-GCM into a self-describing blob and
+//! [`unseal`] reverses it, so integrators share one envelope format instead
+//! of each rolling their own.
+//!
+//! # Blob layout
+//!
+//! | Offset | Size   | Content                                                |
+//! |--------|--------|--------------------------------------------------------|
+//! | 0      | 1      | Format version (currently `1`)                         |
+//! | 1      | 12     | Random AES-GCM nonce                                   |
+//! | 13     | n + 20 | Ciphertext of `passcode (u32 LE) ‖ verifier`, plus tag |
+//!
+//! The version byte and the caller's associated data (typically the device
+//! serial number) are authenticated, so a blob copied onto another record
+//! fails to unseal.
+
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+
+use crate::error::{Result, SealError};
+use crate::payload::SetupPayload;
+
+/// Version byte written at the start of every blob.
+const FORMAT_VERSION: u8 = 1;
+/// Size of the AES-GCM nonce in bytes.
+const NONCE_LEN: usize = 12;
+/// Size of the AES-GCM authentication tag in bytes.
+const TAG_LEN: usize = 16;
+/// Size of the encoded passcode in bytes.
+const PASSCODE_LEN: usize = 4;
+
+/// The secret fields recovered from a sealed blob.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Unsealed {
+    /// Setup PIN code
+    pub pincode: u32,
+    /// SPAKE2+ verifier, or empty if none was sealed
+    pub verifier: Vec<u8>,
+}
+
+/// Encrypts the passcode of `payload` and an optional `verifier` under a 256-bit `key`.
+///
+/// `associated_data` is authenticated but not encrypted; the same bytes must
+/// be passed to [`unseal`].
+///
+/// # Errors
+///
+/// Returns `SealError::Encryption` if the cipher fails (e.g. the record is too large).
+pub fn seal(
+    payload: &SetupPayload,
+    verifier: &[u8],
+    key: &[u8; 32],
+    associated_data: &[u8],
+) -> Result<Vec<u8>> {
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key));
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+
+    let mut plaintext = Vec::with_capacity(PASSCODE_LEN + verifier.len());
+    plaintext.extend_from_slice(&payload.pincode.get().to_le_bytes());
+    plaintext.extend_from_slice(verifier);
+
+    let aad = authenticated_data(FORMAT_VERSION, associated_data);
+    let ciphertext = cipher
+        .encrypt(
+            &nonce,
+            Payload {
+                msg: &plaintext,
+                aad: &aad,
+            },
+        )
+        .map_err(|_| SealError::Encryption)?;
+
+    let mut blob = Vec::with_capacity(1 + NONCE_LEN + ciphertext.len());
+    blob.push(FORMAT_VERSION);
+    blob.extend_from_slice(&nonce);
+    blob.extend_from_slice(&ciphertext);
+    Ok(blob)
+}
+
+/// Decrypts a blob produced by [`seal`].
+///
+/// # Errors
+///
+/// Returns an error if the blob is truncated, uses an unknown format
+/// version, or fails authentication (wrong key, wrong associated data or
+/// tampered content).
+pub fn unseal(blob: &[u8], key: &[u8; 32], associated_data: &[u8]) -> Result<Unsealed> {
+    if blob.len() < 1 + NONCE_LEN + TAG_LEN + PASSCODE_LEN {
+        return Err(SealError::Truncated(blob.len()).into());
+    }
+    let version = blob[0];
+    if version != FORMAT_VERSION {
+        return Err(SealError::UnsupportedVersion(version).into());
+    }
+    let (nonce, ciphertext) = blob[1..].split_at(NONCE_LEN);
+
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key));
+    let aad = authenticated_data(version, associated_data);
+    let plaintext = cipher
+        .decrypt(
+            Nonce::from_slice(nonce),
+            Payload {
+                msg: ciphertext,
+                aad: &aad,
+            },
+        )
+        .map_err(|_| SealError::Authentication)?;
+
+    let (pincode, verifier) = plaintext.split_at(PASSCODE_LEN);
+    Ok(Unsealed {
+        pincode: u32::from_le_bytes(pincode.try_into().expect("split at PASSCODE_LEN")),
+        verifier: verifier.to_vec(),
+    })
+}
+
+/// Prefixes the caller's associated data with the format version so it is authenticated too.
+fn authenticated_data(version: u8, associated_data: &[u8]) -> Vec<u8> {
+    let mut aad = Vec::with_capacity(1 + associated_data.len());
+    aad.push(version);
+    aad.extend_from_slice(associated_data);
+    aad
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MatterPayloadError;
+    use crate::payload::Passcode;
+
+    const KEY: [u8; 32] = [0x42; 32];
+
+    fn payload() -> SetupPayload {
+        SetupPayload::new(
+            3840,
+            Passcode::new(20202021).unwrap(),
+            Some(4),
+            None,
+            Some(0xFFF1),
+            Some(0x8000),
+        )
+    }
+
+    #[test]
+    fn test_seal_unseal_roundtrip() {
+        let verifier = [0xAB; 97];
+        let blob = seal(&payload(), &verifier, &KEY, b"SN-0001").unwrap();
+        assert_eq!(blob[0], FORMAT_VERSION);
+        assert_eq!(
+            blob.len(),
+            1 + NONCE_LEN + PASSCODE_LEN + verifier.len() + TAG_LEN
+        );
+
+        let unsealed = unseal(&blob, &KEY, b"SN-0001").unwrap();
+        assert_eq!(unsealed.pincode, 20202021);
+        assert_eq!(unsealed.verifier, verifier);
+
+        // Fresh nonces make two seals of the same record differ.
+        assert_ne!(blob, seal(&payload(), &verifier, &KEY, b"SN-0001").unwrap());
+    }
+
+    #[test]
+    fn test_unseal_rejects_tampering() {
+        let blob = seal(&payload(), &[], &KEY, b"SN-0001").unwrap();
+        let authentication = MatterPayloadError::Seal(SealError::Authentication);
+
+        assert_eq!(
+            unseal(&blob, &[0x43; 32], b"SN-0001").unwrap_err(),
+            authentication
+        );
+        assert_eq!(unseal(&blob, &KEY, b"SN-0002").unwrap_err(), authentication);
+
+        let mut flipped = blob.clone();
+        *flipped.last_mut().unwrap() ^= 1;
+        assert_eq!(
+            unseal(&flipped, &KEY, b"SN-0001").unwrap_err(),
+            authentication
+        );
+
+        let mut future = blob.clone();
+        future[0] = 2;
+        assert_eq!(
+            unseal(&future, &KEY, b"SN-0001").unwrap_err(),
+            MatterPayloadError::Seal(SealError::UnsupportedVersion(2))
+        );
+
+        assert_eq!(
+            unseal(&blob[..20], &KEY, b"SN-0001").unwrap_err(),
+            MatterPayloadError::Seal(SealError::Truncated(20))
+        );
+    }
+}
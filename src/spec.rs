@@ -0,0 +1,328 @@
+//! Matter specification revisions and the payload features they define.
+//!
+//! Tooling pinned to an older revision must reject payloads using features
+//! introduced later (a commissioner built for Matter 1.1 cannot use Wi-Fi
+//! PAF discovery), while up-to-date tooling accepts them. [`SpecVersion`]
+//! captures that choice for strict parsing and generation.
+
+use crate::error::{Result, SpecError};
+use crate::payload::{CommissioningFlow, DiscoveryCapabilities, SetupPayload};
+
+/// A revision of the Matter core specification.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SpecVersion {
+    /// Matter 1.0
+    V1_0,
+    /// Matter 1.1
+    V1_1,
+    /// Matter 1.2
+    V1_2,
+    /// Matter 1.3
+    V1_3,
+    /// Matter 1.4
+    #[default]
+    V1_4,
+}
+
+impl SpecVersion {
+    /// The most recent revision known to this crate.
+    pub const LATEST: SpecVersion = SpecVersion::V1_4;
+
+    /// Returns the discovery capabilities defined by this revision.
+    pub fn discovery_capabilities(self) -> DiscoveryCapabilities {
+        let base = DiscoveryCapabilities::SOFT_AP
+            | DiscoveryCapabilities::BLE
+            | DiscoveryCapabilities::ON_NETWORK;
+        if self >= SpecVersion::V1_4 {
+            base | DiscoveryCapabilities::WIFI_PAF | DiscoveryCapabilities::NFC
+        } else {
+            base
+        }
+    }
+
+    /// Returns the oldest revision defining every capability in `capabilities`,
+    /// or `None` if some of its bits are reserved in all known revisions.
+    pub fn introducing(capabilities: DiscoveryCapabilities) -> Option<SpecVersion> {
+        [
+            SpecVersion::V1_0,
+            SpecVersion::V1_1,
+            SpecVersion::V1_2,
+            SpecVersion::V1_3,
+            SpecVersion::V1_4,
+        ]
+        .into_iter()
+        .find(|version| version.discovery_capabilities().contains(capabilities))
+    }
+}
+
+impl std::fmt::Display for SpecVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let version = match self {
+            SpecVersion::V1_0 => "1.0",
+            SpecVersion::V1_1 => "1.1",
+            SpecVersion::V1_2 => "1.2",
+            SpecVersion::V1_3 => "1.3",
+            SpecVersion::V1_4 => "1.4",
+        };
+        write!(f, "Matter {version}")
+    }
+}
+
+/// A capability a commissioner needs to commission a device from its payload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SpecFeature {
+    /// Discovery of the device's soft access point
+    SoftApDiscovery,
+    /// Discovery over Bluetooth Low Energy
+    BleDiscovery,
+    /// Discovery with DNS-SD on the IP network
+    OnNetworkDiscovery,
+    /// Discovery over Wi-Fi Public Action Frames
+    WiFiPafDiscovery,
+    /// Discovery over NFC
+    NfcDiscovery,
+    /// Looking up the `CommissioningModeInitialStepsHint` in the DCL to tell
+    /// the user how to put the device in commissioning mode
+    UserIntentFlow,
+    /// Looking up the `CommissioningCustomFlowUrl` in the DCL and sending the
+    /// user to the vendor's flow before commissioning
+    CustomFlow,
+}
+
+impl SpecFeature {
+    /// Returns the revision that introduced the feature.
+    pub fn introduced_in(self) -> SpecVersion {
+        match self {
+            SpecFeature::WiFiPafDiscovery | SpecFeature::NfcDiscovery => SpecVersion::V1_4,
+            _ => SpecVersion::V1_0,
+        }
+    }
+}
+
+impl std::fmt::Display for SpecFeature {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let feature = match self {
+            SpecFeature::SoftApDiscovery => "SoftAP discovery",
+            SpecFeature::BleDiscovery => "BLE discovery",
+            SpecFeature::OnNetworkDiscovery => "on-network discovery",
+            SpecFeature::WiFiPafDiscovery => "Wi-Fi PAF discovery",
+            SpecFeature::NfcDiscovery => "NFC discovery",
+            SpecFeature::UserIntentFlow => "user-intent commissioning flow",
+            SpecFeature::CustomFlow => "custom commissioning flow",
+        };
+        f.write_str(feature)
+    }
+}
+
+impl SetupPayload {
+    /// Lists the capabilities a commissioner needs for this payload, so an
+    /// app can tell the user up front that their hub cannot commission it.
+    ///
+    /// A commissioner needs one of the discovery methods listed, not all of
+    /// them, and every other feature. A manual code names no discovery
+    /// method, so the commissioner tries whichever it supports.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use matter_setup_code::SetupPayload;
+    /// use matter_setup_code::spec::{SpecFeature, SpecVersion};
+    ///
+    /// // Wi-Fi PAF discovery.
+    /// let payload = SetupPayload::parse_str("MT:-24J0M3810KA0648G00").unwrap();
+    /// let features = payload.required_spec_features();
+    /// assert_eq!(features, [SpecFeature::WiFiPafDiscovery]);
+    /// assert_eq!(features[0].introduced_in(), SpecVersion::V1_4);
+    /// ```
+    pub fn required_spec_features(&self) -> Vec<SpecFeature> {
+        let mut features = Vec::new();
+        if let Some(discovery) = self.discovery {
+            for (capability, feature) in [
+                (DiscoveryCapabilities::SOFT_AP, SpecFeature::SoftApDiscovery),
+                (DiscoveryCapabilities::BLE, SpecFeature::BleDiscovery),
+                (
+                    DiscoveryCapabilities::ON_NETWORK,
+                    SpecFeature::OnNetworkDiscovery,
+                ),
+                (
+                    DiscoveryCapabilities::WIFI_PAF,
+                    SpecFeature::WiFiPafDiscovery,
+                ),
+                (DiscoveryCapabilities::NFC, SpecFeature::NfcDiscovery),
+            ] {
+                if discovery.contains(capability) {
+                    features.push(feature);
+                }
+            }
+        }
+        match self.flow {
+            CommissioningFlow::UserIntent => features.push(SpecFeature::UserIntentFlow),
+            CommissioningFlow::Custom => features.push(SpecFeature::CustomFlow),
+            _ => {}
+        }
+        features
+    }
+
+    /// Checks that this payload only uses features defined by `version`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SpecError::UnknownPayloadVersion` if the payload format
+    /// version is not 0, the only one any revision defines,
+    /// `SpecError::ReservedDiscoveryBits` if the discovery bitmask has bits
+    /// no revision defines, or `SpecError::UnsupportedDiscovery` if it uses
+    /// capabilities introduced after `version`.
+    pub fn check_spec_version(&self, version: SpecVersion) -> Result<()> {
+        if self.version != 0 {
+            return Err(SpecError::UnknownPayloadVersion(self.version).into());
+        }
+        if let Some(discovery) = self.discovery {
+            if discovery.reserved_bits() != 0 {
+                return Err(SpecError::ReservedDiscoveryBits(discovery.reserved_bits()).into());
+            }
+            let unsupported = discovery.bits() & !version.discovery_capabilities().bits();
+            if unsupported != 0 {
+                return Err(SpecError::UnsupportedDiscovery {
+                    capabilities: DiscoveryCapabilities::from_bits(unsupported),
+                    version,
+                }
+                .into());
+            }
+        }
+        Ok(())
+    }
+
+    /// Parses a payload string like [`SetupPayload::parse_str`], then rejects
+    /// it if it uses features not defined by `version`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the parsing error, or the error from [`SetupPayload::check_spec_version`].
+    pub fn parse_str_for_spec(payload_str: &str, version: SpecVersion) -> Result<Self> {
+        let payload = SetupPayload::parse_str(payload_str)?;
+        payload.check_spec_version(version)?;
+        Ok(payload)
+    }
+
+    /// Generates the QR code string like [`SetupPayload::to_qr_code_str`],
+    /// refusing features not defined by `version`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error from [`SetupPayload::check_spec_version`] or from encoding.
+    pub fn to_qr_code_str_for_spec(&self, version: SpecVersion) -> Result<String> {
+        self.check_spec_version(version)?;
+        self.to_qr_code_str()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MatterPayloadError;
+    use crate::payload::Passcode;
+
+    #[test]
+    fn test_discovery_by_version() {
+        assert_eq!(
+            SpecVersion::introducing(DiscoveryCapabilities::BLE),
+            Some(SpecVersion::V1_0)
+        );
+        assert_eq!(
+            SpecVersion::introducing(DiscoveryCapabilities::BLE | DiscoveryCapabilities::WIFI_PAF),
+            Some(SpecVersion::V1_4)
+        );
+        assert_eq!(
+            SpecVersion::introducing(DiscoveryCapabilities::from_bits(0x80)),
+            None
+        );
+        assert_eq!(SpecVersion::default(), SpecVersion::LATEST);
+        assert_eq!(SpecVersion::V1_3.to_string(), "Matter 1.3");
+    }
+
+    #[test]
+    fn test_required_spec_features() {
+        let payload = SetupPayload::parse_str("MT:-24J042C00KA0648G00").unwrap();
+        assert_eq!(
+            payload.required_spec_features(),
+            [SpecFeature::BleDiscovery]
+        );
+
+        let mut payload = SetupPayload::parse_str("34970112332").unwrap();
+        assert!(payload.required_spec_features().is_empty());
+        payload.discovery = Some(DiscoveryCapabilities::SOFT_AP | DiscoveryCapabilities::NFC);
+        payload.flow = CommissioningFlow::Custom;
+        let features = payload.required_spec_features();
+        assert_eq!(
+            features,
+            [
+                SpecFeature::SoftApDiscovery,
+                SpecFeature::NfcDiscovery,
+                SpecFeature::CustomFlow
+            ]
+        );
+        assert_eq!(
+            features.iter().map(|f| f.introduced_in()).max(),
+            Some(SpecVersion::V1_4)
+        );
+        assert_eq!(features[2].to_string(), "custom commissioning flow");
+    }
+
+    #[test]
+    fn test_strict_parse_by_version() {
+        // Wi-Fi PAF discovery, only defined from Matter 1.4.
+        let qr = "MT:-24J0M3810KA0648G00";
+        assert!(SetupPayload::parse_str_for_spec(qr, SpecVersion::V1_4).is_ok());
+        assert_eq!(
+            SetupPayload::parse_str_for_spec(qr, SpecVersion::V1_1).unwrap_err(),
+            MatterPayloadError::Spec(SpecError::UnsupportedDiscovery {
+                capabilities: DiscoveryCapabilities::WIFI_PAF,
+                version: SpecVersion::V1_1,
+            })
+        );
+
+        // Reserved bit 7 is rejected whatever the version.
+        assert_eq!(
+            SetupPayload::parse_str_for_spec("MT:-24J088GJ0KA0648G00", SpecVersion::LATEST)
+                .unwrap_err(),
+            MatterPayloadError::Spec(SpecError::ReservedDiscoveryBits(0x80))
+        );
+
+        // Unknown payload versions parse leniently but fail the strict check.
+        let qr = "MT:.24J042C00KA0648G00";
+        assert_eq!(SetupPayload::parse_str(qr).unwrap().version, 1);
+        assert_eq!(
+            SetupPayload::parse_str_for_spec(qr, SpecVersion::LATEST).unwrap_err(),
+            MatterPayloadError::Spec(SpecError::UnknownPayloadVersion(1))
+        );
+
+        // Manual codes carry no discovery capabilities.
+        assert!(SetupPayload::parse_str_for_spec("34970112332", SpecVersion::V1_0).is_ok());
+    }
+
+    #[test]
+    fn test_generation_by_version() {
+        let payload = SetupPayload::new(
+            3840,
+            Passcode::new(20202021).unwrap(),
+            Some(0x02),
+            None,
+            Some(0xFFF1),
+            Some(0x8001),
+        );
+        assert_eq!(
+            payload.to_qr_code_str_for_spec(SpecVersion::V1_0).unwrap(),
+            "MT:-24J042C00KA0648G00"
+        );
+
+        let payload = SetupPayload::new(
+            3840,
+            Passcode::new(20202021).unwrap(),
+            Some(0x10),
+            None,
+            Some(0xFFF1),
+            Some(0x8001),
+        );
+        assert!(payload.to_qr_code_str_for_spec(SpecVersion::V1_3).is_err());
+    }
+}
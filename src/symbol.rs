@@ -0,0 +1,255 @@
+//! Sizing the QR code symbol a setup payload needs.
+//!
+//! A QR code string only uses characters of the QR alphanumeric set, "MT:"
+//! and base38 alike, so it is encoded as a single alphanumeric segment. Its
+//! length, which grows with the optional data, and the error correction
+//! level fix the smallest symbol version that holds it. Label designers
+//! need that version up front to reserve space and pick a print size.
+//!
+//! [`SetupPayload::recommended_qr_symbol`] picks the error correction level
+//! as well: at least [`ErrorCorrection::Medium`], the level the renderers
+//! default to, and more whenever it fits in the same symbol, since the
+//! extra redundancy then costs no space.
+
+use crate::base38;
+use crate::error::Result;
+use crate::payload::SetupPayload;
+
+/// The largest QR code symbol version.
+pub const MAX_VERSION: u8 = 40;
+
+/// Length of the "MT:" prefix of a QR code string.
+const PREFIX_LEN: usize = 3;
+
+/// Length of the fixed fields of a QR code payload, in bytes.
+const FIXED_FIELDS_LEN: usize = 11;
+
+/// Data capacity of each symbol version, in bits, for the Low, Medium,
+/// Quartile and High error correction levels (ISO/IEC 18004, table 7).
+const DATA_CAPACITY_BITS: [[u16; 4]; MAX_VERSION as usize] = [
+    [152, 128, 104, 72],
+    [272, 224, 176, 128],
+    [440, 352, 272, 208],
+    [640, 512, 384, 288],
+    [864, 688, 496, 368],
+    [1088, 864, 608, 480],
+    [1248, 992, 704, 528],
+    [1552, 1232, 880, 688],
+    [1856, 1456, 1056, 800],
+    [2192, 1728, 1232, 976],
+    [2592, 2032, 1440, 1120],
+    [2960, 2320, 1648, 1264],
+    [3424, 2672, 1952, 1440],
+    [3688, 2920, 2088, 1576],
+    [4184, 3320, 2360, 1784],
+    [4712, 3624, 2600, 2024],
+    [5176, 4056, 2936, 2264],
+    [5768, 4504, 3176, 2504],
+    [6360, 5016, 3560, 2728],
+    [6888, 5352, 3880, 3080],
+    [7456, 5712, 4096, 3248],
+    [8048, 6256, 4544, 3536],
+    [8752, 6880, 4912, 3712],
+    [9392, 7312, 5312, 4112],
+    [10208, 8000, 5744, 4304],
+    [10960, 8496, 6032, 4768],
+    [11744, 9024, 6464, 5024],
+    [12248, 9544, 6968, 5288],
+    [13048, 10136, 7288, 5608],
+    [13880, 10984, 7880, 5960],
+    [14744, 11640, 8264, 6344],
+    [15640, 12328, 8920, 6760],
+    [16568, 13048, 9368, 7208],
+    [17528, 13800, 9848, 7688],
+    [18448, 14496, 10288, 7888],
+    [19472, 15312, 10832, 8432],
+    [20528, 15936, 11408, 8768],
+    [21616, 16816, 12016, 9136],
+    [22496, 17728, 12656, 9776],
+    [23648, 18672, 13328, 10208],
+];
+
+/// QR code error correction level, from the least to the most redundant.
+///
+/// More redundancy lets scanners read damaged or dirty labels, at the cost
+/// of a denser symbol.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ErrorCorrection {
+    /// Recovers about 7% of the symbol
+    Low,
+    /// Recovers about 15% of the symbol
+    #[default]
+    Medium,
+    /// Recovers about 25% of the symbol
+    Quartile,
+    /// Recovers about 30% of the symbol
+    High,
+}
+
+impl ErrorCorrection {
+    /// Every level, from the least to the most redundant.
+    pub const ALL: [Self; 4] = [Self::Low, Self::Medium, Self::Quartile, Self::High];
+}
+
+/// A symbol version and error correction level for a QR code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SymbolRecommendation {
+    /// Symbol version, from 1 to 40
+    pub version: u8,
+    /// Error correction level
+    pub error_correction: ErrorCorrection,
+}
+
+impl SymbolRecommendation {
+    /// Width and height of the symbol, in modules, quiet zone excluded.
+    pub fn modules(&self) -> u32 {
+        17 + 4 * u32::from(self.version)
+    }
+}
+
+/// Length of an alphanumeric segment of `chars` characters in a symbol of
+/// `version`, header included, in bits.
+fn alphanumeric_bits(chars: usize, version: u8) -> usize {
+    let count_bits = match version {
+        1..=9 => 9,
+        10..=26 => 11,
+        _ => 13,
+    };
+    4 + count_bits + 11 * (chars / 2) + 6 * (chars % 2)
+}
+
+/// The smallest symbol version holding a QR code string of `chars`
+/// characters, "MT:" included, or `None` if even version 40 is too small.
+///
+/// # Example
+///
+/// ```
+/// use matter_setup_code::symbol::{ErrorCorrection, min_version};
+///
+/// assert_eq!(min_version(22, ErrorCorrection::Low), Some(1));
+/// assert_eq!(min_version(22, ErrorCorrection::High), Some(3));
+/// ```
+pub fn min_version(chars: usize, error_correction: ErrorCorrection) -> Option<u8> {
+    (1..=MAX_VERSION).find(|&version| {
+        let capacity = DATA_CAPACITY_BITS[usize::from(version) - 1][error_correction as usize];
+        alphanumeric_bits(chars, version) <= usize::from(capacity)
+    })
+}
+
+/// The smallest symbol version holding the QR code of a payload with
+/// `optional_data_len` bytes of optional data, for sizing labels before the
+/// optional data is known.
+///
+/// # Example
+///
+/// ```
+/// use matter_setup_code::symbol::{ErrorCorrection, min_version_for_optional_data};
+///
+/// assert_eq!(min_version_for_optional_data(0, ErrorCorrection::Medium), Some(2));
+/// assert_eq!(min_version_for_optional_data(20, ErrorCorrection::Medium), Some(3));
+/// ```
+pub fn min_version_for_optional_data(
+    optional_data_len: usize,
+    error_correction: ErrorCorrection,
+) -> Option<u8> {
+    let chars = PREFIX_LEN + base38::encoded_len(FIXED_FIELDS_LEN + optional_data_len);
+    min_version(chars, error_correction)
+}
+
+/// The level to print a QR code of `chars` characters with, and the
+/// symbol version it needs.
+fn recommend(chars: usize) -> Option<SymbolRecommendation> {
+    let version = min_version(chars, ErrorCorrection::Medium)?;
+    let error_correction = ErrorCorrection::ALL
+        .into_iter()
+        .rev()
+        .find(|&level| min_version(chars, level) == Some(version))?;
+    Some(SymbolRecommendation {
+        version,
+        error_correction,
+    })
+}
+
+impl SetupPayload {
+    /// The smallest symbol version holding the payload's QR code at
+    /// `error_correction`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`SetupPayload::to_qr_code_str`].
+    pub fn min_qr_symbol_version(&self, error_correction: ErrorCorrection) -> Result<u8> {
+        let chars = PREFIX_LEN + self.qr_code_base38_len()?;
+        // Payloads within the QR code size limit fit in far less than version 40.
+        Ok(min_version(chars, error_correction).unwrap_or(MAX_VERSION))
+    }
+
+    /// The symbol version and error correction level to print the payload's
+    /// QR code with: the smallest version holding it at medium error
+    /// correction, and the highest level fitting in that version.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`SetupPayload::to_qr_code_str`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use matter_setup_code::SetupPayload;
+    /// use matter_setup_code::symbol::{ErrorCorrection, SymbolRecommendation};
+    ///
+    /// let payload = SetupPayload::parse_str("MT:-24J042C00KA0648G00").unwrap();
+    /// let recommendation = payload.recommended_qr_symbol().unwrap();
+    /// assert_eq!(
+    ///     recommendation,
+    ///     SymbolRecommendation { version: 2, error_correction: ErrorCorrection::Quartile }
+    /// );
+    /// assert_eq!(recommendation.modules(), 25);
+    /// ```
+    pub fn recommended_qr_symbol(&self) -> Result<SymbolRecommendation> {
+        let chars = PREFIX_LEN + self.qr_code_base38_len()?;
+        Ok(recommend(chars).unwrap_or(SymbolRecommendation {
+            version: MAX_VERSION,
+            error_correction: ErrorCorrection::Medium,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_min_version() {
+        // Alphanumeric capacities of versions 1, 10 and 40 (ISO/IEC 18004, table 7).
+        assert_eq!(min_version(25, ErrorCorrection::Low), Some(1));
+        assert_eq!(min_version(26, ErrorCorrection::Low), Some(2));
+        assert_eq!(min_version(174, ErrorCorrection::High), Some(10));
+        assert_eq!(min_version(175, ErrorCorrection::High), Some(11));
+        assert_eq!(min_version(4296, ErrorCorrection::Low), Some(40));
+        assert_eq!(min_version(4297, ErrorCorrection::Low), None);
+
+        let sizes = ErrorCorrection::ALL.map(|level| min_version(22, level));
+        assert_eq!(sizes, [Some(1), Some(2), Some(2), Some(3)]);
+    }
+
+    #[test]
+    fn test_recommended_qr_symbol() {
+        let payload =
+            SetupPayload::parse_str("MT:-24J042C00KA064IJ3P0-C670NSXK1S3DO1UXSC1O0").unwrap();
+        assert_eq!(
+            payload.min_qr_symbol_version(ErrorCorrection::Low).unwrap(),
+            2
+        );
+        assert_eq!(
+            payload.recommended_qr_symbol().unwrap(),
+            SymbolRecommendation {
+                version: 3,
+                error_correction: ErrorCorrection::Quartile,
+            }
+        );
+        assert_eq!(
+            min_version_for_optional_data(14, ErrorCorrection::Medium),
+            Some(3)
+        );
+    }
+}
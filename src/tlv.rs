@@ -0,0 +1,529 @@
+//! Encoding and decoding of the Matter TLV format.
+//!
+//! QR codes carry their optional data (serial numbers, vendor elements) as
+//! Matter TLV. This module implements the subset of the format that data
+//! uses: signed and unsigned integers, UTF-8 strings and structures, with
+//! anonymous or context-specific tags. It works on plain byte slices and
+//! does not depend on the payload types.
+//!
+//! Integers and string lengths are written with the smallest width holding
+//! them, as the Matter SDK does; decoding accepts every width.
+
+use crate::error::{Result, TlvError};
+
+/// Element type of a structure.
+const STRUCTURE: u8 = 0x15;
+/// Element type closing a container.
+const END_OF_CONTAINER: u8 = 0x18;
+/// Tag control bits of an anonymous tag.
+const ANONYMOUS_TAG: u8 = 0x00;
+/// Tag control bits of a context-specific tag.
+const CONTEXT_TAG: u8 = 0x20;
+/// Deepest container nesting the decoder follows, which bounds its
+/// recursion on hostile input. QR optional data nests at most one level.
+const MAX_NESTING: usize = 32;
+
+/// The tag of an element.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum Tag {
+    /// No tag, e.g. for a top-level container
+    Anonymous,
+    /// A tag meaningful within the enclosing structure
+    Context(u8),
+}
+
+/// The value of an element.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum TlvValue {
+    /// A signed integer
+    Signed(i64),
+    /// An unsigned integer
+    Unsigned(u64),
+    /// A UTF-8 string
+    Utf8(String),
+    /// A structure of tagged members
+    Structure(Vec<Element>),
+}
+
+/// A tagged value.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Element {
+    /// The element's tag
+    pub tag: Tag,
+    /// The element's value
+    pub value: TlvValue,
+}
+
+/// Decodes a sequence of elements filling `bytes` entirely.
+///
+/// # Errors
+///
+/// Returns a `TlvError` if `bytes` is truncated, uses element types or
+/// tags outside the supported subset, or holds an invalid string.
+///
+/// # Example
+///
+/// ```
+/// use matter_setup_code::tlv::{self, Element, Tag, TlvValue};
+///
+/// let elements = tlv::decode(&[0x24, 0x01, 0x2A]).unwrap();
+/// assert_eq!(
+///     elements,
+///     [Element { tag: Tag::Context(1), value: TlvValue::Unsigned(42) }]
+/// );
+/// ```
+pub fn decode(bytes: &[u8]) -> Result<Vec<Element>> {
+    let mut decoder = Decoder::new(bytes);
+    let mut elements = Vec::new();
+    while !decoder.is_at_end() {
+        let start = decoder.offset();
+        match decoder.read_element()? {
+            Some(element) => elements.push(element),
+            None => return Err(TlvError::UnexpectedEndOfContainer { offset: start }.into()),
+        }
+    }
+    Ok(elements)
+}
+
+/// Encodes a sequence of elements.
+///
+/// # Example
+///
+/// ```
+/// use matter_setup_code::tlv::{self, Element, Tag, TlvValue};
+///
+/// let bytes = tlv::encode(&[Element {
+///     tag: Tag::Anonymous,
+///     value: TlvValue::Utf8("SN1".to_string()),
+/// }]);
+/// assert_eq!(bytes, [0x0C, 0x03, b'S', b'N', b'1']);
+/// ```
+pub fn encode(elements: &[Element]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    for element in elements {
+        write_element(&mut bytes, element);
+    }
+    bytes
+}
+
+fn write_element(bytes: &mut Vec<u8>, element: &Element) {
+    let tag_control = match element.tag {
+        Tag::Anonymous => ANONYMOUS_TAG,
+        Tag::Context(_) => CONTEXT_TAG,
+    };
+    let mut head = |element_type: u8| {
+        bytes.push(tag_control | element_type);
+        if let Tag::Context(tag) = element.tag {
+            bytes.push(tag);
+        }
+    };
+    match &element.value {
+        TlvValue::Signed(value) => {
+            let width = signed_width(*value);
+            head(width.trailing_zeros() as u8);
+            bytes.extend_from_slice(&value.to_le_bytes()[..width]);
+        }
+        TlvValue::Unsigned(value) => {
+            let width = unsigned_width(*value);
+            head(0x04 + width.trailing_zeros() as u8);
+            bytes.extend_from_slice(&value.to_le_bytes()[..width]);
+        }
+        TlvValue::Utf8(string) => {
+            let length = string.len() as u64;
+            let width = unsigned_width(length);
+            head(0x0C + width.trailing_zeros() as u8);
+            bytes.extend_from_slice(&length.to_le_bytes()[..width]);
+            bytes.extend_from_slice(string.as_bytes());
+        }
+        TlvValue::Structure(members) => {
+            head(STRUCTURE);
+            for member in members {
+                write_element(bytes, member);
+            }
+            bytes.push(END_OF_CONTAINER);
+        }
+    }
+}
+
+/// Smallest of 1, 2, 4 or 8 bytes holding `value`.
+fn unsigned_width(value: u64) -> usize {
+    match value {
+        0..=0xFF => 1,
+        0x100..=0xFFFF => 2,
+        0x1_0000..=0xFFFF_FFFF => 4,
+        _ => 8,
+    }
+}
+
+/// Smallest of 1, 2, 4 or 8 bytes holding `value` in two's complement.
+fn signed_width(value: i64) -> usize {
+    if i8::try_from(value).is_ok() {
+        1
+    } else if i16::try_from(value).is_ok() {
+        2
+    } else if i32::try_from(value).is_ok() {
+        4
+    } else {
+        8
+    }
+}
+
+/// A cursor decoding elements one at a time, for callers that need the
+/// byte offset of each element or want to walk a structure's members
+/// themselves.
+///
+/// # Example
+///
+/// ```
+/// use matter_setup_code::tlv::{Decoder, Tag};
+///
+/// let mut decoder = Decoder::new(&[0x15, 0x24, 0x01, 0x2A, 0x18]);
+/// assert_eq!(decoder.enter_structure().unwrap(), Some(Tag::Anonymous));
+/// assert_eq!(decoder.offset(), 1);
+/// assert!(decoder.read_element().unwrap().is_some());
+/// assert!(decoder.read_element().unwrap().is_none());
+/// assert!(decoder.is_at_end());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Decoder<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+    depth: usize,
+}
+
+impl<'a> Decoder<'a> {
+    /// Starts decoding at the first byte of `bytes`.
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Decoder {
+            bytes,
+            offset: 0,
+            depth: 0,
+        }
+    }
+
+    /// Offset of the next byte to decode.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Whether every byte has been decoded.
+    pub fn is_at_end(&self) -> bool {
+        self.offset == self.bytes.len()
+    }
+
+    /// Consumes the head of a structure and returns its tag, leaving the
+    /// members to be read with [`Decoder::read_element`]. Returns `None`
+    /// without consuming anything if the next element is not a structure.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `TlvError` if the head is truncated or uses an unsupported
+    /// tag form.
+    pub fn enter_structure(&mut self) -> Result<Option<Tag>> {
+        let start = self.offset;
+        let control = self.read_u8()?;
+        if control & 0x1F != STRUCTURE {
+            self.offset = start;
+            return Ok(None);
+        }
+        self.read_tag(control, start).map(Some)
+    }
+
+    /// Reads one element, structures included, or returns `None` after
+    /// consuming the end of the enclosing container.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `TlvError` if the element is truncated, uses an element
+    /// type or tag outside the supported subset, or holds an invalid string.
+    pub fn read_element(&mut self) -> Result<Option<Element>> {
+        let start = self.offset;
+        let control = self.read_u8()?;
+        if control == END_OF_CONTAINER {
+            return Ok(None);
+        }
+        let tag = self.read_tag(control, start)?;
+        let value = match control & 0x1F {
+            element_type @ 0x00..=0x03 => {
+                let width = 1 << element_type;
+                let raw = self.read_le(width)?;
+                // Sign-extend from the element's width.
+                let shift = 64 - 8 * width;
+                TlvValue::Signed(((raw << shift) as i64) >> shift)
+            }
+            element_type @ 0x04..=0x07 => {
+                TlvValue::Unsigned(self.read_le(1 << (element_type - 0x04))?)
+            }
+            element_type @ 0x0C..=0x0F => {
+                let length = self.read_le(1 << (element_type - 0x0C))?;
+                let bytes = self.read_bytes(length)?;
+                let string = std::str::from_utf8(bytes)
+                    .map_err(|_| TlvError::InvalidUtf8 { offset: start })?;
+                TlvValue::Utf8(string.to_string())
+            }
+            STRUCTURE => {
+                self.enter_container(start)?;
+                let mut members = Vec::new();
+                while let Some(member) = self.read_element()? {
+                    members.push(member);
+                }
+                self.depth -= 1;
+                TlvValue::Structure(members)
+            }
+            element_type => {
+                return Err(TlvError::UnsupportedElementType {
+                    offset: start,
+                    element_type,
+                }
+                .into());
+            }
+        };
+        Ok(Some(Element { tag, value }))
+    }
+
+    /// Skips one element of any type and tag form the TLV format defines,
+    /// including those [`Decoder::read_element`] does not support, and
+    /// returns its encoding, or returns `None` after consuming the end of
+    /// the enclosing container.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `TlvError` if the element is truncated or uses a reserved
+    /// element type.
+    pub fn skip_element(&mut self) -> Result<Option<&'a [u8]>> {
+        let start = self.offset;
+        let control = self.read_u8()?;
+        if control == END_OF_CONTAINER {
+            return Ok(None);
+        }
+        // Tag lengths by tag control: anonymous, context, common profile
+        // (2 and 4 bytes), implicit profile (2 and 4 bytes) and fully
+        // qualified (6 and 8 bytes).
+        const TAG_LENGTHS: [u64; 8] = [0, 1, 2, 4, 2, 4, 6, 8];
+        self.read_bytes(TAG_LENGTHS[usize::from(control >> 5)])?;
+        match control & 0x1F {
+            element_type @ 0x00..=0x07 => {
+                self.read_bytes(1 << (element_type & 0x03))?;
+            }
+            // Booleans and null carry no value.
+            0x08 | 0x09 | 0x14 => {}
+            0x0A => {
+                self.read_bytes(4)?;
+            }
+            0x0B => {
+                self.read_bytes(8)?;
+            }
+            // UTF-8 and byte strings, prefixed by their length.
+            element_type @ 0x0C..=0x13 => {
+                let length = self.read_le(1 << (element_type & 0x03))?;
+                self.read_bytes(length)?;
+            }
+            // Structures, arrays and lists.
+            0x15..=0x17 => {
+                self.enter_container(start)?;
+                while self.skip_element()?.is_some() {}
+                self.depth -= 1;
+            }
+            element_type => {
+                return Err(TlvError::UnsupportedElementType {
+                    offset: start,
+                    element_type,
+                }
+                .into());
+            }
+        }
+        Ok(Some(&self.bytes[start..self.offset]))
+    }
+
+    /// Steps into the container whose control byte was read at `start`.
+    fn enter_container(&mut self, start: usize) -> Result<()> {
+        if self.depth == MAX_NESTING {
+            return Err(TlvError::TooDeeplyNested { offset: start }.into());
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
+    /// Reads the tag announced by the control byte read at `start`.
+    fn read_tag(&mut self, control: u8, start: usize) -> Result<Tag> {
+        match control & 0xE0 {
+            ANONYMOUS_TAG => Ok(Tag::Anonymous),
+            CONTEXT_TAG => Ok(Tag::Context(self.read_u8()?)),
+            _ => Err(TlvError::UnsupportedTag { offset: start }.into()),
+        }
+    }
+
+    fn read_bytes(&mut self, length: u64) -> Result<&'a [u8]> {
+        let end = usize::try_from(length)
+            .ok()
+            .and_then(|length| self.offset.checked_add(length))
+            .filter(|&end| end <= self.bytes.len())
+            .ok_or(TlvError::Truncated {
+                offset: self.offset,
+            })?;
+        let bytes = &self.bytes[self.offset..end];
+        self.offset = end;
+        Ok(bytes)
+    }
+
+    fn read_u8(&mut self) -> Result<u8> {
+        Ok(self.read_bytes(1)?[0])
+    }
+
+    /// Reads a little-endian unsigned integer of `width` bytes.
+    fn read_le(&mut self, width: u32) -> Result<u64> {
+        let bytes = self.read_bytes(u64::from(width))?;
+        Ok(bytes
+            .iter()
+            .rev()
+            .fold(0, |acc, &byte| (acc << 8) | u64::from(byte)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MatterPayloadError;
+
+    fn context(tag: u8, value: TlvValue) -> Element {
+        Element {
+            tag: Tag::Context(tag),
+            value,
+        }
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let bytes = [
+            0x15, // anonymous structure
+            0x2C, 0x00, 0x03, b'S', b'N', b'1', // context tag 0, "SN1"
+            0x24, 0x80, 0x2A, // context tag 0x80, unsigned 42
+            0x20, 0x81, 0xFE, // context tag 0x81, signed -2
+            0x18, // end of container
+        ];
+        let elements = [Element {
+            tag: Tag::Anonymous,
+            value: TlvValue::Structure(vec![
+                context(0x00, TlvValue::Utf8("SN1".to_string())),
+                context(0x80, TlvValue::Unsigned(42)),
+                context(0x81, TlvValue::Signed(-2)),
+            ]),
+        }];
+        assert_eq!(decode(&bytes).unwrap(), elements);
+        assert_eq!(encode(&elements), bytes);
+    }
+
+    #[test]
+    fn test_integer_widths() {
+        for value in [0, 0xFF, 0x100, 0xFFFF_FFFF, u64::MAX] {
+            let elements = [context(1, TlvValue::Unsigned(value))];
+            assert_eq!(decode(&encode(&elements)).unwrap(), elements);
+        }
+        for value in [-1, i8::MIN.into(), -129, i32::MAX.into(), i64::MIN] {
+            let elements = [context(1, TlvValue::Signed(value))];
+            assert_eq!(decode(&encode(&elements)).unwrap(), elements);
+        }
+        assert_eq!(
+            encode(&[context(0x82, TlvValue::Unsigned(0x1234))]),
+            [0x25, 0x82, 0x34, 0x12]
+        );
+        // Wider encodings than needed still decode.
+        assert_eq!(
+            decode(&[0x21, 0x01, 0xFE, 0xFF]).unwrap(),
+            [context(1, TlvValue::Signed(-2))]
+        );
+    }
+
+    #[test]
+    fn test_skip_element() {
+        let bytes = [
+            0x08, // anonymous false
+            0xCA, 0x01, 0x00, 0x02, 0x00, 0x03, 0x00, 0x00, 0x00, 0x80, 0x3F, // 1.0f32
+            0x30, 0x05, 0x02, 0xAB, 0xCD, // context tag 5, byte string
+            0x36, 0x06, 0x04, 0x01, 0x18, // context tag 6, array [1]
+        ];
+        let mut decoder = Decoder::new(&bytes);
+        assert_eq!(decoder.skip_element().unwrap(), Some(&bytes[..1]));
+        assert_eq!(decoder.skip_element().unwrap(), Some(&bytes[1..12]));
+        assert_eq!(decoder.skip_element().unwrap(), Some(&bytes[12..17]));
+        assert_eq!(decoder.skip_element().unwrap(), Some(&bytes[17..]));
+        assert!(decoder.is_at_end());
+
+        assert_eq!(
+            Decoder::new(&[0x19]).skip_element().unwrap_err(),
+            MatterPayloadError::Tlv(TlvError::UnsupportedElementType {
+                offset: 0,
+                element_type: 0x19,
+            })
+        );
+        assert_eq!(
+            Decoder::new(&[0x30, 0x05, 0x02, 0xAB])
+                .skip_element()
+                .unwrap_err(),
+            MatterPayloadError::Tlv(TlvError::Truncated { offset: 3 })
+        );
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        assert_eq!(
+            decode(&[0x2C, 0x00, 0x03, b'S']).unwrap_err(),
+            MatterPayloadError::Tlv(TlvError::Truncated { offset: 3 })
+        );
+        assert_eq!(
+            decode(&[0x08]).unwrap_err(),
+            MatterPayloadError::Tlv(TlvError::UnsupportedElementType {
+                offset: 0,
+                element_type: 0x08,
+            })
+        );
+        assert_eq!(
+            decode(&[0x44, 0x00, 0x00, 0x01]).unwrap_err(),
+            MatterPayloadError::Tlv(TlvError::UnsupportedTag { offset: 0 })
+        );
+        assert_eq!(
+            decode(&[0x0C, 0x01, 0xFF]).unwrap_err(),
+            MatterPayloadError::Tlv(TlvError::InvalidUtf8 { offset: 0 })
+        );
+        assert_eq!(
+            decode(&[0x15, 0x18, 0x18]).unwrap_err(),
+            MatterPayloadError::Tlv(TlvError::UnexpectedEndOfContainer { offset: 2 })
+        );
+        assert_eq!(
+            decode(&[0x15, 0x24, 0x01, 0x2A]).unwrap_err(),
+            MatterPayloadError::Tlv(TlvError::Truncated { offset: 4 })
+        );
+    }
+
+    #[test]
+    fn test_nesting_limit() {
+        let nested = |depth| [vec![0x15u8; depth], vec![0x18; depth]].concat();
+        assert!(decode(&nested(MAX_NESTING)).is_ok());
+        assert!(Decoder::new(&nested(MAX_NESTING)).skip_element().is_ok());
+
+        let too_deep = TlvError::TooDeeplyNested {
+            offset: MAX_NESTING,
+        };
+        assert_eq!(
+            decode(&nested(MAX_NESTING + 1)).unwrap_err(),
+            MatterPayloadError::Tlv(too_deep.clone())
+        );
+        assert_eq!(
+            Decoder::new(&nested(MAX_NESTING + 1))
+                .skip_element()
+                .unwrap_err(),
+            MatterPayloadError::Tlv(too_deep)
+        );
+    }
+}
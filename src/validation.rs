@@ -0,0 +1,337 @@
+//! Whole-payload validation reporting every problem at once.
+//!
+//! Generation fails on the first problem it meets, which makes for a poor
+//! form UI: fix one field, submit, learn about the next. [`SetupPayload::validate`]
+//! checks every field up front and lists all the specification violations,
+//! including a forbidden passcode kept by a lenient parse.
+//!
+//! How strict to be depends on who is asking. A [`ValidationPolicy`] selects
+//! the checks: [`ValidationPolicy::DEVICE_MAKER`] enforces everything a
+//! printed code needs, [`ValidationPolicy::COMMISSIONER`] accepts whatever a
+//! scanner may produce.
+
+use thiserror::Error;
+
+use crate::error::{MatterPayloadError, PayloadError, Result, SpecError};
+use crate::payload::{CommissioningFlow, ParseOptions, SetupPayload, VendorId};
+
+/// A specification violation found by [`SetupPayload::validate`].
+///
+/// Most violations are the errors parsing or generation reports for the same
+/// problem; only what neither checks has a variant of its own.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ValidationIssue {
+    #[error(transparent)]
+    Payload(#[from] PayloadError),
+
+    #[error(transparent)]
+    Spec(#[from] SpecError),
+
+    #[error("vendor ID {0} is reserved for testing")]
+    TestVendorId(VendorId),
+}
+
+/// Which checks [`SetupPayload::validate_with_policy`] applies.
+///
+/// Every field relaxes or adds one check relative to [`ValidationPolicy::SPEC`],
+/// the default, which is what [`SetupPayload::validate`] applies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ValidationPolicy {
+    /// Accept reserved commissioning flow values
+    pub allow_reserved_flow: bool,
+    /// Accept a passcode the specification forbids
+    pub allow_invalid_passcode: bool,
+    /// Accept reserved discovery capability bits
+    pub allow_reserved_discovery_bits: bool,
+    /// Accept unknown payload format versions
+    pub allow_unknown_version: bool,
+    /// Accept a custom flow without an assigned VID and PID
+    pub allow_custom_flow_without_vid_pid: bool,
+    /// Require every field a QR code carries (VID, PID, discovery, long discriminator)
+    pub require_qr_fields: bool,
+    /// Reject the CSA test vendor IDs
+    pub reject_test_vendor_ids: bool,
+    /// Accept QR codes whose four padding bits are not all zero
+    pub allow_nonzero_padding: bool,
+}
+
+impl ValidationPolicy {
+    /// What the specification requires of any payload.
+    pub const SPEC: Self = ValidationPolicy {
+        allow_reserved_flow: false,
+        allow_invalid_passcode: false,
+        allow_reserved_discovery_bits: false,
+        allow_unknown_version: false,
+        allow_custom_flow_without_vid_pid: false,
+        require_qr_fields: false,
+        reject_test_vendor_ids: false,
+        allow_nonzero_padding: false,
+    };
+
+    /// For codes about to be printed on production devices: the specification
+    /// checks, plus every QR code field and no test vendor ID.
+    pub const DEVICE_MAKER: Self = ValidationPolicy {
+        require_qr_fields: true,
+        reject_test_vendor_ids: true,
+        ..Self::SPEC
+    };
+
+    /// For codes read from devices in the field: anything that decodes is
+    /// accepted, reserved values included, so newer devices still commission.
+    pub const COMMISSIONER: Self = ValidationPolicy {
+        allow_reserved_flow: true,
+        allow_invalid_passcode: true,
+        allow_reserved_discovery_bits: true,
+        allow_unknown_version: true,
+        allow_custom_flow_without_vid_pid: true,
+        allow_nonzero_padding: true,
+        ..Self::SPEC
+    };
+}
+
+impl Default for ValidationPolicy {
+    fn default() -> Self {
+        Self::SPEC
+    }
+}
+
+impl SetupPayload {
+    /// Checks every field against the specification and returns all
+    /// violations found, in field order; an empty list means the payload is valid.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use matter_setup_code::{CommissioningFlow, SetupPayload};
+    ///
+    /// let mut payload = SetupPayload::parse_str("MT:-24J042C00KA0648G00").unwrap();
+    /// assert!(payload.validate().is_empty());
+    ///
+    /// payload.flow = CommissioningFlow::Custom;
+    /// payload.vid = None;
+    /// payload.version = 2;
+    /// let issues: Vec<String> = payload.validate().iter().map(|i| i.to_string()).collect();
+    /// assert_eq!(
+    ///     issues,
+    ///     [
+    ///         "custom commissioning flow requires an assigned vendor ID and product ID",
+    ///         "payload format version 2 is not defined by any known revision",
+    ///     ]
+    /// );
+    /// ```
+    pub fn validate(&self) -> Vec<ValidationIssue> {
+        self.validate_with_policy(&ValidationPolicy::SPEC)
+    }
+
+    /// Like [`SetupPayload::validate`], applying the checks selected by `policy`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use matter_setup_code::SetupPayload;
+    /// use matter_setup_code::validation::{ValidationIssue, ValidationPolicy};
+    ///
+    /// // The SDK example code uses a test vendor ID.
+    /// let payload = SetupPayload::parse_str("MT:-24J042C00KA0648G00").unwrap();
+    /// assert!(payload.validate_with_policy(&ValidationPolicy::COMMISSIONER).is_empty());
+    /// assert_eq!(
+    ///     payload.validate_with_policy(&ValidationPolicy::DEVICE_MAKER),
+    ///     [ValidationIssue::TestVendorId(payload.vid.unwrap())]
+    /// );
+    /// ```
+    pub fn validate_with_policy(&self, policy: &ValidationPolicy) -> Vec<ValidationIssue> {
+        let mut issues = Vec::new();
+
+        if let Err(MatterPayloadError::Payload(error)) = self.discriminator.checked() {
+            issues.push(error.into());
+        }
+        if policy.require_qr_fields && self.discriminator.long().is_none() {
+            issues.push(PayloadError::MissingLongDiscriminator.into());
+        }
+
+        if !self.pincode.is_valid() && !policy.allow_invalid_passcode {
+            issues.push(PayloadError::InvalidPasscode(self.pincode.get()).into());
+        }
+
+        match self.flow {
+            CommissioningFlow::Reserved(bits) if !policy.allow_reserved_flow => {
+                issues.push(PayloadError::ReservedCommissioningFlow(bits).into())
+            }
+            CommissioningFlow::Custom if !policy.allow_custom_flow_without_vid_pid => {
+                let assigned = self.vid.is_some_and(|vid| !vid.is_unassigned())
+                    && self.pid.is_some_and(|pid| !pid.is_unassigned());
+                if !assigned {
+                    issues.push(PayloadError::CustomFlowWithoutVidPid.into());
+                }
+            }
+            _ => {}
+        }
+
+        match self.discovery {
+            Some(discovery)
+                if discovery.reserved_bits() != 0 && !policy.allow_reserved_discovery_bits =>
+            {
+                issues.push(SpecError::ReservedDiscoveryBits(discovery.reserved_bits()).into());
+            }
+            None if policy.require_qr_fields => {
+                issues.push(PayloadError::MissingDiscovery.into());
+            }
+            _ => {}
+        }
+
+        match self.vid {
+            Some(vid) if vid.is_test() && policy.reject_test_vendor_ids => {
+                issues.push(ValidationIssue::TestVendorId(vid));
+            }
+            None if policy.require_qr_fields => {
+                issues.push(PayloadError::MissingVendorId.into());
+            }
+            _ => {}
+        }
+        if policy.require_qr_fields && self.pid.is_none() {
+            issues.push(PayloadError::MissingProductId.into());
+        }
+
+        if self.version != 0 && !policy.allow_unknown_version {
+            issues.push(SpecError::UnknownPayloadVersion(self.version).into());
+        }
+
+        issues
+    }
+
+    /// Parses a payload string like [`SetupPayload::parse_str`], then rejects
+    /// it if it breaks a check selected by `policy`.
+    ///
+    /// Nonzero padding bits in a QR code, which the parsed payload does not
+    /// keep, are checked while parsing. Trailing bytes that are not valid
+    /// optional data fail parsing under any policy.
+    ///
+    /// # Errors
+    ///
+    /// Returns the parsing error, `PayloadError::NonZeroPadding` unless
+    /// `policy` allows it, or `MatterPayloadError::Validation` with the
+    /// first issue [`SetupPayload::validate_with_policy`] finds.
+    pub fn parse_str_with_policy(payload_str: &str, policy: &ValidationPolicy) -> Result<Self> {
+        let options = ParseOptions {
+            allow_invalid_passcode: true,
+            require_zero_padding: !policy.allow_nonzero_padding,
+            ..ParseOptions::default()
+        };
+        let payload = SetupPayload::parse_str_with(payload_str, &options)?;
+        match payload.validate_with_policy(policy).into_iter().next() {
+            Some(issue) => Err(issue.into()),
+            None => Ok(payload),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::payload::{DiscoveryCapabilities, Discriminator};
+
+    #[test]
+    fn test_validate_reports_every_issue() {
+        let mut payload = SetupPayload::parse_str("MT:-24J042C00KA0648G00").unwrap();
+        assert!(payload.validate().is_empty());
+
+        payload.discriminator = Discriminator::Long(0x1000);
+        payload.flow = CommissioningFlow::Reserved(3);
+        payload.discovery = Some(DiscoveryCapabilities::from_bits(0x82));
+        assert_eq!(
+            payload.validate(),
+            [
+                ValidationIssue::Payload(PayloadError::DiscriminatorTooLarge(0x1000)),
+                ValidationIssue::Payload(PayloadError::ReservedCommissioningFlow(3)),
+                ValidationIssue::Spec(SpecError::ReservedDiscoveryBits(0x80)),
+            ]
+        );
+
+        // A manual code without VID/PID is valid as is.
+        let manual = SetupPayload::parse_str("34970112332").unwrap();
+        assert!(manual.validate().is_empty());
+    }
+
+    #[test]
+    fn test_invalid_passcode() {
+        // The SDK example code with passcode 12345678.
+        let qr = "MT:-24J042C004QG46Y900";
+        let payload = SetupPayload::parse_str_with(qr, &ParseOptions::LENIENT).unwrap();
+        assert_eq!(
+            payload.validate(),
+            [ValidationIssue::Payload(PayloadError::InvalidPasscode(
+                12345678
+            ))]
+        );
+        for policy in [ValidationPolicy::SPEC, ValidationPolicy::DEVICE_MAKER] {
+            assert_eq!(
+                SetupPayload::parse_str_with_policy(qr, &policy).unwrap_err(),
+                MatterPayloadError::Validation(ValidationIssue::Payload(
+                    PayloadError::InvalidPasscode(12345678)
+                ))
+            );
+        }
+        assert_eq!(
+            SetupPayload::parse_str_with_policy(qr, &ValidationPolicy::COMMISSIONER).unwrap(),
+            payload
+        );
+    }
+
+    #[test]
+    fn test_policies() {
+        // Reserved discovery bit 7 and flow value 3.
+        for qr in ["MT:-24J088GJ0KA0648G00", "MT:-24J0UBG00KA0648G00"] {
+            assert!(
+                SetupPayload::parse_str_with_policy(qr, &ValidationPolicy::COMMISSIONER).is_ok()
+            );
+            assert!(SetupPayload::parse_str_with_policy(qr, &ValidationPolicy::SPEC).is_err());
+        }
+
+        let manual = SetupPayload::parse_str("34970112332").unwrap();
+        assert_eq!(
+            manual.validate_with_policy(&ValidationPolicy::DEVICE_MAKER),
+            [
+                ValidationIssue::Payload(PayloadError::MissingLongDiscriminator),
+                ValidationIssue::Payload(PayloadError::MissingDiscovery),
+                ValidationIssue::Payload(PayloadError::MissingVendorId),
+                ValidationIssue::Payload(PayloadError::MissingProductId),
+            ]
+        );
+        assert_eq!(
+            SetupPayload::parse_str_with_policy("34970112332", &ValidationPolicy::DEVICE_MAKER)
+                .unwrap_err(),
+            MatterPayloadError::Validation(ValidationIssue::Payload(
+                PayloadError::MissingLongDiscriminator
+            ))
+        );
+    }
+
+    #[test]
+    fn test_padding_and_trailing_data() {
+        // The SDK example code with padding 0b0101.
+        let padded = "MT:-24J042C00KA0646NE0";
+        assert!(SetupPayload::parse_str(padded).is_ok());
+        assert_eq!(
+            SetupPayload::parse_str_with_policy(padded, &ValidationPolicy::SPEC).unwrap_err(),
+            MatterPayloadError::Payload(PayloadError::NonZeroPadding(5))
+        );
+        assert!(
+            SetupPayload::parse_str_with_policy(padded, &ValidationPolicy::COMMISSIONER).is_ok()
+        );
+
+        // The SDK example code followed by a stray zero byte.
+        let trailing = "MT:-24J042C00KA0648G000";
+        assert_eq!(
+            SetupPayload::parse_str_with_policy(trailing, &ValidationPolicy::COMMISSIONER)
+                .unwrap_err(),
+            MatterPayloadError::Payload(PayloadError::InvalidOptionalData {
+                offset: 0,
+                reason: "expected an anonymous structure",
+            })
+        );
+        assert_eq!(
+            SetupPayload::parse_str("MT:-24J042C00KA064S2").unwrap_err(),
+            MatterPayloadError::Payload(PayloadError::InvalidPackedLength(10))
+        );
+    }
+}
@@ -63,7 +63,7 @@ fn string_to_digits(s: &str) -> std::result::Result<Vec<u8>, VerhoeffError> {
 /// # Example
 ///
 /// ```
-/// use matter_setup_payload::verhoeff::calculate_checksum;
+/// use matter_setup_code::verhoeff::calculate_checksum;
 ///
 /// let checksum = calculate_checksum("12345").unwrap();
 /// assert_eq!(checksum, 1);
@@ -115,6 +115,111 @@ pub fn validate(input: &str) -> Result<bool> {
     Ok(c == 0)
 }
 
+/// One digit's contribution to a Verhoeff validation, as recorded by [`trace`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TraceStep {
+    /// Position of the digit counted from the right, starting at 0 for the check digit
+    pub position: usize,
+    /// The input digit
+    pub digit: u8,
+    /// Row of the permutation table used for this position (`position % 8`)
+    pub permutation: usize,
+    /// The digit after permutation, `p(position % 8, digit)`
+    pub permuted: u8,
+    /// The accumulator before this step
+    pub check_before: u8,
+    /// The accumulator after this step, `d(check_before, permuted)`
+    pub check_after: u8,
+}
+
+/// The full record of a Verhoeff validation, for explaining a result to a human.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Trace {
+    /// One step per input digit, in processing order (right to left)
+    pub steps: Vec<TraceStep>,
+    /// Whether the final accumulator is 0, i.e. the input is valid
+    pub valid: bool,
+    /// The check digit the input should end with, if it has at least one payload digit
+    pub expected_check_digit: Option<u8>,
+}
+
+/// Validates a string of digits like [`validate`], recording every step of the computation.
+///
+/// # Errors
+///
+/// Returns an `Err` if the input string is empty or contains non-digit characters.
+///
+/// # Example
+///
+/// ```
+/// use matter_setup_code::verhoeff::trace;
+///
+/// let trace = trace("2364").unwrap();
+/// assert!(!trace.valid);
+/// assert_eq!(trace.expected_check_digit, Some(3));
+/// println!("{trace}");
+/// ```
+pub fn trace(input: &str) -> Result<Trace> {
+    let digits = string_to_digits(input)?;
+    let mut c = 0u8;
+    let mut steps = Vec::with_capacity(digits.len());
+
+    for (i, &digit) in digits.iter().rev().enumerate() {
+        let permutation = i % 8;
+        let permuted = P_TABLE[permutation][digit as usize];
+        let check_after = D_TABLE[c as usize][permuted as usize];
+        steps.push(TraceStep {
+            position: i,
+            digit,
+            permutation,
+            permuted,
+            check_before: c,
+            check_after,
+        });
+        c = check_after;
+    }
+
+    let expected_check_digit = match input.len() {
+        1 => None,
+        len => Some(calculate_checksum(&input[..len - 1])?),
+    };
+
+    Ok(Trace {
+        steps,
+        valid: c == 0,
+        expected_check_digit,
+    })
+}
+
+impl std::fmt::Display for Trace {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for step in &self.steps {
+            writeln!(
+                f,
+                "pos {:>2}: digit {} -> p({}, {}) = {} -> c = d({}, {}) = {}",
+                step.position,
+                step.digit,
+                step.permutation,
+                step.digit,
+                step.permuted,
+                step.check_before,
+                step.permuted,
+                step.check_after,
+            )?;
+        }
+        let result = self.steps.last().map_or(0, |step| step.check_after);
+        if self.valid {
+            write!(f, "final c = 0: checksum valid")
+        } else {
+            write!(f, "final c = {result}: checksum invalid")?;
+            match self.expected_check_digit {
+                Some(expected) => write!(f, " (expected check digit {expected})"),
+                None => Ok(()),
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -147,4 +252,64 @@ mod tests {
         let expected = MatterPayloadError::Verhoeff(VerhoeffError::EmptyInput);
         assert_eq!(result.unwrap_err(), expected);
     }
+
+    #[test]
+    fn test_trace() {
+        for input in ["2363", "2364", "123451", "123450", "11237442363", "7"] {
+            assert_eq!(trace(input).unwrap().valid, validate(input).unwrap());
+        }
+
+        let t = trace("2363").unwrap();
+        assert_eq!(t.steps.len(), 4);
+        // The check digit sits at position 0 and goes through the identity permutation.
+        assert_eq!(
+            t.steps[0],
+            TraceStep {
+                position: 0,
+                digit: 3,
+                permutation: 0,
+                permuted: 3,
+                check_before: 0,
+                check_after: 3,
+            }
+        );
+        assert_eq!(t.steps[3].check_after, 0);
+        assert_eq!(t.expected_check_digit, Some(3));
+        assert!(t.to_string().ends_with("checksum valid"));
+
+        let t = trace("2364").unwrap();
+        assert!(t.to_string().ends_with("(expected check digit 3)"));
+        assert_eq!(trace("7").unwrap().expected_check_digit, None);
+    }
+}
+
+// Proof harnesses for the Kani model checker, run with `cargo kani`.
+#[cfg(kani)]
+mod verification {
+    use super::*;
+
+    /// Number of payload digits in a short manual pairing code.
+    const LEN: usize = 10;
+
+    fn to_string(digits: &[u8]) -> String {
+        digits.iter().map(|&d| char::from(b'0' + d)).collect()
+    }
+
+    /// Appending the calculated check digit always yields a valid string, and
+    /// substituting any single digit of it is always detected.
+    #[kani::proof]
+    #[kani::unwind(13)]
+    fn check_single_substitution_detected() {
+        let mut digits: [u8; LEN + 1] = kani::any();
+        for d in &mut digits[..LEN] {
+            kani::assume(*d < 10);
+        }
+        digits[LEN] = calculate_checksum(&to_string(&digits[..LEN])).unwrap();
+        assert!(validate(&to_string(&digits)).unwrap());
+
+        let position: usize = kani::any_where(|&p| p <= LEN);
+        let replacement: u8 = kani::any_where(|&r| r < 10 && r != digits[position]);
+        digits[position] = replacement;
+        assert!(!validate(&to_string(&digits)).unwrap());
+    }
 }